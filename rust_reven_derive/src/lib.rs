@@ -0,0 +1,272 @@
+//! Macro `#[derive(CallbackData)]` de la crate `rust_reven`.
+//!
+//! Utilisez-la via la feature `derive` de `rust_reven`, qui la ré-exporte à côté du trait.
+//! La macro est écrite sur `proc_macro` seul, sans dépendance.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Implémente `rust_reven::CallbackData` pour une structure.
+///
+/// `label` renvoie le nom de la structure, ou la valeur de l'attribut
+/// `#[callback_data(label = "...")]`.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::CallbackData;
+///
+/// #[derive(CallbackData)]
+/// #[callback_data(label = "sensor")]
+/// struct SensorReading {
+///     id: u16,
+///     value: f32,
+/// }
+///
+/// let reading = SensorReading { id: 1, value: 0.5 };
+/// assert_eq!(reading.label(), "sensor");
+/// ```
+///
+/// La macro refuse les énumérations et les unions :
+///
+/// ```compile_fail
+/// use rust_reven::CallbackData;
+///
+/// #[derive(CallbackData)]
+/// enum Event {
+///     Start,
+///     Stop,
+/// }
+/// ```
+///
+/// ainsi que les attributs inconnus :
+///
+/// ```compile_fail
+/// use rust_reven::CallbackData;
+///
+/// #[derive(CallbackData)]
+/// #[callback_data(name = "sensor")]
+/// struct SensorReading;
+/// ```
+#[proc_macro_derive(CallbackData, attributes(callback_data))]
+pub fn derive_callback_data(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(message) => format!("::core::compile_error!({:?});", message)
+            .parse()
+            .expect("appel de compile_error! valide"),
+    }
+}
+
+/// Structure analysée : son nom, ses paramètres génériques et son étiquette.
+struct Item {
+    name: String,          // Nom de la structure.
+    params: Vec<String>,   // Paramètres génériques, avec leurs bornes.
+    args: Vec<String>,     // Paramètres génériques, sans leurs bornes.
+    where_clause: String,  // Clause `where`, éventuellement vide.
+    label: Option<String>, // Littéral chaîne de `#[callback_data(label = ...)]`.
+}
+
+/// Génère l'implémentation du trait, ou un message d'erreur.
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let item = parse(input)?;
+    let label = item.label.unwrap_or_else(|| format!("{:?}", item.name));
+    let (params, args) = if item.params.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (
+            format!("<{}>", item.params.join(", ")),
+            format!("<{}>", item.args.join(", ")),
+        )
+    };
+    let output = format!(
+        "impl{params} ::rust_reven::CallbackData for {name}{args} {where_clause} {{
+            fn label(&self) -> &str {{
+                {label}
+            }}
+        }}",
+        name = item.name,
+        where_clause = item.where_clause,
+    );
+    output
+        .parse()
+        .map_err(|_| "implémentation générée invalide".to_string())
+}
+
+/// Analyse les attributs, le nom, les génériques et la clause `where` d'une structure.
+fn parse(input: TokenStream) -> Result<Item, String> {
+    let mut tokens = input.into_iter().peekable();
+    let mut label = None;
+
+    // Attributs et visibilité, jusqu'au mot-clé de l'item.
+    let keyword = loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = tokens.next() {
+                    if let Some(value) = parse_attribute(group.stream())? {
+                        label = Some(value);
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ident)) => match ident.to_string().as_str() {
+                "pub" => {
+                    if let Some(TokenTree::Group(group)) = tokens.peek() {
+                        if group.delimiter() == Delimiter::Parenthesis {
+                            tokens.next();
+                        }
+                    }
+                }
+                keyword => break keyword.to_string(),
+            },
+            _ => return Err("item inattendu pour `#[derive(CallbackData)]`".to_string()),
+        }
+    };
+    if keyword != "struct" {
+        return Err(format!(
+            "`#[derive(CallbackData)]` ne s'applique qu'aux structures, pas à `{}`",
+            keyword
+        ));
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("nom de structure attendu".to_string()),
+    };
+
+    // Paramètres génériques, entre `<` et le `>` correspondant ; le `>` d'une flèche `->`
+    // ne ferme rien.
+    let mut generics = Vec::new();
+    if matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') {
+        tokens.next();
+        let mut depth = 1;
+        let mut after_minus = false;
+        for token in tokens.by_ref() {
+            if let TokenTree::Punct(punct) = &token {
+                match punct.as_char() {
+                    '<' => depth += 1,
+                    '>' if !after_minus => depth -= 1,
+                    _ => {}
+                }
+            }
+            after_minus = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '-');
+            if depth == 0 {
+                break;
+            }
+            generics.push(token);
+        }
+    }
+    let (params, args) = split_generics(generics);
+
+    // Clause `where`, avant ou après les champs d'une structure tuple.
+    let mut where_clause = String::new();
+    let mut in_where = false;
+    for token in tokens {
+        match &token {
+            TokenTree::Ident(ident) if ident.to_string() == "where" => in_where = true,
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => break,
+            TokenTree::Group(group) if !in_where && group.delimiter() == Delimiter::Parenthesis => {
+                continue
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ';' => break,
+            _ => {}
+        }
+        if in_where {
+            where_clause.push_str(&token.to_string());
+            where_clause.push(' ');
+        }
+    }
+
+    Ok(Item {
+        name,
+        params,
+        args,
+        where_clause,
+        label,
+    })
+}
+
+/// Renvoie la valeur de `label` si l'attribut est `callback_data(label = "...")`.
+fn parse_attribute(attribute: TokenStream) -> Result<Option<String>, String> {
+    let mut tokens = attribute.into_iter();
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "callback_data" => {}
+        _ => return Ok(None),
+    }
+    let arguments = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream().into_iter().collect::<Vec<_>>()
+        }
+        _ => return Err("syntaxe attendue : `#[callback_data(label = \"...\")]`".to_string()),
+    };
+    match arguments.as_slice() {
+        [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(value)]
+            if key.to_string() == "label" && eq.as_char() == '=' =>
+        {
+            let value = value.to_string();
+            if value.starts_with('"') {
+                Ok(Some(value))
+            } else {
+                Err("`label` doit être une chaîne de caractères".to_string())
+            }
+        }
+        [TokenTree::Ident(key), ..] => Err(format!(
+            "attribut `{}` inconnu pour `#[callback_data]`, seul `label` est accepté",
+            key
+        )),
+        _ => Err("syntaxe attendue : `#[callback_data(label = \"...\")]`".to_string()),
+    }
+}
+
+/// Sépare les paramètres génériques et renvoie chacun avec ses bornes, puis sans ; la valeur
+/// par défaut (`T = u8`) n'est pas admise dans l'en-tête d'un `impl`, elle est retirée.
+fn split_generics(generics: Vec<TokenTree>) -> (Vec<String>, Vec<String>) {
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    let mut current: Vec<TokenTree> = Vec::new();
+    let mut default_at = None; // Position du `=` de la valeur par défaut du paramètre courant.
+    let mut depth = 0;
+    let mut after_minus = false;
+    for token in
+        generics
+            .into_iter()
+            .chain(std::iter::once(TokenTree::Punct(proc_macro::Punct::new(
+                ',',
+                proc_macro::Spacing::Alone,
+            ))))
+    {
+        let arrow = after_minus;
+        after_minus = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '-');
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if !arrow => depth -= 1,
+                '=' if depth == 0 && default_at.is_none() => default_at = Some(current.len()),
+                ',' if depth == 0 => {
+                    if !current.is_empty() {
+                        current.truncate(default_at.take().unwrap_or(current.len()));
+                        args.push(generic_arg(&current));
+                        params.push(current.drain(..).collect::<TokenStream>().to_string());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        current.push(token);
+    }
+    (params, args)
+}
+
+/// Renvoie l'argument correspondant à un paramètre générique : `'a`, `T` ou `N` pour `const N`.
+fn generic_arg(param: &[TokenTree]) -> String {
+    match param {
+        [TokenTree::Punct(quote), TokenTree::Ident(lifetime), ..] if quote.as_char() == '\'' => {
+            format!("'{}", lifetime)
+        }
+        [TokenTree::Ident(keyword), TokenTree::Ident(name), ..]
+            if keyword.to_string() == "const" =>
+        {
+            name.to_string()
+        }
+        [first, ..] => first.to_string(),
+        [] => String::new(),
+    }
+}
@@ -0,0 +1,95 @@
+//! Tests de `#[derive(CallbackData)]` sur des structures de différentes formes.
+
+use rust_reven::{CallbackData, CallbackRegistry};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+#[derive(CallbackData)]
+#[callback_data(label = "sensor")]
+struct SensorReading {
+    id: u16,
+    value: f32,
+}
+
+#[derive(CallbackData)]
+pub(crate) struct Heartbeat(u64);
+
+#[derive(CallbackData)]
+struct Unit;
+
+#[derive(CallbackData)]
+struct Tagged<'a, T: Clone, const N: usize>
+where
+    T: Default,
+{
+    tag: &'a str,
+    values: [T; N],
+}
+
+#[derive(CallbackData)]
+struct Marker<T>(PhantomData<T>)
+where
+    T: Copy;
+
+#[derive(CallbackData)]
+struct Defaulted<T = u8, const N: usize = 4> {
+    values: [T; N],
+}
+
+#[derive(CallbackData)]
+struct WithFn<F: Fn() -> u8, G = fn() -> u8> {
+    make: F,
+    fallback: G,
+}
+
+/// Teste que l'attribut `label` remplace le nom de la structure.
+#[test]
+fn test_label_attribute() {
+    let reading = SensorReading { id: 1, value: 2.0 };
+    assert_eq!(reading.label(), "sensor");
+    assert_eq!((reading.id, reading.value), (1, 2.0));
+}
+
+/// Teste que l'étiquette par défaut est le nom de la structure, quelle que soit sa forme.
+#[test]
+fn test_default_label_is_struct_name() {
+    assert_eq!(Heartbeat(3).label(), "Heartbeat");
+    assert_eq!(Unit.label(), "Unit");
+    let tagged = Tagged {
+        tag: "t",
+        values: [0u8; 2],
+    };
+    assert_eq!(tagged.label(), "Tagged");
+    assert_eq!((tagged.tag, tagged.values.len()), ("t", 2));
+    assert_eq!(Marker::<u8>(PhantomData).label(), "Marker");
+}
+
+/// Teste qu'une structure dérivée est transmise de bout en bout par un registre.
+#[test]
+fn test_derived_payload_is_dispatched() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_in_cb = Rc::clone(&seen);
+    let mut registry: CallbackRegistry<Heartbeat> = CallbackRegistry::new();
+    registry.add_callback(move |beat: &Heartbeat| seen_in_cb.borrow_mut().push(beat.0));
+    registry.dispatch_value(&Heartbeat(42));
+    assert_eq!(*seen.borrow(), vec![42]);
+}
+
+/// Teste les paramètres avec valeur par défaut et les bornes qui contiennent une flèche `->`.
+#[test]
+fn test_defaults_and_fn_bounds() {
+    let defaulted: Defaulted = Defaulted { values: [1; 4] };
+    assert_eq!(defaulted.label(), "Defaulted");
+    assert_eq!(defaulted.values.len(), 4);
+
+    fn seven() -> u8 {
+        7
+    }
+    let with_fn: WithFn<_> = WithFn {
+        make: || 3,
+        fallback: seven as fn() -> u8,
+    };
+    assert_eq!(with_fn.label(), "WithFn");
+    assert_eq!(((with_fn.make)(), (with_fn.fallback)()), (3, 7));
+}
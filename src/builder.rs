@@ -0,0 +1,173 @@
+//! Construction progressive d'un registre de callbacks.
+
+use crate::callback::{Callback, CallbackData};
+use crate::data::DataSlot;
+use crate::error::{BuildError, RegistryFull};
+use crate::registry::CallbackRegistry;
+
+/// Builder pour [`CallbackRegistry`], obtenu via [`CallbackRegistry::builder`].
+///
+/// Le builder pré-alloue le vecteur de callbacks et conserve l'ordre d'enregistrement.
+/// Le paramètre `D` suit celui de [`CallbackRegistry`] : un slice par défaut, un tableau `[u8; N]`
+/// pour un [`FixedRegistry`](crate::FixedRegistry).
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackPayload, CallbackRegistry, CallbackHost};
+///
+/// let registry = CallbackRegistry::builder()
+///     .with_capacity(4)
+///     .with_data(&[1, 2, 3])
+///     .add_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)))
+///     .build()
+///     .expect("les données sont fournies");
+/// registry.do_something();
+/// ```
+pub struct CallbackRegistryBuilder<'a, T: CallbackData + ?Sized, D: ?Sized = [u8], R = ()> {
+    capacity: usize,                // Nombre de callbacks à pré-allouer.
+    max_callbacks: Option<usize>,   // Nombre maximal de callbacks du registre.
+    callbacks: Vec<Callback<T, R>>, // Callbacks enregistrés dans l'ordre.
+    data: Option<DataSlot<'a, D>>,  // Données du registre, obligatoires.
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistryBuilder<'a, T, D, R> {
+    /// Crée un builder vide, sans données ni callbacks.
+    pub fn new() -> Self {
+        CallbackRegistryBuilder {
+            capacity: 0,
+            max_callbacks: None,
+            callbacks: Vec::new(),
+            data: None,
+        }
+    }
+
+    /// Pré-alloue la place pour au moins `capacity` callbacks.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Limite le registre à `limit` callbacks, voir [`CallbackRegistry::try_set_callback`].
+    pub fn with_max_callbacks(mut self, limit: usize) -> Self {
+        self.max_callbacks = Some(limit);
+        self
+    }
+
+    /// Définit les données transmises aux callbacks.
+    pub fn with_data(mut self, data: &'a D) -> Self {
+        self.data = Some(DataSlot::Borrowed(data));
+        self
+    }
+
+    /// Définit des données possédées par le registre, par exemple un `Vec<u8>`.
+    pub fn with_owned_data(mut self, data: impl Into<Box<D>>) -> Self {
+        self.data = Some(DataSlot::Owned(data.into()));
+        self
+    }
+
+    /// Ajoute un callback, qui sera appelé après ceux déjà ajoutés.
+    pub fn add_callback(mut self, cb: Callback<T, R>) -> Self {
+        self.callbacks.push(cb);
+        self
+    }
+
+    /// Construit le registre.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`BuildError::MissingData`] si `with_data` n'a pas été appelé, et
+    /// [`BuildError::TooManyCallbacks`] si plus de callbacks ont été ajoutés que la limite.
+    pub fn build(self) -> Result<CallbackRegistry<'a, T, D, R>, BuildError> {
+        let data = self.data.ok_or(BuildError::MissingData)?;
+        if let Some(limit) = self.max_callbacks {
+            if self.callbacks.len() > limit {
+                return Err(BuildError::TooManyCallbacks(RegistryFull {
+                    count: self.callbacks.len(),
+                    limit,
+                }));
+            }
+        }
+        let mut registry = CallbackRegistry::from_slot(data);
+        registry.max_callbacks = self.max_callbacks;
+        registry
+            .callbacks
+            .reserve_exact(self.capacity.max(self.callbacks.len()));
+        registry.extend(self.callbacks);
+        Ok(registry)
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> Default for CallbackRegistryBuilder<'a, T, D, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste que la construction sans données renvoie une erreur explicite.
+    #[test]
+    fn test_build_without_data_fails() {
+        let result = CallbackRegistry::<CallbackPayload>::builder()
+            .with_capacity(2)
+            .build();
+        assert_eq!(result.err(), Some(BuildError::MissingData));
+    }
+
+    /// Teste que la capacité demandée est pré-allouée.
+    #[test]
+    fn test_build_preallocates_capacity() {
+        let registry = CallbackRegistry::<CallbackPayload>::builder()
+            .with_capacity(16)
+            .with_data(&[1, 2, 3])
+            .build()
+            .unwrap();
+
+        assert!(registry.callbacks.capacity() >= 16);
+        assert!(registry.callbacks.is_empty());
+    }
+
+    /// Teste que les callbacks sont appelés dans leur ordre d'ajout.
+    #[test]
+    fn test_build_preserves_registration_order() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let mut builder = CallbackRegistry::builder().with_data(&[1, 2, 3]);
+        for index in 0..3 {
+            let order = Rc::clone(&order);
+            builder = builder.add_callback(Callback::new(move |_data: &CallbackPayload| {
+                order.borrow_mut().push(index)
+            }));
+        }
+
+        let registry = builder.build().unwrap();
+        registry.do_something();
+
+        assert_eq!(*order.borrow(), vec![0, 1, 2]);
+    }
+
+    /// Teste qu'ajouter plus de callbacks que la limite fait échouer la construction.
+    #[test]
+    fn test_build_with_too_many_callbacks_fails() {
+        let result = CallbackRegistry::builder()
+            .with_max_callbacks(1)
+            .with_data(&[1, 2, 3])
+            .add_callback(Callback::new(|_data: &CallbackPayload| {}))
+            .add_callback(Callback::new(|_data: &CallbackPayload| {}))
+            .build();
+
+        assert_eq!(
+            result.err(),
+            Some(BuildError::TooManyCallbacks(RegistryFull {
+                count: 2,
+                limit: 1
+            }))
+        );
+    }
+}
@@ -0,0 +1,900 @@
+//! Définition des callbacks et du trait marqueur des données qu'ils reçoivent.
+
+mod writer;
+
+pub use self::writer::{WriteFormat, WriterSink};
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+/// Définition d'un trait vide nommé `CallbackData`. Les traits peuvent définir des comportements communs que divers types peuvent implémenter.
+///
+/// Le trait est déjà implémenté pour les types courants : bytes (`[u8]`, `Vec<u8>`, `[u8; N]`),
+/// texte (`str`, `String`), types primitifs, références, `Option` et tuples jusqu'à 6 éléments.
+/// Pour ses propres types, voir [`callback_data!`](crate::callback_data) ou, avec la feature
+/// `derive`, `#[derive(CallbackData)]`.
+pub trait CallbackData {
+    /// Renvoie une étiquette lisible du type des données, par défaut le chemin du type.
+    fn label(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Implémente [`CallbackData`] pour chacun des types donnés, par exemple des structures
+/// transmises à [`CallbackRegistry::dispatch_value`](crate::CallbackRegistry::dispatch_value).
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::callback_data;
+///
+/// struct SensorReading {
+///     id: u16,
+///     value: f32,
+/// }
+/// struct Alarm;
+/// callback_data!(SensorReading, Alarm);
+/// ```
+#[macro_export]
+macro_rules! callback_data {
+    ($($ty:ty),+ $(,)?) => {
+        $(impl $crate::CallbackData for $ty {})+
+    };
+}
+
+callback_data!([u8], Vec<u8>, str, String);
+callback_data!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+callback_data!(f32, f64, bool, char, ());
+
+impl<const N: usize> CallbackData for [u8; N] {}
+
+impl<T: CallbackData + ?Sized> CallbackData for &T {}
+
+impl<T: CallbackData> CallbackData for Option<T> {}
+
+/// Implémente `CallbackData` pour les tuples dont chaque élément implémente `CallbackData`.
+macro_rules! tuple_callback_data {
+    ($($name:ident),+) => {
+        impl<$($name: CallbackData),+> CallbackData for ($($name,)+) {}
+    };
+}
+
+tuple_callback_data!(A);
+tuple_callback_data!(A, B);
+tuple_callback_data!(A, B, C);
+tuple_callback_data!(A, B, C, D);
+tuple_callback_data!(A, B, C, D, E);
+tuple_callback_data!(A, B, C, D, E, F);
+
+/// Générique qui permet de gérer un callback.
+///
+/// `Callback` est une structure qui encapsule une fonction (ou closure) qui sera appelée avec une référence à une donnée de type `T`.
+///
+/// # Type Parameters
+///
+/// - `T`: Le type des données de callback. `T` doit implémenter `CallbackData` et peut être non dimensionné,
+///   comme [`CallbackPayload`](crate::CallbackPayload).
+/// - `R`: Le type renvoyé par le callback, `()` par défaut. Voir
+///   [`CallbackRegistry::dispatch_collect`](crate::CallbackRegistry::dispatch_collect) pour récupérer les valeurs.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackPayload};
+///
+/// let callback = Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data));
+/// callback.invoke(CallbackPayload::new(&[1, 2, 3]));
+///
+/// // Les callbacks de priorité plus élevée sont appelés après les autres.
+/// let cleanup = Callback::with_priority(10, |_data: &CallbackPayload| println!("Nettoyage"));
+/// assert_eq!(cleanup.priority(), 10);
+/// ```
+pub struct Callback<T: CallbackData + ?Sized, R = ()> {
+    kind: CallbackKind<T, R>, // La fonction appelée, pointeur de fonction ou closure dans une boîte.
+    priority: i32, // Ordre d'appel : les priorités les plus basses sont appelées en premier.
+    label: Option<Cow<'static, str>>, // Nom facultatif, affiché par `Debug`.
+}
+
+/// Fonction encapsulée par un [`Callback`].
+enum CallbackKind<T: ?Sized, R> {
+    FnPtr(fn(&T) -> R),           // Pointeur de fonction, appelé sans allocation.
+    Boxed(Box<dyn Fn(&T) -> R>),  // Closure placée dans une boîte allouée sur le tas.
+    Shared(Arc<dyn Fn(&T) -> R>), // Closure partagée avec d'autres callbacks, voir `SharedCallback`.
+}
+
+/// Callback clonable à moindre coût, pour enregistrer la même closure dans plusieurs registres.
+///
+/// Les clones partagent la closure via un `Arc` ; chaque clone se passe directement à `set_callback`,
+/// ou se convertit en [`Callback`] par `into()`.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, SharedCallback};
+///
+/// let shared = SharedCallback::new(|data: &CallbackPayload| println!("Data: {:?}", data));
+/// let mut first = CallbackRegistry::with_data(&[1u8][..]);
+/// let mut second = CallbackRegistry::with_data(&[2u8][..]);
+/// first.set_callback(shared.clone());
+/// second.set_callback(shared);
+/// first.do_something();
+/// second.do_something();
+/// ```
+pub struct SharedCallback<T: CallbackData + ?Sized, R = ()> {
+    f: Arc<dyn Fn(&T) -> R>, // La closure partagée par tous les clones.
+    priority: i32,           // Priorité des callbacks obtenus par conversion.
+}
+
+impl<T: CallbackData + ?Sized, R> SharedCallback<T, R> {
+    /// Crée un callback partagé de priorité 0 à partir de la closure `f`.
+    pub fn new(f: impl Fn(&T) -> R + 'static) -> Self {
+        Self::with_priority(0, f)
+    }
+
+    /// Crée un callback partagé de priorité `priority` à partir de la closure `f`.
+    pub fn with_priority(priority: i32, f: impl Fn(&T) -> R + 'static) -> Self {
+        SharedCallback {
+            f: Arc::new(f),
+            priority,
+        }
+    }
+
+    /// Renvoie la priorité des callbacks obtenus par conversion.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+impl<T: CallbackData + ?Sized, R> Clone for SharedCallback<T, R> {
+    fn clone(&self) -> Self {
+        SharedCallback {
+            f: Arc::clone(&self.f),
+            priority: self.priority,
+        }
+    }
+}
+
+impl<T: CallbackData + ?Sized, R> From<SharedCallback<T, R>> for Callback<T, R> {
+    fn from(shared: SharedCallback<T, R>) -> Self {
+        Callback {
+            kind: CallbackKind::Shared(shared.f),
+            priority: shared.priority,
+            label: None,
+        }
+    }
+}
+
+/// Affiche le nom du callback, ou `<anonymous>`, et sa priorité.
+impl<T: CallbackData + ?Sized, R> fmt::Debug for Callback<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Callback")
+            .field("label", &self.label().unwrap_or("<anonymous>"))
+            .field("priority", &self.priority)
+            .finish()
+    }
+}
+
+/// Conversion en [`Callback`], acceptée par
+/// [`CallbackHost::set_callback`](crate::CallbackHost::set_callback).
+///
+/// Implémenté par `Callback` lui-même, par [`SharedCallback`] et par toute closure ou fonction
+/// `Fn(&T) -> R + 'static`. Une fonction passée directement est placée dans une boîte ;
+/// [`Callback::from_ptr`] évite cette allocation.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry};
+///
+/// fn log(data: &CallbackPayload) {
+///     println!("Data: {:?}", data);
+/// }
+///
+/// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+/// registry.set_callback(log);
+/// registry.set_callback(|data: &CallbackPayload| println!("{} bytes", data.as_bytes().len()));
+/// registry.do_something();
+/// ```
+pub trait IntoCallback<T: CallbackData + ?Sized, R = ()> {
+    /// Convertit `self` en `Callback`.
+    fn into_callback(self) -> Callback<T, R>;
+}
+
+impl<T: CallbackData + ?Sized, R> IntoCallback<T, R> for Callback<T, R> {
+    fn into_callback(self) -> Callback<T, R> {
+        self
+    }
+}
+
+impl<T: CallbackData + ?Sized, R> IntoCallback<T, R> for SharedCallback<T, R> {
+    fn into_callback(self) -> Callback<T, R> {
+        self.into()
+    }
+}
+
+impl<T: CallbackData + ?Sized, R, F: Fn(&T) -> R + 'static> IntoCallback<T, R> for F {
+    fn into_callback(self) -> Callback<T, R> {
+        Callback::new(self)
+    }
+}
+
+/// Gestionnaire d'événements avec état, alternative aux closures pour les cas plus riches.
+///
+/// Toute closure `FnMut(&T) -> R` implémente `Handler<T, R>`. Un gestionnaire s'enregistre avec
+/// [`CallbackHost::set_handler`](crate::CallbackHost::set_handler) et est appelé par
+/// `do_something` comme n'importe quel callback.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, Handler};
+///
+/// struct ByteCounter {
+///     total: usize,
+/// }
+///
+/// impl Handler<CallbackPayload> for ByteCounter {
+///     fn call(&mut self, data: &CallbackPayload) {
+///         self.total += data.as_bytes().len();
+///     }
+///
+///     fn name(&self) -> &str {
+///         "byte-counter"
+///     }
+/// }
+///
+/// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+/// registry.set_handler(Box::new(ByteCounter { total: 0 }));
+/// registry.do_something();
+/// ```
+pub trait Handler<T: CallbackData + ?Sized, R = ()> {
+    /// Traite les données `data`.
+    fn call(&mut self, data: &T) -> R;
+
+    /// Renvoie le nom du gestionnaire, utile pour le diagnostic.
+    fn name(&self) -> &str {
+        "unnamed"
+    }
+
+    /// Appelé par `set_handler` avant l'enregistrement, par exemple pour ouvrir une ressource.
+    fn on_register(&mut self) {}
+
+    /// Appelé quand un gestionnaire enregistré par `set_handler` quitte le registre : par
+    /// `remove_callback`, `clear_callbacks` ou la destruction du registre.
+    fn on_unregister(&mut self) {}
+}
+
+/// Gestionnaire enregistré : appelle `on_unregister` à sa destruction.
+struct Registered<T: CallbackData + ?Sized, R>(RefCell<Box<dyn Handler<T, R>>>);
+
+impl<T: CallbackData + ?Sized, R> Drop for Registered<T, R> {
+    fn drop(&mut self) {
+        self.0.get_mut().on_unregister();
+    }
+}
+
+/// Les closures `FnMut(&T) -> R` sont des gestionnaires anonymes.
+impl<T: CallbackData + ?Sized, R, F: FnMut(&T) -> R> Handler<T, R> for F {
+    fn call(&mut self, data: &T) -> R {
+        self(data)
+    }
+}
+
+/// Identifiant opaque d'un callback enregistré, renvoyé par `set_callback`.
+///
+/// Un identifiant désigne une case du registre et la génération de cette case : une case libérée
+/// par un retrait peut être réattribuée, mais sous une nouvelle génération, si bien qu'un
+/// identifiant retiré ne désigne jamais le callback qui lui succède dans la case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CallbackId {
+    index: u32,      // Case du callback dans le registre.
+    generation: u32, // Nombre de fois que la case a été libérée.
+}
+
+impl CallbackId {
+    /// Crée l'identifiant de la case `index` à la génération `generation`.
+    pub(crate) fn from_parts(index: u32, generation: u32) -> Self {
+        CallbackId { index, generation }
+    }
+
+    /// Renvoie la case désignée par l'identifiant.
+    pub(crate) fn index(self) -> usize {
+        self.index as usize
+    }
+
+    /// Renvoie la génération de la case désignée par l'identifiant.
+    pub(crate) fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+/// Générateur d'identifiants [`CallbackId`] monotones, utile pour implémenter
+/// [`CallbackHost`](crate::CallbackHost) sur ses propres types.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::CallbackIdGenerator;
+///
+/// let mut ids = CallbackIdGenerator::default();
+/// let first = ids.next_id();
+/// let second = ids.next_id();
+/// assert!(first < second);
+/// ```
+#[derive(Debug, Default)]
+pub struct CallbackIdGenerator {
+    next: u32, // Case du prochain identifiant.
+}
+
+impl CallbackIdGenerator {
+    /// Renvoie un nouvel identifiant, jamais renvoyé auparavant par ce générateur.
+    pub fn next_id(&mut self) -> CallbackId {
+        let id = CallbackId::from_parts(self.next, 0);
+        self.next += 1;
+        id
+    }
+}
+
+impl<T: CallbackData + ?Sized> Callback<T> {
+    /// Adapte un callback sans valeur de retour pour
+    /// [`dispatch_until_break`](crate::CallbackRegistry::dispatch_until_break) : il renvoie
+    /// toujours `ControlFlow::Continue` et conserve sa priorité.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use rust_reven::{Callback, CallbackPayload};
+    ///
+    /// let callback = Callback::new(|_data: &CallbackPayload| println!("Observé")).continuing();
+    /// assert_eq!(callback.invoke(CallbackPayload::new(&[])), ControlFlow::Continue(()));
+    /// ```
+    pub fn continuing(self) -> Callback<T, ControlFlow<()>>
+    where
+        T: 'static,
+    {
+        let (priority, label) = (self.priority, self.label.clone());
+        Callback::with_priority(priority, move |data: &T| {
+            self.invoke(data);
+            ControlFlow::Continue(())
+        })
+        .relabelled(label)
+    }
+
+    /// Renvoie un callback qui n'appelle `self` que pour les données acceptées par `pred`.
+    ///
+    /// Le callback obtenu conserve la priorité et le nom de `self`. Les combinateurs se composent :
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackPayload};
+    ///
+    /// let log = Callback::new(|data: &CallbackPayload| println!("Trame : {:?}", data))
+    ///     .filter(|data: &CallbackPayload| !data.as_bytes().is_empty())
+    ///     .chain(Callback::new(|_data: &CallbackPayload| println!("Trame traitée")));
+    /// log.invoke(CallbackPayload::new(&[1, 2]));
+    /// ```
+    pub fn filter(self, pred: impl Fn(&T) -> bool + 'static) -> Self
+    where
+        T: 'static,
+    {
+        let (priority, label) = (self.priority, self.label.clone());
+        Callback::with_priority(priority, move |data: &T| {
+            if pred(data) {
+                self.invoke(data);
+            }
+        })
+        .relabelled(label)
+    }
+
+    /// Renvoie un callback qui appelle `self` puis `other`, avec la priorité et le nom de `self`.
+    pub fn chain(self, other: Callback<T>) -> Self
+    where
+        T: 'static,
+    {
+        let (priority, label) = (self.priority, self.label.clone());
+        Callback::with_priority(priority, move |data: &T| {
+            self.invoke(data);
+            other.invoke(data);
+        })
+        .relabelled(label)
+    }
+}
+
+impl<T: CallbackData + ?Sized, V, E> Callback<T, Result<V, E>> {
+    /// Renvoie un callback qui appelle `fallback` avec les données et l'erreur si `self` échoue ;
+    /// son résultat est alors celui de `fallback`. La priorité et le nom de `self` sont conservés.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackError, CallbackPayload};
+    ///
+    /// let send = Callback::new(|_data: &CallbackPayload| Err(CallbackError::new("socket fermée")))
+    ///     .or_else(|data: &CallbackPayload, error| {
+    ///         println!("{}, mise en file de {:?}", error, data);
+    ///         Ok(())
+    ///     });
+    /// assert_eq!(send.invoke(CallbackPayload::new(&[1])), Ok(()));
+    /// ```
+    pub fn or_else(self, fallback: impl Fn(&T, E) -> Result<V, E> + 'static) -> Self
+    where
+        T: 'static,
+        V: 'static,
+        E: 'static,
+    {
+        let (priority, label) = (self.priority, self.label.clone());
+        Callback::with_priority(priority, move |data: &T| {
+            self.invoke(data).or_else(|error| fallback(data, error))
+        })
+        .relabelled(label)
+    }
+}
+
+/// Ancien nom de [`Callback`].
+#[deprecated(
+    since = "0.2.0",
+    note = "utilisez `Callback` à la place de `MyCallback`"
+)]
+pub type MyCallback<T> = Callback<T>;
+
+impl<T: CallbackData + ?Sized, R> Callback<T, R> {
+    /// Crée un callback de priorité 0 à partir de la closure `f`.
+    pub fn new(f: impl Fn(&T) -> R + 'static) -> Self {
+        Self::with_priority(0, f)
+    }
+
+    /// Crée un callback de priorité `priority` à partir de la closure `f`.
+    ///
+    /// Les registres appellent les callbacks par priorité croissante ; à priorité égale,
+    /// l'ordre d'enregistrement est conservé.
+    pub fn with_priority(priority: i32, f: impl Fn(&T) -> R + 'static) -> Self {
+        let mut cb = Self::from_boxed(Box::new(f));
+        cb.priority = priority;
+        cb
+    }
+
+    /// Crée un callback de priorité 0 à partir du pointeur de fonction `f`, sans allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// fn log(data: &CallbackPayload) {
+    ///     println!("Data: {:?}", data);
+    /// }
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(Callback::from_ptr(log));
+    /// registry.do_something();
+    /// ```
+    pub fn from_ptr(f: fn(&T) -> R) -> Self {
+        Callback {
+            kind: CallbackKind::FnPtr(f),
+            priority: 0,
+            label: None,
+        }
+    }
+
+    /// Crée un callback de priorité 0 à partir d'une closure déjà placée dans une boîte.
+    pub fn from_boxed(f: Box<dyn Fn(&T) -> R>) -> Self {
+        Callback {
+            kind: CallbackKind::Boxed(f),
+            priority: 0,
+            label: None,
+        }
+    }
+
+    /// Crée un callback de priorité 0 nommé `label`, nom qui apparaît dans les sorties `Debug`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackPayload};
+    ///
+    /// let callback = Callback::named("flush_metrics", |_data: &CallbackPayload| println!("flush"));
+    /// assert_eq!(callback.label(), Some("flush_metrics"));
+    /// assert!(format!("{:?}", callback).contains("flush_metrics"));
+    /// ```
+    pub fn named(label: impl Into<Cow<'static, str>>, f: impl Fn(&T) -> R + 'static) -> Self {
+        Self::new(f).relabelled(Some(label.into()))
+    }
+
+    /// Renvoie le nom du callback, s'il en a un.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    // Remplace le nom du callback par `label`.
+    fn relabelled(self, label: Option<Cow<'static, str>>) -> Self {
+        Callback { label, ..self }
+    }
+
+    /// Crée un callback de priorité 0 à partir d'une closure `FnMut`, qui peut modifier l'état qu'elle capture.
+    ///
+    /// La closure est placée dans un `RefCell`, ce qui permet aux registres de l'appeler via `&self`.
+    ///
+    /// # Panics
+    ///
+    /// L'appel panique si la closure est rappelée alors qu'elle est déjà en cours d'exécution,
+    /// par exemple par un `do_something` imbriqué.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut total = 0usize;
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(Callback::from_fn_mut(move |data: &CallbackPayload| {
+    ///     total += data.as_bytes().len();
+    ///     println!("{} bytes reçus au total", total);
+    /// }));
+    /// registry.do_something();
+    /// ```
+    pub fn from_fn_mut(f: impl FnMut(&T) -> R + 'static) -> Self {
+        let f = RefCell::new(f);
+        Self::new(move |data: &T| (f.borrow_mut())(data))
+    }
+
+    /// Crée un callback de priorité 0 qui appelle `handler`.
+    ///
+    /// # Panics
+    ///
+    /// Comme pour [`from_fn_mut`](Self::from_fn_mut), l'appel panique si le gestionnaire est
+    /// rappelé pendant sa propre exécution.
+    pub fn from_handler(handler: Box<dyn Handler<T, R>>) -> Self
+    where
+        T: 'static,
+        R: 'static,
+    {
+        let handler = RefCell::new(handler);
+        Self::new(move |data: &T| handler.borrow_mut().call(data))
+    }
+
+    /// Comme [`from_handler`](Self::from_handler), mais appelle `on_register` tout de suite puis
+    /// `on_unregister` à la destruction du callback.
+    pub(crate) fn from_registered_handler(mut handler: Box<dyn Handler<T, R>>) -> Self
+    where
+        T: 'static,
+        R: 'static,
+    {
+        handler.on_register();
+        let handler = Registered(RefCell::new(handler));
+        Self::new(move |data: &T| handler.0.borrow_mut().call(data))
+    }
+
+    /// Renvoie un callback qui transmet à `cb` la vue des données calculée par `f`, par exemple
+    /// les données privées de leur en-tête. Le callback obtenu conserve la priorité et le nom de `cb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackPayload};
+    ///
+    /// let body = Callback::map_data(
+    ///     |data: &CallbackPayload| CallbackPayload::new(&data.as_bytes()[1..]),
+    ///     Callback::new(|body: &CallbackPayload| body.as_bytes().len()),
+    /// );
+    /// assert_eq!(body.invoke(CallbackPayload::new(&[0xFF, 1, 2])), 2);
+    /// ```
+    pub fn map_data<U: CallbackData + ?Sized + 'static>(
+        f: impl Fn(&T) -> &U + 'static,
+        cb: Callback<U, R>,
+    ) -> Self
+    where
+        T: 'static,
+        R: 'static,
+    {
+        let (priority, label) = (cb.priority, cb.label.clone());
+        Callback::with_priority(priority, move |data: &T| cb.invoke(f(data))).relabelled(label)
+    }
+
+    /// Renvoie la priorité du callback.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Change la priorité du callback.
+    pub(crate) fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    /// Exécute la fonction encapsulée avec `data`.
+    pub fn invoke(&self, data: &T) -> R {
+        match &self.kind {
+            CallbackKind::FnPtr(f) => f(data),
+            CallbackKind::Boxed(f) => f(data),
+            CallbackKind::Shared(f) => f(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackRegistry;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct Counter(u32);
+
+    impl CallbackData for Counter {}
+
+    /// Teste que `invoke` transmet bien la donnée à la closure encapsulée.
+    #[test]
+    fn test_invoke_passes_data() {
+        let seen = Rc::new(Cell::new(0));
+        let seen_in_cb = Rc::clone(&seen);
+        let callback = Callback::new(move |data: &Counter| seen_in_cb.set(data.0));
+
+        callback.invoke(&Counter(7));
+
+        assert_eq!(seen.get(), 7);
+    }
+
+    /// Renvoie le double de la valeur reçue.
+    fn store(data: &Counter) -> u32 {
+        data.0 * 2
+    }
+
+    /// Teste qu'un pointeur de fonction est conservé tel quel, sans boîte.
+    #[test]
+    fn test_from_ptr_is_not_boxed() {
+        let callback = Callback::from_ptr(store);
+
+        assert!(matches!(callback.kind, CallbackKind::FnPtr(_)));
+        assert_eq!(callback.invoke(&Counter(21)), 42);
+        assert!(matches!(
+            Callback::from_boxed(Box::new(|data: &Counter| data.0)).kind,
+            CallbackKind::Boxed(_)
+        ));
+    }
+
+    /// Teste que les clones d'un callback partagé appellent la même closure avec sa priorité.
+    #[test]
+    fn test_shared_callback_clones_share_closure() {
+        let shared = SharedCallback::with_priority(3, |data: &Counter| data.0 + 1);
+        let first: Callback<Counter, u32> = shared.clone().into();
+        let second: Callback<Counter, u32> = shared.clone().into();
+
+        assert_eq!(Arc::strong_count(&shared.f), 3);
+        assert_eq!(first.invoke(&Counter(1)), 2);
+        assert_eq!(second.invoke(&Counter(2)), 3);
+        assert_eq!(second.priority(), 3);
+    }
+
+    /// Données composées d'un en-tête et d'une valeur.
+    struct Frame {
+        header: u32,
+        body: Counter,
+    }
+
+    impl CallbackData for Frame {}
+
+    /// Teste un pipeline `map_data`, `filter` puis `chain` sur des données acceptées et refusées.
+    #[test]
+    fn test_combinators_compose() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (first, second) = (Rc::clone(&calls), Rc::clone(&calls));
+        let record =
+            Callback::new(move |data: &Counter| first.borrow_mut().push(("record", data.0)));
+        let audit =
+            Callback::new(move |data: &Counter| second.borrow_mut().push(("audit", data.0)));
+        let pipeline = Callback::map_data(
+            |frame: &Frame| {
+                assert!(frame.header > 0, "en-tête invalide");
+                &frame.body
+            },
+            record
+                .filter(|data: &Counter| data.0.is_multiple_of(2))
+                .chain(audit),
+        );
+
+        pipeline.invoke(&Frame {
+            header: 1,
+            body: Counter(4),
+        });
+        pipeline.invoke(&Frame {
+            header: 2,
+            body: Counter(5),
+        });
+
+        assert_eq!(
+            *calls.borrow(),
+            vec![("record", 4), ("audit", 4), ("audit", 5)]
+        );
+    }
+
+    /// Teste que les combinateurs conservent la priorité du callback d'origine.
+    #[test]
+    fn test_combinators_keep_priority() {
+        let base = Callback::with_priority(7, |_data: &Counter| {});
+        let chained = base
+            .filter(|_data: &Counter| true)
+            .chain(Callback::new(|_data: &Counter| {}));
+        assert_eq!(chained.priority(), 7);
+        let mapped = Callback::map_data(|frame: &Frame| &frame.body, chained);
+        assert_eq!(mapped.priority(), 7);
+    }
+
+    /// Teste que le nom d'un callback survit aux combinateurs et apparaît dans `Debug`.
+    #[test]
+    fn test_named_callback_debug() {
+        let named = Callback::named(String::from("audit"), |_data: &Counter| {})
+            .filter(|_data: &Counter| true);
+
+        assert_eq!(named.label(), Some("audit"));
+        assert_eq!(
+            format!("{:?}", named),
+            r#"Callback { label: "audit", priority: 0 }"#
+        );
+        assert!(format!("{:?}", Callback::new(|_data: &Counter| {})).contains("<anonymous>"));
+    }
+
+    /// Crée un callback qui compte ses appels dans `calls` et renvoie `outcome`.
+    fn outcome(
+        calls: &Rc<Cell<u32>>,
+        outcome: Result<u32, &'static str>,
+    ) -> Callback<Counter, Result<u32, &'static str>> {
+        let calls = Rc::clone(calls);
+        Callback::new(move |_data: &Counter| {
+            calls.set(calls.get() + 1);
+            outcome
+        })
+    }
+
+    /// Teste que le secours n'est pas appelé lorsque le callback principal réussit.
+    #[test]
+    fn test_or_else_primary_succeeds() {
+        let fallback_calls = Rc::new(Cell::new(0));
+        let fallback_calls_in_cb = Rc::clone(&fallback_calls);
+        let primary_calls = Rc::new(Cell::new(0));
+        let combined = outcome(&primary_calls, Ok(1)).or_else(move |_data, _error| {
+            fallback_calls_in_cb.set(fallback_calls_in_cb.get() + 1);
+            Ok(2)
+        });
+
+        assert_eq!(combined.invoke(&Counter(0)), Ok(1));
+        assert_eq!((primary_calls.get(), fallback_calls.get()), (1, 0));
+    }
+
+    /// Teste que le secours reçoit les données et l'erreur, et que son succès l'emporte.
+    #[test]
+    fn test_or_else_fallback_recovers() {
+        let primary_calls = Rc::new(Cell::new(0));
+        let combined = outcome(&primary_calls, Err("socket fermée")).or_else(|data, error| {
+            assert_eq!(error, "socket fermée");
+            Ok(data.0)
+        });
+
+        assert_eq!(combined.invoke(&Counter(7)), Ok(7));
+    }
+
+    /// Teste que l'erreur du secours est renvoyée lorsque les deux callbacks échouent.
+    #[test]
+    fn test_or_else_both_fail() {
+        let primary_calls = Rc::new(Cell::new(0));
+        let combined =
+            outcome(&primary_calls, Err("principal")).or_else(|_data, _error| Err("secours"));
+
+        assert_eq!(combined.invoke(&Counter(0)), Err("secours"));
+        assert_eq!(primary_calls.get(), 1);
+    }
+
+    /// Teste que `new` donne la priorité par défaut 0.
+    #[test]
+    fn test_default_priority() {
+        let callback = Callback::new(|_data: &Counter| {});
+        assert_eq!(callback.priority(), 0);
+        assert_eq!(
+            Callback::with_priority(-3, |_data: &Counter| {}).priority(),
+            -3
+        );
+    }
+
+    /// Teste qu'une closure `FnMut` peut incrémenter un compteur capturé à chaque appel.
+    #[test]
+    fn test_from_fn_mut_keeps_state() {
+        let seen = Rc::new(Cell::new(0));
+        let seen_in_cb = Rc::clone(&seen);
+        let mut count = 0;
+        let callback = Callback::from_fn_mut(move |_data: &Counter| {
+            count += 1;
+            seen_in_cb.set(count);
+        });
+
+        for _ in 0..3 {
+            callback.invoke(&Counter(0));
+        }
+
+        assert_eq!(seen.get(), 3);
+    }
+
+    /// Gestionnaire qui conserve les valeurs reçues.
+    struct Recorder {
+        received: Vec<u32>,
+    }
+
+    impl Handler<Counter> for Recorder {
+        fn call(&mut self, data: &Counter) {
+            self.received.push(data.0);
+        }
+
+        fn name(&self) -> &str {
+            "recorder"
+        }
+    }
+
+    /// Teste qu'un gestionnaire structuré conserve les données reçues dans son état interne.
+    #[test]
+    fn test_struct_handler_records_payloads() {
+        let mut recorder = Recorder {
+            received: Vec::new(),
+        };
+
+        recorder.call(&Counter(1));
+        recorder.call(&Counter(2));
+
+        assert_eq!(recorder.received, vec![1, 2]);
+        assert_eq!(recorder.name(), "recorder");
+    }
+
+    /// Teste qu'une closure est un gestionnaire anonyme.
+    #[test]
+    fn test_closure_is_unnamed_handler() {
+        let mut sum = 0;
+        let mut handler = |data: &Counter| sum += data.0;
+        Handler::call(&mut handler, &Counter(4));
+        assert_eq!(Handler::<Counter>::name(&handler), "unnamed");
+        assert_eq!(sum, 4);
+    }
+
+    /// Teste qu'un callback adapté par `continuing` est appelé, renvoie `Continue` et garde sa priorité.
+    #[test]
+    fn test_continuing_keeps_priority() {
+        let seen = Rc::new(Cell::new(0));
+        let seen_in_cb = Rc::clone(&seen);
+        let callback =
+            Callback::with_priority(5, move |data: &Counter| seen_in_cb.set(data.0)).continuing();
+
+        assert_eq!(callback.invoke(&Counter(9)), ControlFlow::Continue(()));
+        assert_eq!(seen.get(), 9);
+        assert_eq!(callback.priority(), 5);
+    }
+
+    /// Teste que le générateur renvoie des identifiants distincts et croissants.
+    #[test]
+    fn test_id_generator_is_monotonic() {
+        let mut ids = CallbackIdGenerator::default();
+        let generated: Vec<CallbackId> = (0..3).map(|_| ids.next_id()).collect();
+        assert!(generated.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    /// Teste que des registres de `Vec<u8>` et de `String` fonctionnent sans implémentation côté utilisateur.
+    #[test]
+    fn test_common_types_are_callback_data() {
+        let total = Rc::new(Cell::new(0));
+        let mut bytes: CallbackRegistry<Vec<u8>> = CallbackRegistry::new();
+        let total_in_cb = Rc::clone(&total);
+        bytes.add_callback(move |data: &Vec<u8>| total_in_cb.set(total_in_cb.get() + data.len()));
+        bytes.dispatch_value(&vec![1, 2, 3]);
+
+        let mut text: CallbackRegistry<String> = CallbackRegistry::new();
+        let total_in_cb = Rc::clone(&total);
+        text.add_callback(move |data: &String| total_in_cb.set(total_in_cb.get() + data.len()));
+        text.dispatch_value(&"abcd".to_string());
+
+        let mut pairs: CallbackRegistry<(u16, Option<f32>)> = CallbackRegistry::new();
+        let total_in_cb = Rc::clone(&total);
+        pairs.add_callback(move |(id, _value): &(u16, Option<f32>)| {
+            total_in_cb.set(total_in_cb.get() + *id as usize)
+        });
+        pairs.dispatch_value(&(10, None));
+
+        assert_eq!(total.get(), 17);
+    }
+}
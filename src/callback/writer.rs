@@ -0,0 +1,279 @@
+//! Callbacks qui écrivent les données reçues dans une cible `std::io::Write`.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, ErrorKind, Write};
+use std::rc::Rc;
+
+use super::{Callback, IntoCallback};
+use crate::data::encoding::encode_base64;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Délimitation des données écrites par un [`WriterSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteFormat {
+    /// Écrit les bytes tels quels, sans délimitation.
+    #[default]
+    Raw,
+    /// Fait précéder les bytes de leur taille sur 2 bytes big-endian, comme le lit
+    /// [`Framer::length_prefixed`](crate::Framer::length_prefixed).
+    LengthPrefixed,
+    /// Fait suivre les bytes d'un `\n`, comme le lit [`Framer::delimited`](crate::Framer::delimited).
+    Newline,
+    /// Écrit les bytes en base64, une ligne terminée par `\n` par donnée.
+    Base64,
+}
+
+/// État partagé par les clones d'un [`WriterSink`].
+struct SinkState<W> {
+    writer: W,                     // La cible des écritures.
+    format: WriteFormat,           // Délimitation des données écrites.
+    flush: bool,                   // Vide la cible après chaque écriture.
+    last_error: Option<io::Error>, // Dernière erreur d'écriture, `None` s'il n'y en a pas eu.
+}
+
+impl<W: Write> SinkState<W> {
+    /// Écrit `bytes` selon le format, puis vide la cible si demandé.
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self.format {
+            WriteFormat::Raw => self.writer.write_all(bytes)?,
+            WriteFormat::LengthPrefixed => {
+                let len = u16::try_from(bytes.len()).map_err(|_| {
+                    io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "{} bytes ne tiennent pas dans un préfixe de 2 bytes",
+                            bytes.len()
+                        ),
+                    )
+                })?;
+                self.writer.write_all(&len.to_be_bytes())?;
+                self.writer.write_all(bytes)?;
+            }
+            WriteFormat::Newline => {
+                self.writer.write_all(bytes)?;
+                self.writer.write_all(b"\n")?;
+            }
+            WriteFormat::Base64 => {
+                let mut line = encode_base64(bytes);
+                line.push('\n');
+                self.writer.write_all(line.as_bytes())?;
+            }
+        }
+        if self.flush {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Callback qui écrit chaque donnée reçue dans la cible `W`, par exemple un fichier ou une socket.
+///
+/// Les clones partagent la cible : on en enregistre un dans le registre et on garde l'autre pour
+/// consulter la cible ou la dernière erreur d'écriture. Une erreur n'interrompt pas les appels
+/// suivants, qui écrivent à nouveau.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+///
+/// let sink = Callback::to_writer(Vec::new()).newline();
+/// let mut registry: CallbackRegistry<CallbackPayload> = CallbackRegistry::with_data(&b"bonjour"[..]);
+/// registry.set_callback(sink.clone());
+/// registry.do_something();
+/// assert_eq!(sink.with_writer(|written| written.clone()), b"bonjour\n");
+/// assert!(sink.take_error().is_none());
+/// ```
+pub struct WriterSink<W> {
+    state: Rc<RefCell<SinkState<W>>>, // État partagé par les clones.
+}
+
+impl Callback<CallbackPayload> {
+    /// Crée un [`WriterSink`] qui écrit les bytes de chaque donnée reçue dans `w`.
+    pub fn to_writer<W: Write + 'static>(w: W) -> WriterSink<W> {
+        WriterSink {
+            state: Rc::new(RefCell::new(SinkState {
+                writer: w,
+                format: WriteFormat::Raw,
+                flush: false,
+                last_error: None,
+            })),
+        }
+    }
+
+    /// Crée un [`WriterSink`] qui écrit chaque donnée reçue dans `w` en base64, une ligne par
+    /// donnée, voir [`WriteFormat::Base64`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry: CallbackRegistry<CallbackPayload> = CallbackRegistry::with_data(&b"reven"[..]);
+    /// registry.set_callback(Callback::to_base64_writer(std::io::stdout()));
+    /// registry.do_something(); // Affiche « cmV2ZW4= ».
+    /// ```
+    pub fn to_base64_writer<W: Write + 'static>(w: W) -> WriterSink<W> {
+        Callback::to_writer(w).format(WriteFormat::Base64)
+    }
+}
+
+impl<W: Write + 'static> WriterSink<W> {
+    /// Délimite les données écrites selon `format`.
+    pub fn format(self, format: WriteFormat) -> Self {
+        self.state.borrow_mut().format = format;
+        self
+    }
+
+    /// Fait précéder chaque donnée de sa taille, voir [`WriteFormat::LengthPrefixed`].
+    pub fn length_prefixed(self) -> Self {
+        self.format(WriteFormat::LengthPrefixed)
+    }
+
+    /// Fait suivre chaque donnée d'un `\n`, voir [`WriteFormat::Newline`].
+    pub fn newline(self) -> Self {
+        self.format(WriteFormat::Newline)
+    }
+
+    /// Vide la cible après chaque donnée écrite.
+    pub fn flush_each(self) -> Self {
+        self.state.borrow_mut().flush = true;
+        self
+    }
+
+    /// Appelle `f` sur la cible, par exemple pour lire les bytes écrits dans un `Vec<u8>`.
+    ///
+    /// # Panics
+    ///
+    /// Panique si elle est appelée pendant une écriture, depuis la cible elle-même.
+    pub fn with_writer<V>(&self, f: impl FnOnce(&mut W) -> V) -> V {
+        f(&mut self.state.borrow_mut().writer)
+    }
+
+    /// Renvoie la dernière erreur d'écriture et l'oublie, ou `None` s'il n'y en a pas eu.
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.state.borrow_mut().last_error.take()
+    }
+
+    /// Écrit `bytes`, en conservant l'éventuelle erreur.
+    fn write(&self, bytes: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        if let Err(error) = state.write(bytes) {
+            state.last_error = Some(error);
+        }
+    }
+}
+
+impl<W> Clone for WriterSink<W> {
+    fn clone(&self) -> Self {
+        WriterSink {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<W> fmt::Debug for WriterSink<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("WriterSink")
+            .field("format", &state.format)
+            .field("flush", &state.flush)
+            .field("last_error", &state.last_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + 'static> IntoCallback<CallbackPayload> for WriterSink<W> {
+    fn into_callback(self) -> Callback<CallbackPayload> {
+        Callback::new(move |data: &CallbackPayload| self.write(data.as_bytes()))
+    }
+}
+
+impl<W: Write + 'static> IntoCallback<ArcCallbackPayload> for WriterSink<W> {
+    fn into_callback(self) -> Callback<ArcCallbackPayload> {
+        Callback::new(move |data: &ArcCallbackPayload| self.write(data.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{CallbackHost, CallbackRegistry};
+
+    /// Transmet `first` puis `second` à un registre dont le seul callback est `sink`.
+    fn dispatch_two(sink: &WriterSink<Vec<u8>>, first: &[u8], second: &[u8]) {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(first.to_vec());
+        registry.set_callback(sink.clone());
+        registry.do_something();
+        registry.set_data(second.to_vec());
+        registry.do_something();
+    }
+
+    /// Teste le flux exact de bytes écrit pour deux appels, dans chaque format.
+    #[test]
+    fn test_exact_byte_stream_for_two_dispatches() {
+        let formats = [
+            (WriteFormat::Raw, vec![1, 2, 3]),
+            (WriteFormat::LengthPrefixed, vec![0, 2, 1, 2, 0, 1, 3]),
+            (WriteFormat::Newline, vec![1, 2, b'\n', 3, b'\n']),
+            (WriteFormat::Base64, b"AQI=\nAw==\n".to_vec()),
+        ];
+        for (format, expected) in formats {
+            let sink = Callback::to_writer(Vec::new()).format(format);
+            dispatch_two(&sink, &[1, 2], &[3]);
+            assert_eq!(sink.with_writer(|written| written.clone()), expected);
+            assert!(sink.take_error().is_none());
+        }
+    }
+
+    /// Cible qui compte les vidages et refuse d'écrire après `capacity` bytes.
+    struct Limited {
+        written: Vec<u8>, // Bytes acceptés.
+        capacity: usize,  // Nombre maximal de bytes acceptés.
+        flushes: usize,   // Nombre de vidages.
+    }
+
+    impl Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.capacity - self.written.len());
+            if len == 0 && !buf.is_empty() {
+                return Err(ErrorKind::WriteZero.into());
+            }
+            self.written.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    /// Teste le vidage après chaque appel et la conservation de la dernière erreur d'écriture.
+    #[test]
+    fn test_flush_each_and_last_error() {
+        let sink = Callback::to_writer(Limited {
+            written: Vec::new(),
+            capacity: 3,
+            flushes: 0,
+        })
+        .flush_each();
+        let mut registry: CallbackRegistry<'static, ArcCallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1, 2]);
+        registry.set_callback(sink.clone());
+
+        registry.do_something();
+        assert_eq!(sink.with_writer(|target| target.flushes), 1);
+        assert!(sink.take_error().is_none());
+
+        registry.do_something();
+        assert_eq!(sink.take_error().unwrap().kind(), ErrorKind::WriteZero);
+        assert!(sink.take_error().is_none());
+        assert_eq!(
+            sink.with_writer(|target| target.written.clone()),
+            vec![1, 2, 1]
+        );
+    }
+}
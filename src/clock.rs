@@ -0,0 +1,80 @@
+//! Horloges injectables, pour que les fonctionnalités qui dépendent du temps se testent sans attendre.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source de l'heure courante.
+///
+/// Toute closure `Fn() -> Instant` est une horloge.
+pub trait Clock {
+    /// Renvoie l'heure courante.
+    fn now(&self) -> Instant;
+}
+
+impl<F: Fn() -> Instant> Clock for F {
+    fn now(&self) -> Instant {
+        self()
+    }
+}
+
+/// Horloge du système, qui renvoie [`Instant::now`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Horloge qui n'avance que sur demande, pour les tests.
+///
+/// Les clones partagent la même heure : avancer l'un avance tous les autres.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Clock, ManualClock};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let start = clock.now();
+/// clock.clone().advance(Duration::from_secs(2));
+/// assert_eq!(clock.now() - start, Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>, // Heure courante, partagée par les clones.
+}
+
+impl ManualClock {
+    /// Crée une horloge arrêtée à l'heure actuelle.
+    pub fn new() -> Self {
+        ManualClock {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Avance l'horloge de `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self
+            .now
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self
+            .now
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
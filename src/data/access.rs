@@ -0,0 +1,136 @@
+//! Lecture d'entiers à une position donnée des données, avec vérification des bornes.
+
+use std::slice::SliceIndex;
+
+use super::CallbackPayload;
+
+/// Déclare, pour chaque type entier, ses accesseurs gros-boutiste et petit-boutiste.
+macro_rules! int_accessors {
+    ($($ty:ident => $be:ident, $le:ident;)+) => {
+        $(
+            #[doc = concat!("Lit un `", stringify!($ty), "` gros-boutiste à la position `offset`.")]
+            ///
+            /// Renvoie `None` si les données sont trop courtes.
+            pub fn $be(&self, offset: usize) -> Option<$ty> {
+                self.array_at(offset).map($ty::from_be_bytes)
+            }
+
+            #[doc = concat!("Lit un `", stringify!($ty), "` petit-boutiste à la position `offset`.")]
+            ///
+            /// Renvoie `None` si les données sont trop courtes.
+            pub fn $le(&self, offset: usize) -> Option<$ty> {
+                self.array_at(offset).map($ty::from_le_bytes)
+            }
+        )+
+    };
+}
+
+impl CallbackPayload {
+    /// Renvoie les `N` bytes à partir de `offset`, ou `None` s'ils dépassent la fin des données.
+    fn array_at<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        let end = offset.checked_add(N)?;
+        let bytes = self.as_bytes().get(offset..end)?;
+        Some(bytes.try_into().expect("slice de longueur N"))
+    }
+
+    /// Lit le byte à la position `offset`, ou `None` si elle dépasse la fin des données.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let data = CallbackPayload::new(&[0x01, 0x12, 0x34]);
+    /// assert_eq!(data.u8_at(0), Some(0x01));
+    /// assert_eq!(data.u16_be_at(1), Some(0x1234));
+    /// assert_eq!(data.u16_le_at(1), Some(0x3412));
+    /// assert_eq!(data.u32_be_at(0), None);
+    /// ```
+    pub fn u8_at(&self, offset: usize) -> Option<u8> {
+        self.as_bytes().get(offset).copied()
+    }
+
+    /// Lit le byte à la position `offset` comme un `i8`, ou `None` si elle dépasse la fin des données.
+    pub fn i8_at(&self, offset: usize) -> Option<i8> {
+        self.u8_at(offset).map(|byte| byte as i8)
+    }
+
+    int_accessors! {
+        u16 => u16_be_at, u16_le_at;
+        i16 => i16_be_at, i16_le_at;
+        u32 => u32_be_at, u32_le_at;
+        i32 => i32_be_at, i32_le_at;
+        u64 => u64_be_at, u64_le_at;
+        i64 => i64_be_at, i64_le_at;
+    }
+
+    /// Renvoie les bytes de `range`, ou `None` si la plage dépasse la fin des données.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let data = CallbackPayload::new(&[1, 2, 3, 4]);
+    /// assert_eq!(data.slice(1..3), Some(&[2, 3][..]));
+    /// assert_eq!(data.slice(2..), Some(&[3, 4][..]));
+    /// assert_eq!(data.slice(..5), None);
+    /// ```
+    pub fn slice(&self, range: impl SliceIndex<[u8], Output = [u8]>) -> Option<&[u8]> {
+        self.as_bytes().get(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste les lectures qui se terminent exactement sur le dernier byte.
+    #[test]
+    fn test_exact_boundary_reads() {
+        let data = CallbackPayload::new(&[0xAA, 0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(data.u8_at(4), Some(0x04));
+        assert_eq!(data.u16_be_at(3), Some(0x0304));
+        assert_eq!(data.u16_le_at(3), Some(0x0403));
+        assert_eq!(data.u32_be_at(1), Some(0x0102_0304));
+        assert_eq!(data.u32_le_at(1), Some(0x0403_0201));
+        assert_eq!(data.i8_at(0), Some(-86));
+        assert_eq!(data.slice(5..), Some(&[][..]));
+        assert_eq!(data.slice(..), Some(data.as_bytes()));
+    }
+
+    /// Teste qu'une lecture qui dépasse la fin renvoie `None` sans paniquer.
+    #[test]
+    fn test_out_of_range_offsets() {
+        let data = CallbackPayload::new(&[1, 2, 3, 4]);
+        assert_eq!(data.u8_at(4), None);
+        assert_eq!(data.u16_be_at(3), None);
+        assert_eq!(data.u32_le_at(1), None);
+        assert_eq!(data.u64_be_at(0), None);
+        assert_eq!(data.u16_le_at(usize::MAX), None);
+        assert_eq!(data.slice(3..5), None);
+        assert_eq!(data.slice(5..), None);
+    }
+
+    /// Teste que toutes les lectures échouent sur des données vides, sauf la plage vide.
+    #[test]
+    fn test_empty_slice() {
+        let data = CallbackPayload::new(&[]);
+        assert_eq!(data.u8_at(0), None);
+        assert_eq!(data.i16_be_at(0), None);
+        assert_eq!(data.i64_le_at(0), None);
+        assert_eq!(data.slice(0..0), Some(&[][..]));
+        assert_eq!(data.slice(0..1), None);
+    }
+
+    /// Teste la lecture des entiers signés et de 64 bits.
+    #[test]
+    fn test_signed_and_wide_reads() {
+        let bytes = [0xFF, 0xFE, 0, 0, 0, 0, 0, 0, 0x01];
+        let data = CallbackPayload::new(&bytes);
+        assert_eq!(data.i16_be_at(0), Some(-2));
+        assert_eq!(data.i16_le_at(0), Some(-257));
+        assert_eq!(data.u64_le_at(1), Some(0x0100_0000_0000_00FE));
+        assert_eq!(data.i32_be_at(0), Some(-131_072));
+    }
+}
@@ -0,0 +1,162 @@
+//! Sommes de contrôle en fin de trame : calcul, vérification et retrait.
+
+use super::process_data;
+use crate::error::ChecksumError;
+
+/// Table du CRC-32 (polynôme réfléchi `0xEDB88320`), calculée à la compilation.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+/// Algorithme de la somme de contrôle placée à la fin des données.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Checksum {
+    /// Somme des bytes modulo 256, sur un byte.
+    Additive,
+    /// CRC-32 IEEE (celui de zlib et d'Ethernet), sur 4 bytes petit-boutistes.
+    Crc32,
+}
+
+impl Checksum {
+    /// Renvoie le nombre de bytes occupés par la somme de contrôle.
+    pub fn size(self) -> usize {
+        match self {
+            Checksum::Additive => 1,
+            Checksum::Crc32 => 4,
+        }
+    }
+
+    /// Calcule la somme de contrôle de `data`.
+    pub fn compute(self, data: &[u8]) -> u32 {
+        match self {
+            Checksum::Additive => u32::from(data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))),
+            Checksum::Crc32 => !data.iter().fold(!0u32, |crc, &byte| {
+                CRC32_TABLE[usize::from((crc as u8) ^ byte)] ^ (crc >> 8)
+            }),
+        }
+    }
+
+    /// Ajoute à `frame` la somme de contrôle de son contenu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{process_data_checked, Checksum};
+    ///
+    /// let mut frame = vec![1, 2, 3];
+    /// Checksum::Additive.append(&mut frame);
+    /// assert_eq!(frame, vec![1, 2, 3, 6]);
+    /// assert_eq!(process_data_checked(&frame, Checksum::Additive), Ok(&[1, 2, 3][..]));
+    /// ```
+    pub fn append(self, frame: &mut Vec<u8>) {
+        let checksum = self.compute(frame);
+        frame.extend_from_slice(&checksum.to_le_bytes()[..self.size()]);
+    }
+
+    /// Vérifie la somme de contrôle qui termine `frame` et renvoie les données qui la précèdent.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`ChecksumError::TooShort`] si `frame` ne contient pas de somme de contrôle entière,
+    /// et [`ChecksumError::Mismatch`] si elle ne correspond pas aux données.
+    pub fn verify(self, frame: &[u8]) -> Result<&[u8], ChecksumError> {
+        let size = self.size();
+        if frame.len() < size {
+            return Err(ChecksumError::TooShort {
+                len: frame.len(),
+                needed: size,
+            });
+        }
+        let (payload, trailer) = frame.split_at(frame.len() - size);
+        let mut bytes = [0u8; 4];
+        bytes[..size].copy_from_slice(trailer);
+        let expected = u32::from_le_bytes(bytes);
+        let actual = self.compute(payload);
+        if expected == actual {
+            Ok(payload)
+        } else {
+            Err(ChecksumError::Mismatch { expected, actual })
+        }
+    }
+}
+
+/// Comme [`process_data`], mais vérifie d'abord la somme de contrôle `algo` qui termine `data` et
+/// ne traite que les données qui la précèdent, renvoyées en cas de succès.
+///
+/// # Errors
+///
+/// Renvoie l'erreur de [`Checksum::verify`] ; les données ne sont alors pas traitées.
+pub fn process_data_checked(data: &[u8], algo: Checksum) -> Result<&[u8], ChecksumError> {
+    let payload = algo.verify(data)?;
+    process_data(payload);
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste les sommes de contrôle sur des valeurs de référence.
+    #[test]
+    fn test_known_good_vectors() {
+        assert_eq!(Checksum::Crc32.compute(b"123456789"), 0xCBF4_3926);
+        assert_eq!(Checksum::Crc32.compute(b""), 0);
+        assert_eq!(
+            Checksum::Crc32.compute(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+        assert_eq!(Checksum::Additive.compute(&[0xff, 0x02]), 0x01);
+
+        let frame = [b"123456789".as_slice(), &0xCBF4_3926u32.to_le_bytes()].concat();
+        assert_eq!(
+            process_data_checked(&frame, Checksum::Crc32),
+            Ok(&b"123456789"[..])
+        );
+    }
+
+    /// Teste qu'un byte corrompu est détecté par les deux algorithmes.
+    #[test]
+    fn test_corrupted_byte_is_rejected() {
+        for algo in [Checksum::Additive, Checksum::Crc32] {
+            let mut frame = vec![0x10, 0x20, 0x30];
+            algo.append(&mut frame);
+            assert!(algo.verify(&frame).is_ok());
+            frame[1] ^= 0x01;
+            assert!(matches!(
+                process_data_checked(&frame, algo),
+                Err(ChecksumError::Mismatch { .. })
+            ));
+        }
+    }
+
+    /// Teste qu'une trame plus courte que la somme de contrôle est refusée, et qu'une trame qui
+    /// ne contient que la somme d'un contenu vide est acceptée.
+    #[test]
+    fn test_too_short_frame() {
+        assert_eq!(
+            Checksum::Crc32.verify(&[1, 2, 3]),
+            Err(ChecksumError::TooShort { len: 3, needed: 4 })
+        );
+        assert_eq!(
+            Checksum::Additive.verify(&[]),
+            Err(ChecksumError::TooShort { len: 0, needed: 1 })
+        );
+        assert_eq!(Checksum::Additive.verify(&[0]), Ok(&[][..]));
+    }
+}
@@ -0,0 +1,131 @@
+//! Découpage des données en enregistrements de taille fixe.
+
+use crate::error::ChunkError;
+
+/// Traitement du dernier morceau quand la taille des données n'est pas un multiple de celle des
+/// morceaux, voir [`CallbackRegistry::dispatch_chunks`](crate::CallbackRegistry::dispatch_chunks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartialChunk {
+    /// Transmet le dernier morceau, plus court que les autres.
+    #[default]
+    Deliver,
+    /// Ignore le dernier morceau.
+    Drop,
+    /// Refuse les données entières ; aucun morceau n'est transmis.
+    Error,
+}
+
+/// Découpe `data` en morceaux de `chunk_size` bytes selon `partial`, ou renvoie l'erreur qui
+/// refuse le découpage.
+pub(crate) fn split(
+    data: &[u8],
+    chunk_size: usize,
+    partial: PartialChunk,
+) -> Result<impl Iterator<Item = &[u8]>, ChunkError> {
+    if chunk_size == 0 {
+        return Err(ChunkError::ZeroSize);
+    }
+    let remainder = data.len() % chunk_size;
+    if remainder != 0 && partial == PartialChunk::Error {
+        return Err(ChunkError::Partial {
+            len: remainder,
+            chunk_size,
+        });
+    }
+    let keep = match partial {
+        PartialChunk::Drop => data.len() - remainder,
+        PartialChunk::Deliver | PartialChunk::Error => data.len(),
+    };
+    Ok(data[..keep].chunks(chunk_size))
+}
+
+/// Appelle `f` sur chaque morceau de `chunk_size` bytes de `data`, dans l'ordre ; le dernier
+/// morceau est plus court si la taille de `data` n'est pas un multiple de `chunk_size`.
+///
+/// # Errors
+///
+/// Renvoie [`ChunkError::ZeroSize`] si `chunk_size` vaut 0 ; `f` n'est alors pas appelée.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::process_data_chunked;
+///
+/// let mut records = Vec::new();
+/// process_data_chunked(&[1, 2, 3, 4, 5], 2, |record| records.push(record.to_vec())).unwrap();
+/// assert_eq!(records, vec![vec![1, 2], vec![3, 4], vec![5]]);
+/// ```
+pub fn process_data_chunked(
+    data: &[u8],
+    chunk_size: usize,
+    f: impl FnMut(&[u8]),
+) -> Result<(), ChunkError> {
+    split(data, chunk_size, PartialChunk::Deliver)?.for_each(f);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Découpe `data` et renvoie les morceaux, ou l'erreur.
+    fn chunks(
+        data: &[u8],
+        chunk_size: usize,
+        partial: PartialChunk,
+    ) -> Result<Vec<Vec<u8>>, ChunkError> {
+        Ok(split(data, chunk_size, partial)?
+            .map(<[u8]>::to_vec)
+            .collect())
+    }
+
+    /// Teste un multiple exact, qui ne dépend pas de la politique du dernier morceau.
+    #[test]
+    fn test_exact_multiple() {
+        for partial in [
+            PartialChunk::Deliver,
+            PartialChunk::Drop,
+            PartialChunk::Error,
+        ] {
+            assert_eq!(
+                chunks(&[1, 2, 3, 4], 2, partial),
+                Ok(vec![vec![1, 2], vec![3, 4]])
+            );
+        }
+    }
+
+    /// Teste chaque politique sur un dernier morceau incomplet.
+    #[test]
+    fn test_trailing_remainder() {
+        let data = [1, 2, 3, 4, 5];
+        assert_eq!(
+            chunks(&data, 2, PartialChunk::Deliver),
+            Ok(vec![vec![1, 2], vec![3, 4], vec![5]])
+        );
+        assert_eq!(
+            chunks(&data, 2, PartialChunk::Drop),
+            Ok(vec![vec![1, 2], vec![3, 4]])
+        );
+        assert_eq!(
+            chunks(&data, 2, PartialChunk::Error),
+            Err(ChunkError::Partial {
+                len: 1,
+                chunk_size: 2
+            })
+        );
+    }
+
+    /// Teste des données plus courtes qu'un morceau, et le refus d'une taille nulle.
+    #[test]
+    fn test_smaller_than_chunk_and_zero_size() {
+        assert_eq!(
+            chunks(&[1, 2], 4, PartialChunk::Deliver),
+            Ok(vec![vec![1, 2]])
+        );
+        assert_eq!(chunks(&[1, 2], 4, PartialChunk::Drop), Ok(vec![]));
+        assert_eq!(
+            process_data_chunked(&[1, 2], 0, |_| panic!("morceau inattendu")),
+            Err(ChunkError::ZeroSize)
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Compression RLE des données, activée par la feature `compression`.
+//!
+//! Les données compressées commencent par l'en-tête `RLE\x01` suivi de la taille des données
+//! d'origine sur 4 bytes big-endian. Le corps est une suite de blocs ouverts par un byte de
+//! contrôle `n` :
+//!
+//! - `n < 128` : les `n + 1` bytes suivants sont copiés tels quels ;
+//! - `n >= 128` : le byte suivant est répété `n - 125` fois, soit de 3 à 130 fois.
+
+use crate::error::ValidationError;
+use crate::registry::Transform;
+
+/// Octets qui ouvrent des données compressées, version du format comprise.
+const MAGIC: &[u8; 4] = b"RLE\x01";
+
+/// Taille de l'en-tête des données compressées.
+const HEADER_LEN: usize = MAGIC.len() + 4;
+
+/// Nombre maximal de bytes d'un bloc copié tel quel.
+const MAX_LITERAL: usize = 128;
+
+/// Longueur minimale d'une répétition codée comme telle.
+const MIN_RUN: usize = 3;
+
+/// Longueur maximale d'une répétition codée en un bloc.
+const MAX_RUN: usize = 130;
+
+/// Tailles d'origine et compressée de données compressées par [`Transform::compress`].
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CompressionInfo, Transform};
+///
+/// let compressed = Transform::compress(6).apply(&[0; 1000]).unwrap();
+/// let info = CompressionInfo::of(&compressed).unwrap();
+/// assert_eq!(info.original_len, 1000);
+/// assert_eq!(info.compressed_len, compressed.len());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionInfo {
+    pub original_len: usize,   // Taille des données d'origine.
+    pub compressed_len: usize, // Taille des données compressées, en-tête compris.
+}
+
+impl CompressionInfo {
+    /// Lit les tailles dans l'en-tête de `compressed`, ou renvoie `None` si `compressed` ne
+    /// commence pas par l'en-tête de données compressées.
+    pub fn of(compressed: &[u8]) -> Option<Self> {
+        let (magic, rest) = compressed.split_first_chunk::<4>()?;
+        let (len, _) = rest.split_first_chunk::<4>()?;
+        (magic == MAGIC).then(|| CompressionInfo {
+            original_len: u32::from_be_bytes(*len) as usize,
+            compressed_len: compressed.len(),
+        })
+    }
+}
+
+/// Compresse `data` ; `level` 0 copie les données sans coder les répétitions.
+fn compress(data: &[u8], level: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + data.len() + data.len() / MAX_LITERAL + 1);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < data.len() {
+        let run = data[i..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|&&byte| byte == data[i])
+            .count();
+        if level > 0 && run >= MIN_RUN {
+            push_literals(&mut out, &data[literal_start..i]);
+            out.push((run - MIN_RUN + 128) as u8);
+            out.push(data[i]);
+            i += run;
+            literal_start = i;
+        } else {
+            i += 1;
+        }
+    }
+    push_literals(&mut out, &data[literal_start..]);
+    out
+}
+
+/// Ajoute `literals` à `out` en blocs copiés tels quels.
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for block in literals.chunks(MAX_LITERAL) {
+        out.push((block.len() - 1) as u8);
+        out.extend_from_slice(block);
+    }
+}
+
+/// Décompresse des données écrites par [`compress`].
+fn decompress(compressed: &[u8]) -> Result<Vec<u8>, ValidationError> {
+    let invalid =
+        |reason: &str| ValidationError::new(format!("données compressées invalides : {}", reason));
+    let info = CompressionInfo::of(compressed).ok_or_else(|| invalid("en-tête absent"))?;
+    let body = &compressed[HEADER_LEN..];
+    // La taille annoncée vient des données : la réservation est bornée par ce que le corps peut
+    // produire, au plus `MAX_RUN` bytes par byte lu.
+    let mut out = Vec::with_capacity(info.original_len.min(body.len() * MAX_RUN));
+    let mut body = body;
+    while let Some((&control, rest)) = body.split_first() {
+        let control = usize::from(control);
+        let (block, rest) = if control < 128 {
+            rest.split_at_checked(control + 1)
+                .ok_or_else(|| invalid("bloc tronqué"))?
+        } else {
+            let (&byte, rest) = rest
+                .split_first()
+                .ok_or_else(|| invalid("répétition tronquée"))?;
+            out.resize(out.len() + control - 128 + MIN_RUN, byte);
+            (&[][..], rest)
+        };
+        out.extend_from_slice(block);
+        if out.len() > info.original_len {
+            return Err(invalid("plus de bytes que la taille annoncée"));
+        }
+        body = rest;
+    }
+    if out.len() != info.original_len {
+        return Err(invalid("moins de bytes que la taille annoncée"));
+    }
+    Ok(out)
+}
+
+impl Transform {
+    /// Crée une transformation qui compresse les données par RLE (codage des répétitions), par
+    /// exemple avant de les confier à un callback de stockage.
+    ///
+    /// `level` 0 copie les données sans les compresser ; les autres niveaux codent les
+    /// répétitions d'au moins 3 bytes. Quelle que soit l'entrée, les données compressées
+    /// dépassent l'original d'au plus 8 bytes plus 1 byte par tranche de 128. Leur en-tête
+    /// donne les deux tailles, voir [`CompressionInfo`].
+    pub fn compress(level: u32) -> Self {
+        Transform::new(move |data: &[u8]| compress(data, level))
+    }
+
+    /// Crée une transformation qui décompresse les données compressées par
+    /// [`compress`](Self::compress). Des données invalides sont refusées comme par un
+    /// validateur.
+    pub fn decompress() -> Self {
+        Transform::fallible(decompress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::{CallbackHost, CallbackRegistry};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Compresse puis décompresse `data` au niveau `level`.
+    fn round_trip(data: &[u8], level: u32) -> Vec<u8> {
+        let compressed = Transform::compress(level).apply(data).unwrap();
+        let info = CompressionInfo::of(&compressed).unwrap();
+        assert_eq!(info.original_len, data.len());
+        Transform::decompress().apply(&compressed).unwrap()
+    }
+
+    /// Teste l'aller-retour sur des données répétitives, qui rétrécissent.
+    #[test]
+    fn test_round_trip_shrinks_repetitive_data() {
+        let json = br#"{"valeurs": [0, 0, 0, 0, 0, 0, 0, 0],          "fin": true}"#.repeat(50);
+        let mut data = vec![7; 1000];
+        data.extend_from_slice(&json);
+        for level in [0, 1, 9] {
+            assert_eq!(round_trip(&data, level), data);
+        }
+        let compressed = Transform::compress(6).apply(&data).unwrap();
+        // Les 1000 premiers bytes tiennent en 8 répétitions de 2 bytes.
+        assert!(compressed.len() < data.len() - 900);
+        assert_eq!(round_trip(&[], 6), Vec::<u8>::new());
+    }
+
+    /// Teste que des données incompressibles ne sont ni corrompues ni trop agrandies.
+    #[test]
+    fn test_incompressible_data_is_preserved() {
+        // Suite pseudo-aléatoire sans répétition de 3 bytes.
+        let mut state = 0x2545_f491u32;
+        let data: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        let compressed = Transform::compress(9).apply(&data).unwrap();
+        assert!(compressed.len() <= HEADER_LEN + data.len() + data.len().div_ceil(MAX_LITERAL));
+        assert_eq!(round_trip(&data, 9), data);
+        // Les répétitions à la limite d'un bloc.
+        let edges = [vec![1; MAX_RUN + 1], vec![2; MAX_LITERAL + 3], vec![0, 0]].concat();
+        assert_eq!(round_trip(&edges, 9), edges);
+    }
+
+    /// Teste qu'une décompression ratée passe par le chemin d'erreur du registre.
+    #[test]
+    fn test_decompression_failure_reports_invalid() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let mut compressed = Transform::compress(6).apply(&[5; 10]).unwrap();
+        compressed.pop();
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(compressed);
+        registry.add_transform(Transform::decompress());
+        let errors_in_handler = Rc::clone(&errors);
+        registry.set_on_invalid(move |error: &ValidationError| {
+            errors_in_handler.borrow_mut().push(error.reason.clone())
+        });
+        registry.set_callback(|_data: &CallbackPayload| panic!("données invalides transmises"));
+
+        registry.do_something();
+        registry.set_data("pas compressé".as_bytes().to_vec());
+        registry.do_something();
+
+        assert_eq!(
+            *errors.borrow(),
+            vec![
+                "données compressées invalides : répétition tronquée",
+                "données compressées invalides : en-tête absent",
+            ]
+        );
+    }
+
+    /// Teste qu'une taille annoncée démesurée, sans corps, est refusée sans réserver la mémoire
+    /// annoncée.
+    #[test]
+    fn test_oversized_header_is_rejected() {
+        let error = decompress(b"RLE\x01\xff\xff\xff\xff").unwrap_err();
+        assert_eq!(
+            error.reason,
+            "données compressées invalides : moins de bytes que la taille annoncée"
+        );
+
+        let mut lying = b"RLE\x01\xff\xff\xff\xff".to_vec();
+        lying.extend_from_slice(&[255, 7]);
+        let error = decompress(&lying).unwrap_err();
+        assert_eq!(
+            error.reason,
+            "données compressées invalides : moins de bytes que la taille annoncée"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Encodage des données en hexadécimal et en base64, pour les protocoles textuels.
+
+use super::{CallbackPayload, CallbackPayloadBuf};
+use crate::error::DecodeError;
+
+/// Alphabet base64 standard (RFC 4648).
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Chiffres hexadécimaux minuscules.
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encode `bytes` en hexadécimal minuscule.
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(HEX_DIGITS[usize::from(byte >> 4)] as char);
+        hex.push(HEX_DIGITS[usize::from(byte & 0x0f)] as char);
+    }
+    hex
+}
+
+/// Décode la chaîne hexadécimale `hex`, minuscules et majuscules acceptées.
+pub(crate) fn decode_hex(hex: &str) -> Result<Vec<u8>, DecodeError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(DecodeError::OddLength(hex.len()));
+    }
+    let digit = |index: usize| {
+        let character = hex[index..].chars().next().unwrap_or_default();
+        character
+            .to_digit(16)
+            .ok_or(DecodeError::InvalidCharacter { character, index })
+    };
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            if !hex.is_char_boundary(index + 1) {
+                // Un caractère non ASCII chevauche la paire.
+                return Err(DecodeError::InvalidCharacter {
+                    character: hex[index..].chars().next().unwrap_or_default(),
+                    index,
+                });
+            }
+            Ok((digit(index)? * 16 + digit(index + 1)?) as u8)
+        })
+        .collect()
+}
+
+/// Encode `bytes` en base64 standard, avec remplissage `=`.
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let word = group.iter().enumerate().fold(0u32, |word, (i, &byte)| {
+            word | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= group.len() {
+                text.push(BASE64_ALPHABET[(word >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Décode le texte base64 standard `text`, dont le remplissage `=` est obligatoire.
+pub(crate) fn decode_base64(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let input = text.as_bytes();
+    if !input.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidLength(input.len()));
+    }
+    let padding = input.iter().rev().take_while(|&&byte| byte == b'=').count();
+    if padding > 2 {
+        return Err(DecodeError::BadPadding);
+    }
+    let mut bytes = Vec::with_capacity(input.len() / 4 * 3);
+    for (group_index, group) in input.chunks(4).enumerate() {
+        let last = group_index == input.len() / 4 - 1;
+        let significant = if last { 4 - padding } else { 4 };
+        let mut word = 0u32;
+        for (i, &byte) in group[..significant].iter().enumerate() {
+            let index = group_index * 4 + i;
+            let value = match BASE64_ALPHABET.iter().position(|&digit| digit == byte) {
+                Some(value) => value as u32,
+                None if byte == b'=' => return Err(DecodeError::BadPadding),
+                None => {
+                    return Err(DecodeError::InvalidCharacter {
+                        character: text[index..].chars().next().unwrap_or_default(),
+                        index,
+                    })
+                }
+            };
+            word |= value << (18 - 6 * i);
+        }
+        let decoded = [(word >> 16) as u8, (word >> 8) as u8, word as u8];
+        let len = significant - 1;
+        // Les bits qui suivent le dernier byte doivent être nuls.
+        if decoded[len..].iter().any(|&byte| byte != 0) {
+            return Err(DecodeError::BadPadding);
+        }
+        bytes.extend_from_slice(&decoded[..len]);
+    }
+    Ok(bytes)
+}
+
+impl CallbackPayload {
+    /// Encode les données en hexadécimal minuscule, deux chiffres par byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let data = CallbackPayload::new(&[0x01, 0xab]);
+    /// assert_eq!(data.to_hex(), "01ab");
+    /// assert_eq!(CallbackPayload::from_hex("01AB").unwrap().as_bytes(), data.as_bytes());
+    /// ```
+    pub fn to_hex(&self) -> String {
+        encode_hex(self.as_bytes())
+    }
+
+    /// Encode les données en base64 standard, avec remplissage `=`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let data = CallbackPayload::new(b"reven");
+    /// assert_eq!(data.to_base64(), "cmV2ZW4=");
+    /// assert_eq!(CallbackPayload::from_base64("cmV2ZW4=").unwrap().as_bytes(), b"reven");
+    /// ```
+    pub fn to_base64(&self) -> String {
+        encode_base64(self.as_bytes())
+    }
+
+    /// Décode des données écrites en hexadécimal, minuscules et majuscules acceptées.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`DecodeError::OddLength`] si `hex` a un nombre impair de chiffres, et
+    /// [`DecodeError::InvalidCharacter`] pour un caractère qui n'est pas un chiffre hexadécimal.
+    pub fn from_hex(hex: &str) -> Result<CallbackPayloadBuf, DecodeError> {
+        decode_hex(hex).map(CallbackPayloadBuf::new)
+    }
+
+    /// Décode des données écrites en base64 standard, remplissage `=` compris.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`DecodeError::InvalidLength`] si la taille de `text` n'est pas un multiple de 4,
+    /// [`DecodeError::InvalidCharacter`] pour un caractère hors de l'alphabet base64, et
+    /// [`DecodeError::BadPadding`] si le remplissage est mal placé ou incohérent.
+    pub fn from_base64(text: &str) -> Result<CallbackPayloadBuf, DecodeError> {
+        decode_base64(text).map(CallbackPayloadBuf::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste l'aller-retour en hexadécimal et en base64 pour chaque reste de la division par 3.
+    #[test]
+    fn test_round_trips() {
+        let samples: [&[u8]; 5] = [b"", b"f", b"fo", b"foo", &[0x00, 0xff, 0x80, 0x7f]];
+        let base64 = ["", "Zg==", "Zm8=", "Zm9v", "AP+Afw=="];
+        for (bytes, text) in samples.iter().zip(base64) {
+            let data = CallbackPayload::new(bytes);
+            assert_eq!(data.to_base64(), text);
+            assert_eq!(
+                CallbackPayload::from_base64(text).unwrap().as_bytes(),
+                *bytes
+            );
+            assert_eq!(
+                CallbackPayload::from_hex(&data.to_hex())
+                    .unwrap()
+                    .as_bytes(),
+                *bytes
+            );
+        }
+        assert_eq!(CallbackPayload::new(&[0x0a, 0xff]).to_hex(), "0aff");
+    }
+
+    /// Teste les erreurs sur de l'hexadécimal invalide.
+    #[test]
+    fn test_malformed_hex() {
+        assert_eq!(
+            CallbackPayload::from_hex("abc"),
+            Err(DecodeError::OddLength(3))
+        );
+        assert_eq!(
+            CallbackPayload::from_hex("0g"),
+            Err(DecodeError::InvalidCharacter {
+                character: 'g',
+                index: 1
+            })
+        );
+        assert_eq!(
+            CallbackPayload::from_hex("+a"),
+            Err(DecodeError::InvalidCharacter {
+                character: '+',
+                index: 0
+            })
+        );
+        assert_eq!(
+            CallbackPayload::from_hex("é"),
+            Err(DecodeError::InvalidCharacter {
+                character: 'é',
+                index: 0
+            })
+        );
+    }
+
+    /// Teste les erreurs sur du base64 invalide : taille, caractères et remplissage.
+    #[test]
+    fn test_malformed_base64() {
+        assert_eq!(
+            CallbackPayload::from_base64("Zg="),
+            Err(DecodeError::InvalidLength(3))
+        );
+        assert_eq!(
+            CallbackPayload::from_base64("Zm9-"),
+            Err(DecodeError::InvalidCharacter {
+                character: '-',
+                index: 3
+            })
+        );
+        for bad_padding in ["Z===", "Zg==Zm9v", "Z=g=", "Zh=="] {
+            assert_eq!(
+                CallbackPayload::from_base64(bad_padding),
+                Err(DecodeError::BadPadding),
+                "{}",
+                bad_padding
+            );
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Affichage des données en hexadécimal, en ASCII ou sous forme de dump hexadécimal.
+
+use std::fmt::{self, Write};
+
+use super::{ArcCallbackPayload, CallbackPayload, CallbackPayloadBuf};
+
+/// Nombre de bytes par ligne de [`CallbackPayload::hex_dump`].
+const DUMP_WIDTH: usize = 16;
+
+/// Représentation choisie par un [`PayloadFormatter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Hex,   // Bytes en hexadécimal, séparés par des espaces.
+    Ascii, // Caractères ASCII imprimables, `.` pour les autres.
+}
+
+/// Affiche des données selon la représentation choisie via [`CallbackPayload::fmt_hex`] ou
+/// [`CallbackPayload::fmt_ascii`].
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadFormatter<'a> {
+    bytes: &'a [u8], // Les bytes à afficher.
+    style: Style,    // Représentation des bytes.
+}
+
+impl fmt::Display for PayloadFormatter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.style {
+            Style::Hex => write_hex(f, self.bytes),
+            Style::Ascii => write_ascii(f, self.bytes),
+        }
+    }
+}
+
+/// Écrit `bytes` en hexadécimal, séparés par des espaces.
+fn write_hex(out: &mut impl Write, bytes: &[u8]) -> fmt::Result {
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            out.write_char(' ')?;
+        }
+        write!(out, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+/// Écrit `bytes` en ASCII, en remplaçant les bytes non imprimables par `.`.
+fn write_ascii(out: &mut impl Write, bytes: &[u8]) -> fmt::Result {
+    for &byte in bytes {
+        let printable = byte.is_ascii_graphic() || byte == b' ';
+        out.write_char(if printable { byte as char } else { '.' })?;
+    }
+    Ok(())
+}
+
+impl CallbackPayload {
+    /// Renvoie un affichage des données en hexadécimal, identique à `Display`.
+    pub fn fmt_hex(&self) -> PayloadFormatter<'_> {
+        PayloadFormatter {
+            bytes: self.as_bytes(),
+            style: Style::Hex,
+        }
+    }
+
+    /// Renvoie un affichage des données en ASCII, où les bytes non imprimables deviennent `.`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let data = CallbackPayload::new(b"ok\n");
+    /// assert_eq!(data.to_string(), "6f 6b 0a");
+    /// assert_eq!(data.fmt_ascii().to_string(), "ok.");
+    /// ```
+    pub fn fmt_ascii(&self) -> PayloadFormatter<'_> {
+        PayloadFormatter {
+            bytes: self.as_bytes(),
+            style: Style::Ascii,
+        }
+    }
+
+    /// Renvoie un dump hexadécimal des données : pour chaque ligne de 16 bytes, la position du
+    /// premier byte, les bytes en hexadécimal puis en ASCII. Renvoie une chaîne vide pour des
+    /// données vides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let data = CallbackPayload::new(b"Hello");
+    /// assert_eq!(
+    ///     data.hex_dump(),
+    ///     "00000000  48 65 6c 6c 6f                                    |Hello|\n"
+    /// );
+    /// ```
+    pub fn hex_dump(&self) -> String {
+        let mut out = String::new();
+        for (line, chunk) in self.as_bytes().chunks(DUMP_WIDTH).enumerate() {
+            let (left, right) = chunk.split_at(chunk.len().min(DUMP_WIDTH / 2));
+            let mut hex = String::new();
+            // L'écriture dans une `String` n'échoue pas.
+            let _ = write_hex(&mut hex, left);
+            if !right.is_empty() {
+                hex.push_str("  ");
+                let _ = write_hex(&mut hex, right);
+            }
+            let _ = write!(out, "{:08x}  {:<48}  |", line * DUMP_WIDTH, hex);
+            let _ = write_ascii(&mut out, chunk);
+            out.push_str("|\n");
+        }
+        out
+    }
+}
+
+/// Affiche les bytes en hexadécimal, séparés par des espaces : `01 02 03`.
+impl fmt::Display for CallbackPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.as_bytes())
+    }
+}
+
+/// Affiche les bytes en hexadécimal, comme [`CallbackPayload`].
+impl fmt::Display for CallbackPayloadBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.as_bytes())
+    }
+}
+
+/// Affiche les bytes en hexadécimal, comme [`CallbackPayload`].
+impl fmt::Display for ArcCallbackPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Teste l'affichage hexadécimal compact.
+    #[test]
+    fn test_display_is_compact_hex() {
+        let data = CallbackPayload::new(&[1, 2, 3, 0xAB]);
+        assert_eq!(data.to_string(), "01 02 03 ab");
+        assert_eq!(data.fmt_hex().to_string(), "01 02 03 ab");
+        assert_eq!(data.to_owned().to_string(), "01 02 03 ab");
+        let shared = ArcCallbackPayload::new(Arc::from(&[0xFFu8][..]));
+        assert_eq!(shared.to_string(), "ff");
+    }
+
+    /// Teste que les données vides donnent des affichages vides.
+    #[test]
+    fn test_empty_data() {
+        let data = CallbackPayload::new(&[]);
+        assert_eq!(data.to_string(), "");
+        assert_eq!(data.fmt_ascii().to_string(), "");
+        assert_eq!(data.hex_dump(), "");
+    }
+
+    /// Teste que les bytes non imprimables sont affichés `.` en ASCII.
+    #[test]
+    fn test_ascii_replaces_non_printable() {
+        let data = CallbackPayload::new(b"a b\x00\x7f\xff~");
+        assert_eq!(data.fmt_ascii().to_string(), "a b...~");
+    }
+
+    /// Teste le dump d'une ligne complète suivie d'une ligne partielle.
+    #[test]
+    fn test_hex_dump_partial_last_line() {
+        let bytes: Vec<u8> = (0x41..0x41 + 20).collect();
+        let data = CallbackPayload::new(&bytes);
+        assert_eq!(
+            data.hex_dump(),
+            "00000000  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|\n\
+             00000010  51 52 53 54                                       |QRST|\n"
+        );
+    }
+
+    /// Teste le dump de deux lignes complètes contenant des bytes non imprimables.
+    #[test]
+    fn test_hex_dump_full_lines() {
+        let bytes: Vec<u8> = (0..32).collect();
+        let dump = CallbackPayload::new(&bytes).hex_dump();
+        assert_eq!(
+            dump,
+            "00000000  00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f  |................|\n\
+             00000010  10 11 12 13 14 15 16 17  18 19 1a 1b 1c 1d 1e 1f  |................|\n"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Traitement des données une fois par appel, après les callbacks.
+
+use super::process_data;
+use crate::error::ProcessError;
+
+/// Traitement des données appelé une fois par appel à `do_something`, après tous les callbacks,
+/// voir [`CallbackRegistry::set_processor`](crate::CallbackRegistry::set_processor).
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{DataProcessor, ProcessError};
+///
+/// /// Compte les bytes traités.
+/// struct ByteCounter(usize);
+///
+/// impl DataProcessor for ByteCounter {
+///     fn process(&mut self, data: &[u8]) -> Result<(), ProcessError> {
+///         self.0 += data.len();
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait DataProcessor {
+    /// Traite les données transmises aux callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie une [`ProcessError`] si le traitement a échoué ; elle est transmise au gestionnaire
+    /// de [`set_on_process_error`](crate::CallbackRegistry::set_on_process_error).
+    fn process(&mut self, data: &[u8]) -> Result<(), ProcessError>;
+}
+
+/// Toute closure `FnMut(&[u8]) -> Result<(), ProcessError>` est un `DataProcessor`.
+impl<F: FnMut(&[u8]) -> Result<(), ProcessError>> DataProcessor for F {
+    fn process(&mut self, data: &[u8]) -> Result<(), ProcessError> {
+        self(data)
+    }
+}
+
+/// `DataProcessor` qui affiche les données avec [`process_data`], comme le faisait le registre
+/// avant qu'on puisse choisir son traitement.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrintProcessor;
+
+impl DataProcessor for PrintProcessor {
+    fn process(&mut self, data: &[u8]) -> Result<(), ProcessError> {
+        process_data(data);
+        Ok(())
+    }
+}
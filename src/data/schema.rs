@@ -0,0 +1,239 @@
+//! Schémas : lecture des données comme un enregistrement de champs entiers nommés.
+
+use std::borrow::Cow;
+
+use crate::error::DecodeError;
+
+/// Type d'un champ de [`Schema`] : sa taille et son ordre des bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    U8,
+    U16Be,
+    U16Le,
+    U32Be,
+    U32Le,
+    U64Be,
+    U64Le,
+}
+
+impl FieldKind {
+    /// Renvoie la taille du champ en bytes.
+    fn size(self) -> usize {
+        match self {
+            FieldKind::U8 => 1,
+            FieldKind::U16Be | FieldKind::U16Le => 2,
+            FieldKind::U32Be | FieldKind::U32Le => 4,
+            FieldKind::U64Be | FieldKind::U64Le => 8,
+        }
+    }
+
+    /// Lit le champ dans `bytes`, qui en a exactement la taille.
+    fn read(self, bytes: &[u8]) -> u64 {
+        let big_endian = matches!(self, FieldKind::U16Be | FieldKind::U32Be | FieldKind::U64Be);
+        let fold = |value: u64, byte: &u8| value << 8 | u64::from(*byte);
+        if big_endian {
+            bytes.iter().fold(0, fold)
+        } else {
+            bytes.iter().rev().fold(0, fold)
+        }
+    }
+}
+
+/// Description des données comme une suite de champs entiers nommés, à partir du byte 0.
+///
+/// Les bytes qui suivent le dernier champ sont ignorés.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::Schema;
+///
+/// let schema = Schema::new().u8("op").u16_be("id");
+/// let record = schema.decode(&[1, 2, 3]).unwrap();
+/// assert_eq!(record.get("op"), Ok(1));
+/// assert_eq!(record.get("id"), Ok(0x0203));
+/// assert!(record.get("len").is_err());
+/// assert!(schema.decode(&[1, 2]).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    fields: Vec<(Cow<'static, str>, FieldKind)>, // Champs, dans l'ordre des données.
+}
+
+/// Déclare, pour chaque type de champ, la méthode de `Schema` qui l'ajoute.
+macro_rules! field_builders {
+    ($($name:ident => $kind:ident, $ty:literal, $order:literal;)+) => {
+        $(
+            #[doc = concat!("Ajoute à la suite un champ `", $ty, "`", $order, " nommé `name`.")]
+            ///
+            /// # Panics
+            ///
+            /// Panique si un champ de ce nom est déjà déclaré.
+            pub fn $name(self, name: impl Into<Cow<'static, str>>) -> Self {
+                self.field(name.into(), FieldKind::$kind)
+            }
+        )+
+    };
+}
+
+impl Schema {
+    /// Crée un schéma sans champ.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    field_builders! {
+        u8 => U8, "u8", "";
+        u16_be => U16Be, "u16", " gros-boutiste";
+        u16_le => U16Le, "u16", " petit-boutiste";
+        u32_be => U32Be, "u32", " gros-boutiste";
+        u32_le => U32Le, "u32", " petit-boutiste";
+        u64_be => U64Be, "u64", " gros-boutiste";
+        u64_le => U64Le, "u64", " petit-boutiste";
+    }
+
+    /// Ajoute le champ `name` de type `kind` à la suite.
+    fn field(mut self, name: Cow<'static, str>, kind: FieldKind) -> Self {
+        assert!(
+            self.position(&name).is_none(),
+            "le champ `{}` est déjà déclaré",
+            name
+        );
+        self.fields.push((name, kind));
+        self
+    }
+
+    /// Renvoie la position du champ `name`, s'il est déclaré.
+    fn position(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|(field, _)| field == name)
+    }
+
+    /// Renvoie le nombre de bytes lus par le schéma.
+    pub fn len(&self) -> usize {
+        self.fields.iter().map(|(_, kind)| kind.size()).sum()
+    }
+
+    /// Indique si le schéma ne déclare aucun champ.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Lit les champs du schéma dans `data`.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`DecodeError::TooShort`] pour le premier champ qui dépasse la fin de `data`.
+    pub fn decode(&self, data: &[u8]) -> Result<Record<'_>, DecodeError> {
+        let mut offset = 0;
+        let values = self
+            .fields
+            .iter()
+            .map(|(name, kind)| {
+                let end = offset + kind.size();
+                let bytes = data.get(offset..end).ok_or_else(|| DecodeError::TooShort {
+                    field: name.to_string(),
+                    needed: end,
+                    len: data.len(),
+                })?;
+                offset = end;
+                Ok(kind.read(bytes))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Record {
+            schema: self,
+            values,
+        })
+    }
+}
+
+/// Données lues avec un [`Schema`] : la valeur de chacun de ses champs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'s> {
+    schema: &'s Schema, // Schéma qui nomme les valeurs.
+    values: Vec<u64>,   // Valeurs des champs, dans l'ordre du schéma.
+}
+
+impl<'s> Record<'s> {
+    /// Renvoie la valeur du champ `name`.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`DecodeError::UnknownField`] si le schéma ne déclare aucun champ `name`.
+    pub fn get(&self, name: &str) -> Result<u64, DecodeError> {
+        self.schema
+            .position(name)
+            .map(|index| self.values[index])
+            .ok_or_else(|| DecodeError::UnknownField(name.to_string()))
+    }
+
+    /// Itère sur les noms et les valeurs des champs, dans l'ordre du schéma.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&'s str, u64)> + '_ {
+        self.schema
+            .fields
+            .iter()
+            .zip(&self.values)
+            .map(|((name, _), value)| (name.as_ref(), *value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste la lecture de tous les types de champs, dans les deux ordres des bytes.
+    #[test]
+    fn test_decode_every_kind() {
+        let schema = Schema::new()
+            .u8("op")
+            .u16_be("a")
+            .u16_le("b")
+            .u32_be("c")
+            .u32_le("d")
+            .u64_be("e")
+            .u64_le("f");
+        assert_eq!(schema.len(), 29);
+        let mut data = vec![9, 0x01, 0x02, 0x01, 0x02, 1, 2, 3, 4, 1, 2, 3, 4];
+        data.extend([1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 5, 6, 7, 8, 0xff]);
+
+        let record = schema.decode(&data).unwrap();
+        let fields: Vec<_> = record.iter().collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("op", 9),
+                ("a", 0x0102),
+                ("b", 0x0201),
+                ("c", 0x0102_0304),
+                ("d", 0x0403_0201),
+                ("e", 0x0102_0304_0506_0708),
+                ("f", 0x0807_0605_0403_0201),
+            ]
+        );
+    }
+
+    /// Teste les erreurs des données trop courtes et des champs inconnus.
+    #[test]
+    fn test_short_payload_and_unknown_field() {
+        let schema = Schema::new().u8("op").u16_be("id");
+        assert_eq!(
+            schema.decode(&[1, 2]),
+            Err(DecodeError::TooShort {
+                field: "id".to_string(),
+                needed: 3,
+                len: 2
+            })
+        );
+        let record = schema.decode(&[1, 2, 3]).unwrap();
+        assert_eq!(
+            record.get("size"),
+            Err(DecodeError::UnknownField("size".to_string()))
+        );
+    }
+
+    /// Teste qu'un nom de champ ne peut pas être déclaré deux fois.
+    #[test]
+    #[should_panic(expected = "déjà déclaré")]
+    fn test_duplicate_field_panics() {
+        let _ = Schema::new().u8("op").u16_be("op");
+    }
+}
@@ -0,0 +1,170 @@
+//! Sérialisation des données avec serde, activée par la feature `serde`.
+//!
+//! Les bytes sont écrits d'un bloc : en hexadécimal dans les formats lisibles comme JSON, tels
+//! quels dans les formats binaires.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::encoding::{decode_hex, encode_hex};
+use super::{ArcCallbackPayload, CallbackPayload, CallbackPayloadBuf};
+
+/// Sérialise `bytes` en hexadécimal pour un format lisible, d'un bloc sinon.
+pub(crate) fn serialize_bytes<S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode_hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// Reconstruit des bytes depuis une chaîne hexadécimale, un bloc de bytes ou une liste d'entiers.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("des bytes ou une chaîne hexadécimale")
+    }
+
+    fn visit_str<E: de::Error>(self, hex: &str) -> Result<Vec<u8>, E> {
+        decode_hex(hex).map_err(|_| E::invalid_value(de::Unexpected::Str(hex), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Désérialise des bytes écrits par [`serialize_bytes`].
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(BytesVisitor)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+impl Serialize for CallbackPayload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_bytes(), serializer)
+    }
+}
+
+impl Serialize for CallbackPayloadBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CallbackPayloadBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bytes(deserializer).map(CallbackPayloadBuf::new)
+    }
+}
+
+impl Serialize for ArcCallbackPayload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ArcCallbackPayload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bytes(deserializer).map(|bytes| ArcCallbackPayload::new(Arc::from(bytes)))
+    }
+}
+
+impl CallbackPayload {
+    /// Sérialise les données en JSON, sous forme de chaîne hexadécimale.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie l'erreur de `serde_json`, ce qui n'arrive pas pour des bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::CallbackPayload;
+    ///
+    /// let json = CallbackPayload::new(&[0x01, 0xab]).to_json().unwrap();
+    /// assert_eq!(json, "\"01ab\"");
+    /// assert_eq!(CallbackPayload::from_json(&json).unwrap().as_bytes(), &[0x01, 0xab]);
+    /// ```
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Reconstruit des données possédées depuis le JSON écrit par [`to_json`](Self::to_json).
+    ///
+    /// Une liste d'entiers, comme `[1, 171]`, est aussi acceptée.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie l'erreur de `serde_json` si `json` n'est pas une chaîne hexadécimale valide.
+    pub fn from_json(json: &str) -> Result<CallbackPayloadBuf, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste l'aller-retour en JSON, données vides comprises.
+    #[test]
+    fn test_json_round_trip() {
+        for bytes in [&[][..], &[0, 1, 0x7f, 0x80, 0xff][..]] {
+            let json = CallbackPayload::new(bytes).to_json().unwrap();
+            assert_eq!(CallbackPayload::from_json(&json).unwrap().as_bytes(), bytes);
+        }
+        assert_eq!(CallbackPayload::new(&[]).to_json().unwrap(), "\"\"");
+    }
+
+    /// Teste l'aller-retour dans un format binaire, où les bytes sont écrits d'un bloc.
+    #[test]
+    fn test_binary_round_trip() {
+        for bytes in [&[][..], &[0xde, 0xad, 0xbe, 0xef][..]] {
+            let encoded = bincode::serialize(CallbackPayload::new(bytes)).unwrap();
+            // Longueur sur 8 bytes, puis les bytes eux-mêmes.
+            assert_eq!(encoded.len(), 8 + bytes.len());
+            let decoded: CallbackPayloadBuf = bincode::deserialize(&encoded).unwrap();
+            assert_eq!(decoded.as_bytes(), bytes);
+
+            let shared: ArcCallbackPayload = bincode::deserialize(&encoded).unwrap();
+            assert_eq!(bincode::serialize(&shared).unwrap(), encoded);
+        }
+    }
+
+    /// Teste qu'une liste d'entiers est acceptée et qu'une chaîne invalide est refusée.
+    #[test]
+    fn test_json_accepts_arrays_and_rejects_bad_hex() {
+        assert_eq!(
+            CallbackPayload::from_json("[1, 171]").unwrap().as_bytes(),
+            &[1, 171]
+        );
+        assert!(CallbackPayload::from_json("\"abc\"").is_err());
+        assert!(CallbackPayload::from_json("\"zz\"").is_err());
+        assert!(CallbackPayload::from_json("\"+a\"").is_err());
+        assert!(CallbackPayload::from_json("[256]").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Statistiques sur les données traitées, sans écrire de callback.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::DataProcessor;
+use crate::error::ProcessError;
+
+/// Statistiques accumulées par un [`StatsProcessor`].
+///
+/// Les totaux saturent à `u64::MAX` au lieu de déborder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataStats {
+    pub payloads: u64,           // Nombre de données traitées, vides comprises.
+    pub total_bytes: u64,        // Nombre total de bytes traités.
+    pub min_len: Option<usize>,  // Taille des plus petites données, `None` avant la première.
+    pub max_len: Option<usize>,  // Taille des plus grandes données, `None` avant la première.
+    pub byte_counts: [u64; 256], // Nombre d'occurrences de chaque valeur de byte.
+}
+
+impl DataStats {
+    /// Renvoie la taille moyenne des données, ou `None` avant la première.
+    pub fn mean_len(&self) -> Option<f64> {
+        (self.payloads > 0).then(|| self.total_bytes as f64 / self.payloads as f64)
+    }
+
+    /// Ajoute `data` aux statistiques.
+    fn record(&mut self, data: &[u8]) {
+        self.payloads = self.payloads.saturating_add(1);
+        self.total_bytes = self.total_bytes.saturating_add(data.len() as u64);
+        self.min_len = Some(self.min_len.map_or(data.len(), |min| min.min(data.len())));
+        self.max_len = Some(self.max_len.map_or(data.len(), |max| max.max(data.len())));
+        for &byte in data {
+            let count = &mut self.byte_counts[usize::from(byte)];
+            *count = count.saturating_add(1);
+        }
+    }
+}
+
+impl Default for DataStats {
+    fn default() -> Self {
+        DataStats {
+            payloads: 0,
+            total_bytes: 0,
+            min_len: None,
+            max_len: None,
+            byte_counts: [0; 256],
+        }
+    }
+}
+
+/// `DataProcessor` qui accumule des [`DataStats`] sur les données qui traversent un registre.
+///
+/// Les clones partagent les statistiques : on en installe un dans le registre et on garde
+/// l'autre pour les consulter.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackHost, CallbackRegistry, CallbackPayload, StatsProcessor};
+///
+/// let stats = StatsProcessor::new();
+/// let mut registry: CallbackRegistry<CallbackPayload> = CallbackRegistry::with_data(&[7u8, 7, 1][..]);
+/// registry.set_processor(Box::new(stats.clone()));
+/// registry.do_something();
+///
+/// let snapshot = stats.snapshot();
+/// assert_eq!(snapshot.total_bytes, 3);
+/// assert_eq!(snapshot.byte_counts[7], 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatsProcessor {
+    stats: Rc<RefCell<DataStats>>, // Statistiques partagées par les clones.
+}
+
+impl StatsProcessor {
+    /// Crée un `StatsProcessor` sans statistiques.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renvoie une copie des statistiques actuelles.
+    pub fn snapshot(&self) -> DataStats {
+        self.stats.borrow().clone()
+    }
+
+    /// Remet les statistiques à zéro.
+    pub fn reset(&self) {
+        *self.stats.borrow_mut() = DataStats::default();
+    }
+}
+
+impl DataProcessor for StatsProcessor {
+    fn process(&mut self, data: &[u8]) -> Result<(), ProcessError> {
+        self.stats.borrow_mut().record(data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste les statistiques exactes de données connues, données vides comprises.
+    #[test]
+    fn test_exact_statistics() {
+        let stats = StatsProcessor::new();
+        let mut processor = stats.clone();
+        assert_eq!(stats.snapshot().mean_len(), None);
+        for data in [&[1u8, 2, 2][..], &[], &[2, 255, 0, 0, 0]] {
+            processor.process(data).unwrap();
+        }
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.payloads, 3);
+        assert_eq!(snapshot.total_bytes, 8);
+        assert_eq!((snapshot.min_len, snapshot.max_len), (Some(0), Some(5)));
+        assert_eq!(snapshot.mean_len(), Some(8.0 / 3.0));
+        assert_eq!(snapshot.byte_counts[0], 3);
+        assert_eq!(snapshot.byte_counts[1], 1);
+        assert_eq!(snapshot.byte_counts[2], 3);
+        assert_eq!(snapshot.byte_counts[255], 1);
+        assert_eq!(snapshot.byte_counts.iter().sum::<u64>(), 8);
+
+        stats.reset();
+        assert_eq!(stats.snapshot(), DataStats::default());
+    }
+
+    /// Teste que les totaux saturent au lieu de déborder.
+    #[test]
+    fn test_totals_saturate() {
+        let mut stats = DataStats {
+            payloads: u64::MAX,
+            total_bytes: u64::MAX - 1,
+            ..DataStats::default()
+        };
+        stats.byte_counts[9] = u64::MAX;
+        stats.record(&[9, 9]);
+        assert_eq!(stats.payloads, u64::MAX);
+        assert_eq!(stats.total_bytes, u64::MAX);
+        assert_eq!(stats.byte_counts[9], u64::MAX);
+        assert_eq!((stats.min_len, stats.max_len), (Some(2), Some(2)));
+    }
+}
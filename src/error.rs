@@ -0,0 +1,500 @@
+//! Erreurs renvoyées par l'API publique de la crate.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::callback::CallbackId;
+
+/// Erreur renvoyée par [`CallbackRegistryBuilder::build`](crate::CallbackRegistryBuilder::build)
+/// lorsque la configuration du registre est incomplète.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// Aucune donnée n'a été fournie via `with_data`.
+    MissingData,
+    /// Plus de callbacks ont été ajoutés que la limite fixée par `with_max_callbacks`.
+    TooManyCallbacks(RegistryFull),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingData => write!(
+                f,
+                "impossible de construire le registre : aucune donnée fournie (appelez `with_data`)"
+            ),
+            BuildError::TooManyCallbacks(full) => {
+                write!(f, "impossible de construire le registre : {}", full)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// Erreur renvoyée par [`CallbackRegistry::set_named_callback`](crate::CallbackRegistry::set_named_callback)
+/// lorsqu'un callback porte déjà ce nom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateName {
+    pub name: String, // Le nom déjà utilisé.
+}
+
+impl fmt::Display for DuplicateName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "un callback nommé `{}` est déjà enregistré", self.name)
+    }
+}
+
+impl Error for DuplicateName {}
+
+/// Erreur renvoyée par [`CallbackRegistry::set_callback_limited`](crate::CallbackRegistry::set_callback_limited)
+/// lorsque le nombre maximal d'appels demandé est nul.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroLimit;
+
+impl fmt::Display for ZeroLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "le nombre maximal d'appels d'un callback doit être au moins 1"
+        )
+    }
+}
+
+impl Error for ZeroLimit {}
+
+/// Erreur transmise au gestionnaire de
+/// [`CallbackRegistry::set_on_rejected`](crate::CallbackRegistry::set_on_rejected), ou renvoyée par
+/// [`CallbackRegistry::try_set_data`](crate::CallbackRegistry::try_set_data), lorsque les données
+/// dépassent la taille maximale du registre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    pub size: usize,  // Taille des données refusées, en bytes.
+    pub limit: usize, // Taille maximale des données du registre.
+}
+
+impl fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "données de {} bytes refusées : la taille maximale est de {} bytes",
+            self.size, self.limit
+        )
+    }
+}
+
+impl Error for PayloadTooLarge {}
+
+/// Erreur renvoyée par [`CallbackRegistry::enqueue`](crate::CallbackRegistry::enqueue) lorsque la
+/// file est pleine ; la donnée refusée est rendue à l'appelant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueFull {
+    pub payload: Vec<u8>, // Donnée refusée.
+    pub capacity: usize,  // Nombre maximal de données en file.
+}
+
+impl fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "file d'attente pleine : {} données en attente au maximum",
+            self.capacity
+        )
+    }
+}
+
+impl Error for QueueFull {}
+
+/// Erreur renvoyée par [`CallbackRegistry::set_callback_sampled`](crate::CallbackRegistry::set_callback_sampled)
+/// et [`CallbackRegistry::set_sample_rate`](crate::CallbackRegistry::set_sample_rate) lorsque le
+/// taux d'échantillonnage demandé est nul.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroSampleRate;
+
+impl fmt::Display for ZeroSampleRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "le taux d'échantillonnage doit être au moins 1 (un appel sur 1)"
+        )
+    }
+}
+
+impl Error for ZeroSampleRate {}
+
+/// Erreur renvoyée par [`CallbackRegistry::try_set_callback`](crate::CallbackRegistry::try_set_callback)
+/// lorsque le registre a atteint son nombre maximal de callbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistryFull {
+    pub count: usize, // Nombre de callbacks enregistrés.
+    pub limit: usize, // Nombre maximal de callbacks du registre.
+}
+
+impl fmt::Display for RegistryFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "le registre est plein : {} callbacks enregistrés pour une limite de {}",
+            self.count, self.limit
+        )
+    }
+}
+
+impl Error for RegistryFull {}
+
+/// Erreur signalée par un callback faillible, voir
+/// [`CallbackRegistry::try_do_something`](crate::CallbackRegistry::try_do_something).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallbackError {
+    /// Le callback a échoué ; le message décrit l'échec.
+    Failed(String),
+    /// Le callback n'a pas terminé dans son délai, voir
+    /// [`CallbackRegistry::set_callback_with_timeout`](crate::CallbackRegistry::set_callback_with_timeout).
+    TimedOut(CallbackId),
+}
+
+impl CallbackError {
+    /// Crée une erreur [`Failed`](CallbackError::Failed) décrite par `message`.
+    pub fn new(message: impl Into<String>) -> Self {
+        CallbackError::Failed(message.into())
+    }
+
+    /// Renvoie la description de l'échec.
+    pub fn message(&self) -> &str {
+        match self {
+            CallbackError::Failed(message) => message,
+            CallbackError::TimedOut(_) => "délai dépassé",
+        }
+    }
+}
+
+impl fmt::Display for CallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallbackError::Failed(message) => write!(f, "échec du callback : {}", message),
+            CallbackError::TimedOut(id) => {
+                write!(f, "le callback {:?} n'a pas terminé dans son délai", id)
+            }
+        }
+    }
+}
+
+impl Error for CallbackError {}
+
+/// Erreur renvoyée par [`Event::parse`](crate::Event::parse) lorsque les données sont vides et
+/// ne contiennent donc pas de type d'événement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyEvent;
+
+impl fmt::Display for EmptyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "données vides : un événement commence par un byte de type"
+        )
+    }
+}
+
+impl Error for EmptyEvent {}
+
+/// Erreur renvoyée par [`CallbackRegistry::merge`](crate::CallbackRegistry::merge) ; aucun des
+/// deux registres n'est alors modifié.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// Un callback de chaque registre porte le même nom.
+    DuplicateName(DuplicateName),
+    /// Le registre fusionné dépasserait son nombre maximal de callbacks.
+    RegistryFull(RegistryFull),
+    /// Le callback de cet identifiant, dans le registre ajouté, porte la même clé de
+    /// déduplication qu'un callback de ce registre.
+    DuplicateKey(CallbackId),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::DuplicateName(error) => write!(f, "fusion impossible : {}", error),
+            MergeError::RegistryFull(error) => write!(f, "fusion impossible : {}", error),
+            MergeError::DuplicateKey(id) => write!(
+                f,
+                "fusion impossible : la clé du callback {:?} est déjà utilisée",
+                id
+            ),
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+/// Erreur renvoyée lorsqu'aucun callback n'est enregistré sous l'identifiant demandé,
+/// par exemple par [`CallbackRegistry::replace_callback`](crate::CallbackRegistry::replace_callback).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownId(pub CallbackId);
+
+impl fmt::Display for UnknownId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "aucun callback n'est enregistré sous l'identifiant {:?}",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownId {}
+
+/// Erreur renvoyée par un validateur enregistré avec
+/// [`CallbackRegistry::set_validator`](crate::CallbackRegistry::set_validator) pour refuser des
+/// données malformées.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub reason: String, // Pourquoi les données ont été refusées.
+}
+
+impl ValidationError {
+    /// Crée une erreur de validation expliquée par `reason`.
+    pub fn new(reason: impl Into<String>) -> Self {
+        ValidationError {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "données refusées par la validation : {}", self.reason)
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Erreur renvoyée par [`DataProcessor::process`](crate::DataProcessor::process) lorsque le
+/// traitement des données a échoué.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessError {
+    pub reason: String, // Pourquoi le traitement a échoué.
+}
+
+impl ProcessError {
+    /// Crée une erreur de traitement expliquée par `reason`.
+    pub fn new(reason: impl Into<String>) -> Self {
+        ProcessError {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "échec du traitement des données : {}", self.reason)
+    }
+}
+
+impl Error for ProcessError {}
+
+/// Erreur renvoyée par [`Checksum::verify`](crate::Checksum::verify) lorsque la somme de contrôle
+/// qui termine les données est absente ou fausse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumError {
+    /// Les données sont plus courtes que la somme de contrôle.
+    TooShort { len: usize, needed: usize },
+    /// La somme de contrôle lue ne correspond pas à celle des données.
+    Mismatch { expected: u32, actual: u32 },
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumError::TooShort { len, needed } => write!(
+                f,
+                "trame de {} bytes trop courte pour une somme de contrôle de {} bytes",
+                len, needed
+            ),
+            ChecksumError::Mismatch { expected, actual } => write!(
+                f,
+                "somme de contrôle incorrecte : {:#x} lue, {:#x} calculée",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl Error for ChecksumError {}
+
+/// Erreur renvoyée par [`process_data_chunked`](crate::process_data_chunked) et
+/// [`CallbackRegistry::dispatch_chunks`](crate::CallbackRegistry::dispatch_chunks) lorsque les
+/// données ne peuvent pas être découpées.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkError {
+    /// La taille des morceaux demandée est nulle.
+    ZeroSize,
+    /// Le dernier morceau est incomplet et la politique [`PartialChunk::Error`](crate::PartialChunk::Error) le refuse.
+    Partial { len: usize, chunk_size: usize },
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkError::ZeroSize => write!(f, "la taille des morceaux doit être au moins 1 byte"),
+            ChunkError::Partial { len, chunk_size } => write!(
+                f,
+                "dernier morceau incomplet : {} bytes sur {}",
+                len, chunk_size
+            ),
+        }
+    }
+}
+
+impl Error for ChunkError {}
+
+/// Erreur renvoyée par [`CallbackPayload::from_hex`](crate::CallbackPayload::from_hex) et
+/// [`CallbackPayload::from_base64`](crate::CallbackPayload::from_base64) lorsque le texte n'est pas
+/// un encodage valide, et par [`Schema::decode`](crate::Schema::decode) lorsque les données ne
+/// correspondent pas au schéma.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Le texte hexadécimal a un nombre impair de chiffres.
+    OddLength(usize),
+    /// Le texte base64 n'a pas une taille multiple de 4.
+    InvalidLength(usize),
+    /// Le caractère `character`, à l'octet `index` du texte, n'appartient pas à l'alphabet.
+    InvalidCharacter { character: char, index: usize },
+    /// Le remplissage `=` du texte base64 est mal placé ou incohérent avec les données.
+    BadPadding,
+    /// Les données, de `len` bytes, s'arrêtent avant la fin du champ `field`, qui en demande `needed`.
+    TooShort {
+        field: String,
+        needed: usize,
+        len: usize,
+    },
+    /// Le schéma ne déclare aucun champ de ce nom.
+    UnknownField(String),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::OddLength(len) => {
+                write!(
+                    f,
+                    "texte hexadécimal de longueur impaire ({} caractères)",
+                    len
+                )
+            }
+            DecodeError::InvalidLength(len) => write!(
+                f,
+                "texte base64 de {} caractères : la longueur doit être un multiple de 4",
+                len
+            ),
+            DecodeError::InvalidCharacter { character, index } => {
+                write!(
+                    f,
+                    "caractère {:?} invalide à la position {}",
+                    character, index
+                )
+            }
+            DecodeError::BadPadding => write!(f, "remplissage base64 invalide"),
+            DecodeError::TooShort { field, needed, len } => write!(
+                f,
+                "données trop courtes pour le champ `{}` : {} bytes nécessaires, {} reçus",
+                field, needed, len
+            ),
+            DecodeError::UnknownField(name) => write!(f, "champ `{}` inconnu", name),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Erreur renvoyée par [`replay_file`](crate::replay_file) lorsque l'enregistrement ne peut pas
+/// être relu.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// Le fichier n'a pas pu être lu.
+    Io(std::io::Error),
+    /// Le fichier ne commence pas par l'en-tête d'un enregistrement.
+    BadMagic,
+    /// Le fichier a été écrit dans une version du format que cette version ne sait pas lire.
+    UnsupportedVersion(u16),
+    /// Le fichier se termine au milieu de l'événement qui commence à l'octet `offset`.
+    Truncated { offset: usize },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => {
+                write!(f, "lecture de l'enregistrement impossible : {}", error)
+            }
+            ReplayError::BadMagic => {
+                write!(f, "ce fichier n'est pas un enregistrement d'événements")
+            }
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "enregistrement au format {} non pris en charge", version)
+            }
+            ReplayError::Truncated { offset } => write!(
+                f,
+                "enregistrement tronqué : l'événement de l'octet {} est incomplet",
+                offset
+            ),
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(error: std::io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+/// Une somme de contrôle incorrecte refuse les données comme un validateur.
+impl From<ChecksumError> for ValidationError {
+    fn from(error: ChecksumError) -> Self {
+        ValidationError::new(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste que le message d'erreur indique comment corriger la configuration.
+    #[test]
+    fn test_missing_data_message() {
+        let message = BuildError::MissingData.to_string();
+        assert!(message.contains("with_data"));
+    }
+
+    /// Teste que le message d'erreur cite le nom en double.
+    #[test]
+    fn test_duplicate_name_message() {
+        let error = DuplicateName {
+            name: "flush".to_string(),
+        };
+        assert!(error.to_string().contains("`flush`"));
+    }
+
+    /// Teste que le message d'erreur donne le nombre de callbacks et la limite.
+    #[test]
+    fn test_registry_full_message() {
+        let message = RegistryFull { count: 2, limit: 2 }.to_string();
+        assert!(message.contains("2 callbacks"));
+        assert!(message.contains("limite de 2"));
+    }
+
+    /// Teste que le message d'erreur d'un callback reprend sa description.
+    #[test]
+    fn test_callback_error_message() {
+        let error = CallbackError::new("capteur déconnecté");
+        assert_eq!(error.message(), "capteur déconnecté");
+        assert!(error.to_string().contains("capteur déconnecté"));
+    }
+}
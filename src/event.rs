@@ -0,0 +1,73 @@
+//! Événements structurés : un byte de type d'événement suivi de son contenu.
+
+use crate::callback::CallbackData;
+use crate::error::EmptyEvent;
+
+/// Vue sur un événement encodé `[kind, payload...]` : le premier byte donne le type d'événement.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::Event;
+///
+/// let event = Event::parse(&[0x02, 10, 20]).unwrap();
+/// assert_eq!(event.kind, 0x02);
+/// assert_eq!(event.payload, &[10, 20]);
+/// assert!(Event::parse(&[]).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event<'p> {
+    pub kind: u8,          // Type d'événement, premier byte des données brutes.
+    pub payload: &'p [u8], // Bytes qui suivent le type d'événement.
+}
+
+impl<'p> Event<'p> {
+    /// Crée un événement de type `kind` avec le contenu `payload`.
+    pub fn new(kind: u8, payload: &'p [u8]) -> Self {
+        Event { kind, payload }
+    }
+
+    /// Décode un événement depuis les données brutes `raw`.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`EmptyEvent`] si `raw` est vide et ne contient donc pas de type d'événement.
+    pub fn parse(raw: &'p [u8]) -> Result<Self, EmptyEvent> {
+        match raw.split_first() {
+            Some((&kind, payload)) => Ok(Event { kind, payload }),
+            None => Err(EmptyEvent),
+        }
+    }
+}
+
+/// Sérialise l'événement en `{ kind, payload }`, le contenu étant écrit comme des données de
+/// callback (voir la feature `serde`).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Event<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut event = serializer.serialize_struct("Event", 2)?;
+        event.serialize_field("kind", &self.kind)?;
+        event.serialize_field("payload", crate::data::CallbackPayload::new(self.payload))?;
+        event.end()
+    }
+}
+
+/// Implémentation du trait `CallbackData` pour `Event`.
+impl CallbackData for Event<'_> {}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    /// Teste la forme JSON d'un événement.
+    #[test]
+    fn test_event_serializes_as_struct() {
+        let event = Event::new(2, &[0x0a, 0xff]);
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"kind":2,"payload":"0aff"}"#
+        );
+    }
+}
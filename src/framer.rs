@@ -0,0 +1,232 @@
+//! Reconstitution de trames complètes à partir d'un flux de bytes lu par morceaux.
+
+use std::error::Error;
+use std::fmt;
+
+/// Taille maximale par défaut d'une trame, en bytes.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Manière dont les trames sont délimitées dans le flux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    Fixed(usize),   // Trames de taille fixe.
+    LengthPrefixed, // Trames précédées de leur taille sur 2 bytes big-endian.
+    Delimited(u8),  // Trames terminées par un byte délimiteur.
+}
+
+/// Reconstitue des trames complètes à partir de bytes reçus par morceaux de taille quelconque,
+/// par exemple depuis une socket.
+///
+/// Les bytes d'une trame incomplète sont conservés jusqu'au morceau suivant. Une trame plus
+/// grande que [`max_frame_len`](Self::with_max_frame_len) est refusée au lieu d'être conservée
+/// indéfiniment.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::Framer;
+///
+/// let mut framer = Framer::length_prefixed();
+/// let mut frames = Vec::new();
+/// framer.push(&[0, 2, 0xaa], |frame| frames.push(frame.to_vec())).unwrap();
+/// assert!(frames.is_empty());
+/// framer.push(&[0xbb, 0, 1, 0xcc], |frame| frames.push(frame.to_vec())).unwrap();
+/// assert_eq!(frames, vec![vec![0xaa, 0xbb], vec![0xcc]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Framer {
+    strategy: Strategy,   // Délimitation des trames.
+    max_frame_len: usize, // Taille maximale d'une trame, préfixe et délimiteur exclus.
+    buffer: Vec<u8>,      // Bytes reçus qui ne forment pas encore une trame complète.
+}
+
+impl Framer {
+    /// Crée un `Framer` sur la délimitation `strategy`, avec la taille maximale par défaut.
+    fn new(strategy: Strategy) -> Self {
+        Framer {
+            strategy,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Crée un `Framer` pour des trames de `len` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `len` vaut 0.
+    pub fn fixed(len: usize) -> Self {
+        assert!(
+            len > 0,
+            "une trame de taille fixe doit contenir au moins 1 byte"
+        );
+        Framer::new(Strategy::Fixed(len))
+    }
+
+    /// Crée un `Framer` pour des trames précédées de leur taille, sur 2 bytes big-endian ; le
+    /// préfixe n'est pas transmis avec la trame.
+    pub fn length_prefixed() -> Self {
+        Framer::new(Strategy::LengthPrefixed)
+    }
+
+    /// Crée un `Framer` pour des trames terminées par `delimiter`, qui n'est pas transmis avec la
+    /// trame.
+    pub fn delimited(delimiter: u8) -> Self {
+        Framer::new(Strategy::Delimited(delimiter))
+    }
+
+    /// Fixe la taille maximale d'une trame, [`DEFAULT_MAX_FRAME_LEN`] par défaut.
+    pub fn with_max_frame_len(self, max_frame_len: usize) -> Self {
+        Framer {
+            max_frame_len,
+            ..self
+        }
+    }
+
+    /// Renvoie le nombre de bytes reçus qui attendent la fin de leur trame.
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Ajoute `bytes` au flux et appelle `on_frame` sur chaque trame complétée, dans l'ordre.
+    /// Renvoie le nombre de trames complétées.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`FrameTooLarge`] dès qu'une trame dépasse la taille maximale. Les trames qui la
+    /// précèdent ont été transmises ; les bytes en attente sont oubliés, le flux reprend au
+    /// prochain appel.
+    pub fn push(
+        &mut self,
+        bytes: &[u8],
+        mut on_frame: impl FnMut(&[u8]),
+    ) -> Result<usize, FrameTooLarge> {
+        self.buffer.extend_from_slice(bytes);
+        let mut consumed = 0;
+        let mut frames = 0;
+        let result = loop {
+            match self.next_frame(&self.buffer[consumed..]) {
+                Ok(Some((start, len, used))) => {
+                    on_frame(&self.buffer[consumed + start..consumed + start + len]);
+                    consumed += used;
+                    frames += 1;
+                }
+                Ok(None) => break Ok(frames),
+                Err(error) => {
+                    consumed = self.buffer.len();
+                    break Err(error);
+                }
+            }
+        };
+        self.buffer.drain(..consumed);
+        result
+    }
+
+    /// Cherche une trame complète au début de `pending` et renvoie sa position, sa taille et le
+    /// nombre de bytes qu'elle occupe dans le flux.
+    fn next_frame(&self, pending: &[u8]) -> Result<Option<(usize, usize, usize)>, FrameTooLarge> {
+        let too_large = |len| FrameTooLarge {
+            len,
+            max: self.max_frame_len,
+        };
+        match self.strategy {
+            Strategy::Fixed(len) if len > self.max_frame_len => Err(too_large(len)),
+            Strategy::Fixed(len) => Ok((pending.len() >= len).then_some((0, len, len))),
+            Strategy::LengthPrefixed => {
+                let Some(&[high, low]) = pending.first_chunk() else {
+                    return Ok(None);
+                };
+                let len = usize::from(u16::from_be_bytes([high, low]));
+                if len > self.max_frame_len {
+                    return Err(too_large(len));
+                }
+                Ok((pending.len() >= 2 + len).then_some((2, len, 2 + len)))
+            }
+            Strategy::Delimited(delimiter) => {
+                match pending.iter().position(|&byte| byte == delimiter) {
+                    Some(len) if len > self.max_frame_len => Err(too_large(len)),
+                    Some(len) => Ok(Some((0, len, len + 1))),
+                    // Sans délimiteur, la trame en cours mesure déjà au moins `pending.len()` bytes.
+                    None if pending.len() > self.max_frame_len => Err(too_large(pending.len())),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+}
+
+/// Erreur renvoyée par [`Framer::push`] lorsqu'une trame dépasse la taille maximale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTooLarge {
+    pub len: usize, // Taille de la trame, connue ou déjà atteinte.
+    pub max: usize, // Taille maximale d'une trame.
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "trame de {} bytes refusée : la taille maximale est de {} bytes",
+            self.len, self.max
+        )
+    }
+}
+
+impl Error for FrameTooLarge {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Donne `chunks` à `framer`, dans l'ordre, et renvoie les trames complétées.
+    fn frames(framer: &mut Framer, chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        for chunk in chunks {
+            framer
+                .push(chunk, |frame| frames.push(frame.to_vec()))
+                .unwrap();
+        }
+        frames
+    }
+
+    /// Teste des trames de taille fixe, plusieurs par morceau et à cheval sur deux morceaux.
+    #[test]
+    fn test_fixed_length() {
+        let mut framer = Framer::fixed(3);
+        assert_eq!(
+            frames(&mut framer, &[&[1, 2, 3, 4], &[5, 6, 7, 8, 9], &[10]]),
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]
+        );
+        assert_eq!(framer.pending(), 1);
+    }
+
+    /// Teste des trames délimitées, dont une vide.
+    #[test]
+    fn test_delimited() {
+        let mut framer = Framer::delimited(b'\n');
+        assert_eq!(
+            frames(&mut framer, &[b"ab\n\nc", b"d\n"]),
+            vec![b"ab".to_vec(), vec![], b"cd".to_vec()]
+        );
+    }
+
+    /// Teste qu'une trame trop grande est refusée sans retenir ses bytes.
+    #[test]
+    fn test_oversized_frames_are_rejected() {
+        let mut framer = Framer::delimited(0).with_max_frame_len(4);
+        assert_eq!(
+            framer.push(&[1, 2, 3, 4, 5], |_| panic!("trame inattendue")),
+            Err(FrameTooLarge { len: 5, max: 4 })
+        );
+        assert_eq!(framer.pending(), 0);
+
+        let mut framer = Framer::length_prefixed().with_max_frame_len(4);
+        let mut seen = Vec::new();
+        assert_eq!(
+            framer.push(&[0, 1, 7, 0, 9], |frame| seen.push(frame.to_vec())),
+            Err(FrameTooLarge { len: 9, max: 4 })
+        );
+        assert_eq!(seen, vec![vec![7]]);
+        assert_eq!(frames(&mut framer, &[&[0, 1, 8]]), vec![vec![8]]);
+    }
+}
@@ -0,0 +1,129 @@
+//! # My Callback System
+//!
+//! Ce module implémente un système de gestion de callbacks générique utilisant Rust.
+//! Il fournit des structures et des traits pour enregistrer et exécuter des callbacks
+//! avec des données associées. Les examples incluent comment configurer et utiliser ces callbacks.
+//!
+//! ## Fonctionnalités
+//!
+//! - `CallbackData`: Trait servant de base pour les types pouvant être utilisés comme données dans des callbacks.
+//! - `callback_data!`: Macro qui implémente `CallbackData` pour ses propres types, transmis via `CallbackRegistry::dispatch_value`.
+//! - `CallbackPayload`: Vue concrète sur un slice de bytes implémentant `CallbackData`.
+//! - `CallbackPayloadBuf` / `CowCallbackPayload`: Versions possédée et copie-à-l'écriture de `CallbackPayload`.
+//! - `ArcCallbackPayload`: Données partagées via un `Arc<[u8]>`, que les callbacks peuvent conserver.
+//! - `AnyCallbackData`: Événement de type quelconque, pour un registre d'événements hétérogènes.
+//! - `Event`: Vue sur des données brutes dont le premier byte donne le type d'événement.
+//! - `Framer`: Reconstitution de trames complètes à partir de bytes reçus par morceaux.
+//! - `Recorder` / `replay_file`: Enregistrement des données transmises dans un fichier, puis relecture.
+//! - `AnnotatedData`: Données accompagnées de l'heure, de l'origine et du numéro de leur appel.
+//! - `Clock` / `ManualClock`: Horloges injectables des fonctionnalités qui dépendent du temps.
+//! - `Callback`: Structure générique pour gérer des callbacks.
+//! - `CallbackHost`: Trait pour les structures désirant implémenter un système de callback.
+//! - `CallbackRegistry`: Implémentation d'une structure utilisant `CallbackHost` et gérant plusieurs callbacks.
+//! - `StaticRegistry`: Registre dont les callbacks, fixés à la construction, sont appelés sans indirection.
+//! - `TypedRegistry`: Registre d'événements de types hétérogènes, rangés par `TypeId`.
+//!
+//! La feature `derive` fournit `#[derive(CallbackData)]` ; la feature `serde` rend les données
+//! sérialisables et ajoute `CallbackPayload::to_json` / `from_json` ; la feature `compression`
+//! ajoute les transformations `Transform::compress` / `decompress` ; la feature `async` ajoute
+//! les callbacks asynchrones `AsyncCallback`, attendus par `CallbackRegistry::dispatch_async`, et le
+//! flux `EventStream` des données transmises.
+//!
+//! Les anciens noms (`MyStruct`, `MyTrait`, `MyCallback`, `MyCallbackData`, ...) restent disponibles
+//! sous forme d'alias obsolètes pendant une version.
+//!
+//! ## Exemple
+//!
+//! ```
+//! use rust_reven::{Callback, CallbackRegistry, CallbackPayload, CallbackHost};
+//!
+//! let mut registry = CallbackRegistry::builder()
+//!     .with_data(&[1, 2, 3])
+//!     .build()
+//!     .unwrap();
+//! registry.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+//! registry.do_something();
+//! ```
+
+extern crate self as rust_reven;
+
+mod builder;
+mod callback;
+mod clock;
+mod data;
+mod error;
+mod event;
+mod framer;
+pub mod prelude;
+mod recorder;
+mod registry;
+mod static_registry;
+mod typed_registry;
+
+pub use crate::builder::CallbackRegistryBuilder;
+pub use crate::callback::{
+    Callback, CallbackData, CallbackId, CallbackIdGenerator, Handler, IntoCallback, SharedCallback,
+    WriteFormat, WriterSink,
+};
+pub use crate::clock::{Clock, ManualClock, SystemClock};
+#[cfg(feature = "compression")]
+pub use crate::data::CompressionInfo;
+pub use crate::data::{
+    process_data, process_data_checked, process_data_chunked, AnyCallbackData, ArcCallbackPayload,
+    CallbackPayload, CallbackPayloadBuf, Checksum, CowCallbackPayload, DataProcessor, DataStats,
+    PartialChunk, PayloadFormatter, PrintProcessor, Record, Schema, StatsProcessor,
+};
+pub use crate::error::{
+    BuildError, CallbackError, ChecksumError, ChunkError, DecodeError, DuplicateName, EmptyEvent,
+    MergeError, PayloadTooLarge, ProcessError, QueueFull, RegistryFull, ReplayError, UnknownId,
+    ValidationError, ZeroLimit, ZeroSampleRate,
+};
+pub use crate::registry::{
+    AnnotatedData, Backpressure, BatchContext, CallbackContext, CallbackHost, CallbackInfo,
+    CallbackRegistry, CallbackSnapshot, CatchUp, Coalesce, DebounceEdge, DebounceTimer, Debouncer,
+    DedupFilter, DispatchHandle, DispatchReport, DispatchStats, FailureReason, FixedRegistry,
+    OverflowPolicy, OwnedRegistry, ProcessingMode, RateLimitPolicy, RegistryHandle, ReplyMode,
+    Responder, RetryPolicy, SchedulerHandle, SequenceStats, SubscriptionGuard, TimerId, Transform,
+    WindowedData,
+};
+#[cfg(feature = "async")]
+pub use crate::registry::{AsyncCallback, AsyncMode, BoxFuture, EventStream};
+#[cfg(feature = "derive")]
+pub use rust_reven_derive::CallbackData;
+
+pub use crate::event::Event;
+pub use crate::framer::{FrameTooLarge, Framer, DEFAULT_MAX_FRAME_LEN};
+pub use crate::recorder::{replay_file, Recorder, ReplaySpeed};
+pub use crate::static_registry::{CallbackList, Dispatch, StaticRegistry};
+pub use crate::typed_registry::TypedRegistry;
+
+#[allow(deprecated)]
+pub use crate::callback::MyCallback;
+#[allow(deprecated)]
+pub use crate::data::{ArcCallbackData, CowCallbackData, MyCallbackBuf, MyCallbackData};
+#[allow(deprecated)]
+pub use crate::registry::{MyStruct, MyTrait};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Teste que les anciens noms obsolètes désignent toujours les nouveaux types.
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_aliases() {
+        let mut registry: MyStruct<MyCallbackData> = MyStruct::with_data(&[1, 2, 3]);
+        let callback: MyCallback<MyCallbackData> = MyCallback::new(|_data: &MyCallbackData| {});
+        <MyStruct<MyCallbackData> as MyTrait<MyCallbackData>>::set_callback(
+            &mut registry,
+            callback,
+        );
+        let owned: MyCallbackBuf = MyCallbackData::new(&[4]).to_owned();
+        let cow: CowCallbackData = MyCallbackData::borrowed(&[5]);
+        let _: Option<ArcCallbackData> = None;
+
+        assert_eq!(registry.callbacks.len(), 1);
+        assert_eq!(owned.as_bytes(), &[4]);
+        assert_eq!(cow.as_bytes(), &[5]);
+    }
+}
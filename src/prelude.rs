@@ -0,0 +1,29 @@
+//! Prélude de la crate : `use rust_reven::prelude::*;` suffit pour écrire un programme simple.
+//!
+//! # Examples
+//!
+//! ```
+//! use rust_reven::prelude::*;
+//!
+//! let mut registry = CallbackRegistry::builder().with_data(&[1, 2, 3]).build().unwrap();
+//! registry.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+//! registry.do_something();
+//! ```
+
+pub use crate::builder::CallbackRegistryBuilder;
+pub use crate::callback::{
+    Callback, CallbackData, CallbackId, CallbackIdGenerator, Handler, IntoCallback, SharedCallback,
+    WriteFormat, WriterSink,
+};
+pub use crate::data::{
+    AnyCallbackData, ArcCallbackPayload, CallbackPayload, CallbackPayloadBuf, CowCallbackPayload,
+    DataProcessor, PrintProcessor, StatsProcessor,
+};
+pub use crate::event::Event;
+pub use crate::registry::{
+    AnnotatedData, CallbackContext, CallbackHost, CallbackInfo, CallbackRegistry, CallbackSnapshot,
+    Coalesce, DedupFilter, DispatchReport, FailureReason, FixedRegistry, OwnedRegistry,
+    RegistryHandle, ReplyMode, Responder, RetryPolicy, SubscriptionGuard, WindowedData,
+};
+pub use crate::static_registry::{Dispatch, StaticRegistry};
+pub use crate::typed_registry::TypedRegistry;
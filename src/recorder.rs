@@ -0,0 +1,338 @@
+//! Enregistrement des données transmises aux callbacks dans un fichier, pour les rejouer plus tard.
+//!
+//! Le fichier commence par l'en-tête `RVRC` suivi de la version du format sur 2 bytes
+//! big-endian. Chaque événement est ensuite écrit sous la forme :
+//!
+//! - l'heure de l'appel, en microsecondes depuis l'epoch Unix, sur 8 bytes big-endian ;
+//! - la taille des données, sur 4 bytes big-endian ;
+//! - les bytes des données.
+
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::callback::{Callback, CallbackId};
+use crate::data::CallbackPayload;
+use crate::error::ReplayError;
+use crate::registry::{ignore_result, CallbackRegistry, Entry};
+
+/// Octets qui ouvrent un enregistrement.
+const MAGIC: &[u8; 4] = b"RVRC";
+
+/// Version du format écrit par [`Recorder`].
+const VERSION: u16 = 1;
+
+/// Taille de l'en-tête du fichier.
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// Taille de l'en-tête d'un événement : heure puis taille des données.
+const EVENT_HEADER_LEN: usize = 8 + 4;
+
+/// Vitesse de relecture d'un enregistrement, voir [`replay_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplaySpeed {
+    /// Transmet les événements les uns après les autres, sans attendre.
+    #[default]
+    AsFastAsPossible,
+    /// Attend entre deux événements le même délai que lors de l'enregistrement.
+    Original,
+}
+
+/// État partagé entre un [`Recorder`] et son callback.
+#[derive(Debug)]
+struct RecorderState {
+    file: File,                    // Le fichier, ouvert en ajout.
+    last_error: Option<io::Error>, // Dernière erreur d'écriture, `None` s'il n'y en a pas eu.
+}
+
+/// Enregistre dans un fichier chaque donnée transmise aux callbacks d'un registre, avec l'heure
+/// de son appel ; [`replay_file`] les rejoue ensuite.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rust_reven::{replay_file, CallbackHost, OwnedRegistry, CallbackPayload, Recorder, ReplaySpeed};
+///
+/// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![1, 2]);
+/// let recorder = Recorder::attach(&mut registry, "session.rvrc").unwrap();
+/// registry.do_something();
+/// assert!(recorder.take_error().is_none());
+///
+/// let mut debug: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+/// debug.set_callback(|data: &CallbackPayload| println!("{:?}", data));
+/// replay_file("session.rvrc", &debug, ReplaySpeed::Original).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    id: CallbackId,                    // Identifiant du callback d'enregistrement.
+    state: Rc<RefCell<RecorderState>>, // État partagé avec le callback.
+}
+
+impl Recorder {
+    /// Enregistre dans `registry` un callback qui ajoute chaque donnée reçue au fichier `path`.
+    ///
+    /// Le fichier est créé s'il n'existe pas ; s'il existe, ses événements sont conservés et les
+    /// nouveaux sont ajoutés à la suite. Retirer le callback, via [`id`](Self::id), arrête
+    /// l'enregistrement.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie l'erreur d'ouverture du fichier, ou une erreur [`ErrorKind::InvalidData`] si le
+    /// fichier existant n'est pas un enregistrement dans la version actuelle du format.
+    pub fn attach<D: AsRef<[u8]> + ?Sized>(
+        registry: &mut CallbackRegistry<'_, CallbackPayload, D>,
+        path: impl AsRef<Path>,
+    ) -> io::Result<Recorder> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        if file.metadata()?.len() == 0 {
+            let mut header = MAGIC.to_vec();
+            header.extend_from_slice(&VERSION.to_be_bytes());
+            file.write_all(&header)?;
+        } else {
+            let mut header = [0; HEADER_LEN];
+            file.read_exact(&mut header)?;
+            check_header(&header).map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+        }
+
+        let state = Rc::new(RefCell::new(RecorderState {
+            file,
+            last_error: None,
+        }));
+        let context = Rc::clone(&registry.context);
+        let state_in_cb = Rc::clone(&state);
+        let id = registry.push_callback(Callback::new(move |data: &CallbackPayload| {
+            // Tous les callbacks d'un même appel voient la même heure.
+            let time = context
+                .get()
+                .map_or_else(SystemTime::now, |ctx| ctx.wall_time);
+            let micros = time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros();
+            let bytes = data.as_bytes();
+            let mut event = Vec::with_capacity(EVENT_HEADER_LEN + bytes.len());
+            event.extend_from_slice(&(micros as u64).to_be_bytes());
+            event.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            event.extend_from_slice(bytes);
+
+            let mut state = state_in_cb.borrow_mut();
+            if let Err(error) = state.file.write_all(&event) {
+                state.last_error = Some(error);
+            }
+        }));
+        Ok(Recorder { id, state })
+    }
+
+    /// Renvoie l'identifiant du callback d'enregistrement.
+    pub fn id(&self) -> CallbackId {
+        self.id
+    }
+
+    /// Renvoie la dernière erreur d'écriture et l'oublie, ou `None` s'il n'y en a pas eu.
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.state.borrow_mut().last_error.take()
+    }
+}
+
+/// Vérifie l'en-tête `header` d'un enregistrement.
+fn check_header(header: &[u8]) -> Result<(), ReplayError> {
+    match header.split_first_chunk::<4>() {
+        Some((magic, rest)) if magic == MAGIC && rest.len() >= 2 => {
+            match u16::from_be_bytes([rest[0], rest[1]]) {
+                VERSION => Ok(()),
+                version => Err(ReplayError::UnsupportedVersion(version)),
+            }
+        }
+        _ => Err(ReplayError::BadMagic),
+    }
+}
+
+/// Découpe les événements de l'enregistrement `bytes` en heures, en microsecondes, et données.
+fn parse_events(bytes: &[u8]) -> Result<Vec<(u64, &[u8])>, ReplayError> {
+    check_header(bytes)?;
+    let mut events = Vec::new();
+    let mut offset = HEADER_LEN;
+    while offset < bytes.len() {
+        let truncated = ReplayError::Truncated { offset };
+        let Some((header, rest)) = bytes[offset..].split_first_chunk::<EVENT_HEADER_LEN>() else {
+            return Err(truncated);
+        };
+        let (micros, len) = header.split_at(8);
+        let micros = u64::from_be_bytes(micros.try_into().expect("8 bytes"));
+        let len = u32::from_be_bytes(len.try_into().expect("4 bytes")) as usize;
+        let Some(data) = rest.get(..len) else {
+            return Err(truncated);
+        };
+        events.push((micros, data));
+        offset += EVENT_HEADER_LEN + len;
+    }
+    Ok(events)
+}
+
+/// Relit l'enregistrement `path` écrit par un [`Recorder`] et transmet chacun de ses événements
+/// aux callbacks de `registry`, comme `do_something`, au rythme `speed`. Renvoie le nombre
+/// d'événements rejoués.
+///
+/// # Errors
+///
+/// Renvoie une [`ReplayError`] si le fichier ne peut pas être lu, n'est pas un enregistrement
+/// ou est tronqué ; le fichier entier est vérifié avant de rejouer le premier événement.
+pub fn replay_file<D: AsRef<[u8]> + ?Sized, R>(
+    path: impl AsRef<Path>,
+    registry: &CallbackRegistry<'_, CallbackPayload, D, R>,
+    speed: ReplaySpeed,
+) -> Result<usize, ReplayError> {
+    let bytes = fs::read(path)?;
+    let events = parse_events(&bytes)?;
+    let mut previous = None;
+    for &(micros, data) in &events {
+        if let (ReplaySpeed::Original, Some(previous)) = (speed, previous) {
+            thread::sleep(Duration::from_micros(micros.saturating_sub(previous)));
+        }
+        previous = Some(micros);
+        let dispatched =
+            registry.dispatch_bytes(data, |_| true, Entry::invoke, ignore_result, false);
+        if let Err(error) = dispatched {
+            registry.report_invalid(error);
+        }
+    }
+    Ok(events.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    /// Renvoie un chemin de fichier temporaire propre au test `name`, supprimé s'il existe.
+    fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("rust_reven-{}-{}.rvrc", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    /// Écrit un enregistrement fait des événements `events`, heures en microsecondes.
+    fn write_recording(path: &Path, events: &[(u64, &[u8])]) {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_be_bytes());
+        for (micros, data) in events {
+            bytes.extend_from_slice(&micros.to_be_bytes());
+            bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(data);
+        }
+        fs::write(path, bytes).unwrap();
+    }
+
+    /// Teste l'enregistrement de trois événements puis leur relecture dans un autre registre,
+    /// ainsi que l'ajout à un enregistrement existant.
+    #[test]
+    fn test_record_then_replay_round_trip() {
+        let path = temp_path("round-trip");
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        let recorder = Recorder::attach(&mut registry, &path).unwrap();
+        for data in [vec![1], vec![], vec![2, 3]] {
+            registry.set_data(data);
+            registry.do_something();
+        }
+        assert!(recorder.take_error().is_none());
+        drop(registry);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut replayed: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        replayed.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        });
+        assert_eq!(
+            replay_file(&path, &replayed, ReplaySpeed::AsFastAsPossible).unwrap(),
+            3
+        );
+        assert_eq!(*seen.borrow(), vec![vec![1], vec![], vec![2, 3]]);
+
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![4]);
+        Recorder::attach(&mut registry, &path).unwrap();
+        registry.do_something();
+        assert_eq!(
+            replay_file(&path, &replayed, ReplaySpeed::AsFastAsPossible).unwrap(),
+            4
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Teste que la relecture au rythme d'origine respecte les délais entre les événements.
+    #[test]
+    fn test_replay_honours_original_delays() {
+        let path = temp_path("delays");
+        write_recording(
+            &path,
+            &[(1_000_000, &[1]), (1_030_000, &[2]), (1_030_000, &[3])],
+        );
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        });
+
+        let start = Instant::now();
+        replay_file(&path, &registry, ReplaySpeed::Original).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(seen.borrow().len(), 3);
+        fs::remove_file(&path).unwrap();
+    }
+
+    /// Teste les erreurs sur un fichier étranger, d'une autre version ou tronqué, sans qu'aucun
+    /// événement ne soit rejoué.
+    #[test]
+    fn test_corrupted_and_truncated_files() {
+        let path = temp_path("corrupted");
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        });
+        let replay = || replay_file(&path, &registry, ReplaySpeed::AsFastAsPossible);
+
+        fs::write(&path, b"PNG\x0d\x0a").unwrap();
+        assert!(matches!(replay(), Err(ReplayError::BadMagic)));
+
+        fs::write(&path, b"RVRC\x00\x09").unwrap();
+        assert!(matches!(replay(), Err(ReplayError::UnsupportedVersion(9))));
+
+        write_recording(&path, &[(1, &[1, 2, 3])]);
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.pop();
+        fs::write(&path, &bytes).unwrap();
+        let error = replay().unwrap_err();
+        assert!(matches!(
+            error,
+            ReplayError::Truncated { offset: HEADER_LEN }
+        ));
+        assert!(error.to_string().contains("tronqué"));
+
+        // Un fichier existant étranger n'est pas complété.
+        let mut recorded: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        fs::write(&path, b"pas un enregistrement").unwrap();
+        let error = Recorder::attach(&mut recorded, &path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(replay(), Err(ReplayError::Io(_))));
+        assert!(seen.borrow().is_empty());
+    }
+}
@@ -0,0 +1,1613 @@
+//! Registre de callbacks : le trait `CallbackHost` et son implémentation `CallbackRegistry`.
+
+mod annotated;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod batch;
+mod bulk;
+mod capacity;
+mod change;
+mod chunked;
+mod coalesce;
+mod context;
+mod debounce;
+mod decoded;
+mod dedup;
+mod deferred;
+mod dispatcher;
+mod entry;
+mod erased;
+mod fallible;
+mod filter;
+mod framing;
+mod group;
+mod guard;
+mod history;
+mod info;
+mod isolated;
+mod keyed;
+mod kind;
+mod limited;
+mod merge;
+mod mutable;
+mod named;
+mod parallel;
+mod payload_limit;
+mod periodic;
+mod processing;
+mod propagation;
+mod quarantine;
+mod queue;
+mod rate_limit;
+mod replace;
+mod report;
+mod request;
+mod sampling;
+mod scheduler;
+mod sequence;
+mod slab;
+mod snapshot;
+mod sticky;
+mod storage;
+#[cfg(feature = "async")]
+mod stream;
+mod timeout;
+mod timing;
+mod toggle;
+mod transform;
+mod validation;
+mod weak;
+mod window;
+
+use self::annotated::SourceSlot;
+#[cfg(feature = "async")]
+use self::asynchronous::AsyncCallbacks;
+use self::change::LastPayload;
+use self::context::ContextSlot;
+use self::deferred::DeferredQueue;
+use self::history::HistoryRing;
+use self::mutable::Mutator;
+use self::parallel::ParallelCallbacks;
+use self::payload_limit::RejectedHandler;
+use self::processing::ProcessorSlot;
+use self::queue::DispatchQueue;
+use self::rate_limit::RateLimiter;
+use self::sampling::Sampler;
+use self::scheduler::Timers;
+use self::sequence::SequenceTracking;
+use self::slab::EntrySlab;
+use self::sticky::Sticky;
+use self::validation::{InvalidHandler, Validator};
+use crate::builder::CallbackRegistryBuilder;
+use crate::callback::{Callback, CallbackData, CallbackId, Handler, IntoCallback};
+use crate::data::{ArcCallbackPayload, CallbackPayload, Checksum, DataSlot};
+use crate::error::PayloadTooLarge;
+use crate::framer::Framer;
+use std::cell::Cell;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use self::annotated::AnnotatedData;
+#[cfg(feature = "async")]
+pub use self::asynchronous::{AsyncCallback, AsyncMode, BoxFuture};
+pub use self::coalesce::Coalesce;
+pub use self::context::{BatchContext, CallbackContext};
+pub use self::debounce::{DebounceEdge, DebounceTimer, Debouncer};
+pub use self::dedup::DedupFilter;
+pub use self::deferred::RegistryHandle;
+pub use self::dispatcher::{Backpressure, DispatchHandle};
+pub(crate) use self::entry::Entry;
+pub use self::fallible::RetryPolicy;
+pub use self::framing::DispatchStats;
+pub use self::guard::SubscriptionGuard;
+pub use self::info::CallbackInfo;
+pub use self::periodic::CatchUp;
+pub use self::processing::ProcessingMode;
+pub use self::quarantine::FailureReason;
+pub use self::queue::OverflowPolicy;
+pub use self::rate_limit::RateLimitPolicy;
+pub use self::report::DispatchReport;
+pub use self::request::{ReplyMode, Responder};
+pub use self::scheduler::{SchedulerHandle, TimerId};
+pub use self::sequence::SequenceStats;
+pub use self::snapshot::CallbackSnapshot;
+#[cfg(feature = "async")]
+pub use self::stream::EventStream;
+pub use self::transform::Transform;
+pub use self::window::WindowedData;
+
+/// `CallbackHost` définit les comportements pour les structures qui veulent implémenter un mécanisme de callback.
+///
+/// Ce trait permet de configurer un ou plusieurs callbacks et de les exécuter.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{
+///     Callback, CallbackHost, CallbackId, CallbackIdGenerator, CallbackPayload, IntoCallback,
+///     PayloadTooLarge,
+/// };
+///
+/// struct ExampleStruct {
+///     callbacks: Vec<(CallbackId, Callback<CallbackPayload>)>,
+///     ids: CallbackIdGenerator,
+///     data: &'static [u8; 3],
+/// }
+///
+/// impl CallbackHost<'static, CallbackPayload> for ExampleStruct {
+///     fn set_callback(&mut self, cb: impl IntoCallback<CallbackPayload>) -> CallbackId {
+///         let id = self.ids.next_id();
+///         self.callbacks.push((id, cb.into_callback()));
+///         id
+///     }
+///
+///     fn remove_callback(&mut self, id: CallbackId) -> bool {
+///         let before = self.callbacks.len();
+///         self.callbacks.retain(|(cb_id, _)| *cb_id != id);
+///         self.callbacks.len() != before
+///     }
+///
+///     fn clear_callbacks(&mut self) {
+///         self.callbacks.clear();
+///     }
+///
+///     fn callback_count(&self) -> usize {
+///         self.callbacks.len()
+///     }
+///
+///     fn do_something(&self) {
+///         self.dispatch_value(CallbackPayload::new(self.data));
+///     }
+///
+///     fn dispatch(&self, data: &[u8]) -> Result<(), PayloadTooLarge> {
+///         self.dispatch_value(CallbackPayload::new(data));
+///         Ok(())
+///     }
+///
+///     fn dispatch_value(&self, value: &CallbackPayload) {
+///         for (_, cb) in &self.callbacks {
+///             cb.invoke(value);
+///         }
+///     }
+/// }
+///
+/// let mut example = ExampleStruct {
+///     callbacks: Vec::new(),
+///     ids: CallbackIdGenerator::default(),
+///     data: &[1, 2, 3],
+/// };
+/// let id = example.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+/// example.do_something();
+/// assert!(example.remove_callback(id));
+/// assert!(!example.has_callbacks()); // Implémentation par défaut.
+/// ```
+pub trait CallbackHost<'a, T: CallbackData + ?Sized, R = ()> {
+    fn set_callback(&mut self, cb: impl IntoCallback<T, R>) -> CallbackId; // Méthode pour ajouter un callback (ou une closure), renvoie son identifiant.
+    fn remove_callback(&mut self, id: CallbackId) -> bool; // Retire le callback `id`, renvoie `false` s'il est inconnu.
+    fn clear_callbacks(&mut self); // Retire (et détruit) tous les callbacks.
+    fn callback_count(&self) -> usize; // Nombre de callbacks enregistrés.
+    fn do_something(&self); // Méthode abstraite pour effectuer une action, non définie ici.
+
+    /// Appelle les callbacks avec `data` au lieu des données du registre, comme `do_something`.
+    ///
+    /// `data` n'est empruntée que pendant l'appel.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`PayloadTooLarge`] si `data` dépasse la taille maximale du registre, voir
+    /// [`CallbackRegistry::set_max_payload_size`] ; aucun callback n'a alors été appelé.
+    fn dispatch(&self, data: &[u8]) -> Result<(), PayloadTooLarge>;
+
+    /// Appelle les callbacks avec `value`, déjà construite, au lieu des données du registre.
+    fn dispatch_value(&self, value: &T);
+
+    /// Enregistre le gestionnaire `handler`, appelé par `do_something` comme les autres callbacks.
+    ///
+    /// `handler.on_register` est appelé immédiatement, et `handler.on_unregister` lorsque le
+    /// gestionnaire quitte le registre, y compris à la destruction de celui-ci.
+    fn set_handler(&mut self, handler: Box<dyn Handler<T, R>>) -> CallbackId
+    where
+        T: 'static,
+        R: 'static,
+    {
+        self.set_callback(Callback::from_registered_handler(handler))
+    }
+
+    /// Indique si au moins un callback est enregistré.
+    fn has_callbacks(&self) -> bool {
+        self.callback_count() > 0
+    }
+}
+
+/// Ancien nom de [`CallbackHost`].
+///
+/// Obsolète depuis la version 0.2.0 : utilisez `CallbackHost`. Rust ne sait pas signaler
+/// l'utilisation d'un trait ré-exporté, cet alias ne produit donc pas d'avertissement.
+pub use self::CallbackHost as MyTrait;
+
+/// `CallbackRegistry` est une structure générique qui utilise `CallbackData` pour gérer une série de callbacks et des données associées.
+///
+/// # Type Parameters
+///
+/// - `T`: Le type des données de callback. `T` doit implémenter `CallbackData`.
+/// - `'a`: La durée de vie des données empruntées (`'static` pour un [`OwnedRegistry`]).
+/// - `D`: Le conteneur des données, un slice `[u8]` par défaut. Voir [`FixedRegistry`] pour un tampon de taille fixe.
+/// - `R`: Le type renvoyé par les callbacks, `()` par défaut. Voir [`CallbackRegistry::dispatch_collect`].
+///
+/// # Fields
+///
+/// - `callbacks`: Les callbacks enregistrés, rangés selon leur identifiant, et leur ordre d'appel ; les deux premiers
+///   sont stockés dans le registre lui-même, sans allocation.
+/// - `data`: Les données, empruntées ou possédées, vues comme un slice de bytes de longueur quelconque (éventuellement vide).
+/// - `paused` / `dropped`: L'état de pause du registre et le nombre d'appels ignorés pendant la pause.
+/// - `max_callbacks`: Le nombre maximal de callbacks, voir [`CallbackRegistry::try_set_callback`].
+/// - `dispatch_seq` / `context`: Le nombre d'appels à `do_something` et le contexte de l'appel en cours, voir [`CallbackContext`].
+/// - `source`: L'origine des données, transmise aux callbacks annotés, voir [`AnnotatedData`].
+/// - `failure_threshold`: Le nombre d'échecs consécutifs qui met un callback en quarantaine, voir [`CallbackRegistry::set_failure_threshold`].
+/// - `slow_threshold`: La durée au-delà de laquelle un callback est signalé, voir [`CallbackRegistry::dispatch_timed`].
+/// - `mutators`: Les callbacks qui modifient les données, voir [`CallbackRegistry::do_something_mut`].
+/// - `parallel`: Les callbacks appelés en parallèle, voir [`CallbackRegistry::dispatch_parallel`].
+/// - `async_callbacks`: Les callbacks asynchrones (feature `async`), voir [`CallbackRegistry::dispatch_async`].
+/// - `deferred`: Les opérations demandées via un [`RegistryHandle`], voir [`CallbackRegistry::apply_deferred`].
+/// - `validators` / `on_invalid`: Les validateurs des données et le gestionnaire de leurs erreurs, voir [`CallbackRegistry::set_validator`].
+/// - `checksum`: La somme de contrôle qui termine les données, voir [`CallbackRegistry::verify_checksum`].
+/// - `transforms`: Les transformations appliquées aux données avant les callbacks, voir [`CallbackRegistry::add_transform`].
+/// - `last_payload`: Les dernières données transmises aux callbacks, voir [`CallbackRegistry::dispatch_on_change`].
+/// - `history`: L'historique des données transmises aux callbacks, voir [`CallbackRegistry::enable_history`].
+/// - `sticky`: Les dernières données, remises à chaque nouveau callback, voir [`CallbackRegistry::enable_sticky`].
+/// - `dedup`: Le filtre qui écarte les données transmises récemment, voir [`CallbackRegistry::set_dedup_filter`].
+/// - `sampler`: L'échantillonnage des appels à `do_something`, voir [`CallbackRegistry::set_sample_rate`].
+/// - `coalesced`: Le nombre de données fusionnées dans l'appel en cours, voir [`CallbackRegistry::dispatch_coalesced`].
+/// - `batch`: La position des données de l'appel en cours dans leur lot, voir [`CallbackRegistry::dispatch_batch`].
+/// - `framer`: Le découpage en trames des bytes reçus, voir [`CallbackRegistry::feed`].
+/// - `max_payload_size` / `on_rejected`: La taille maximale des données et le gestionnaire des données refusées, voir [`CallbackRegistry::set_max_payload_size`].
+/// - `sequence`: Le suivi des numéros de séquence des données, voir [`CallbackRegistry::track_sequence`].
+/// - `rate_limit`: La limite de débit des appels, voir [`CallbackRegistry::set_rate_limit`].
+/// - `timers`: Les données programmées pour plus tard et les callbacks périodiques, voir [`CallbackRegistry::schedule`].
+/// - `queue`: Les données mises en file, en attente de transmission, voir [`CallbackRegistry::enqueue`].
+/// - `processor`: Le traitement des données après les callbacks, voir [`CallbackRegistry::set_processor`].
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackPayload, CallbackRegistry, CallbackHost};
+///
+/// let data = &[1, 2, 3];
+/// let mut registry = CallbackRegistry::builder().with_data(data).build().unwrap();
+/// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+/// registry.do_something();
+/// ```
+pub struct CallbackRegistry<'a, T: CallbackData + ?Sized, D: ?Sized = [u8], R = ()> {
+    pub(crate) callbacks: EntrySlab<T, R>, // Callbacks de type `T` et leur ordre d'appel.
+    pub(crate) data: DataSlot<'a, D>,      // Les données, vues comme un slice de bytes.
+    pub(crate) paused: bool,               // `true` entre `pause` et `resume`.
+    pub(crate) dropped: Cell<usize>, // Nombre d'appels à `do_something` ignorés pendant la pause.
+    pub(crate) max_callbacks: Option<usize>, // Nombre maximal de callbacks, `None` si illimité.
+    pub(crate) dispatch_seq: Cell<u64>, // Nombre d'appels à `do_something` effectués.
+    pub(crate) context: ContextSlot, // Contexte du callback en cours d'appel.
+    pub(crate) source: SourceSlot,   // Origine des données, transmise aux callbacks annotés.
+    pub(crate) failure_threshold: Option<u32>, // Échecs consécutifs avant quarantaine, `None` si jamais.
+    pub(crate) slow_threshold: Option<Duration>, // Durée d'un callback lent, `None` si non mesurée.
+    pub(crate) mutators: Vec<(CallbackId, Mutator)>, // Callbacks de `do_something_mut`, dans l'ordre d'enregistrement.
+    pub(crate) parallel: ParallelCallbacks<T>,       // Callbacks de `dispatch_parallel`.
+    #[cfg(feature = "async")]
+    pub(crate) async_callbacks: AsyncCallbacks<T>, // Callbacks de `dispatch_async`.
+    pub(crate) deferred: DeferredQueue<T, R>, // Opérations différées, dans l'ordre de leur demande.
+    pub(crate) validators: Vec<Validator>, // Validateurs des données, dans l'ordre d'enregistrement.
+    pub(crate) on_invalid: Option<InvalidHandler>, // Reçoit les erreurs des validateurs.
+    pub(crate) checksum: Option<Checksum>, // Somme de contrôle vérifiée puis retirée avant les callbacks.
+    pub(crate) transforms: Vec<Transform>, // Transformations des données, dans l'ordre d'enregistrement.
+    pub(crate) last_payload: Option<LastPayload>, // Dernières données transmises, `None` sans détection des changements.
+    pub(crate) history: Option<HistoryRing>, // Historique des données transmises, `None` s'il n'est pas activé.
+    pub(crate) sticky: Option<Sticky<T, R>>, // Dernières données remises aux nouveaux callbacks, `None` hors mode collant.
+    pub(crate) dedup: Option<DedupFilter>, // Déduplication des données, `None` si elle n'est pas activée.
+    pub(crate) sampler: Option<Sampler>, // Échantillonnage des appels, `None` si tous sont transmis.
+    pub(crate) coalesced: Cell<usize>,   // Nombre de données fusionnées dans l'appel en cours.
+    pub(crate) batch: Cell<Option<BatchContext>>, // Position dans le lot en cours, `None` hors lot.
+    pub(crate) framer: Option<Framer>, // Découpage en trames des bytes de `feed`, `None` s'il n'est pas défini.
+    pub(crate) max_payload_size: Option<usize>, // Taille maximale des données, `None` si illimitée.
+    pub(crate) on_rejected: Option<RejectedHandler>, // Reçoit les données refusées car trop grandes.
+    pub(crate) sequence: Option<SequenceTracking>, // Suivi des numéros de séquence, `None` s'il n'est pas activé.
+    pub(crate) rate_limit: Option<RateLimiter>, // Limite de débit des appels, `None` si illimité.
+    pub(crate) timers: Timers,                  // Données programmées, en attente de leur heure.
+    pub(crate) queue: DispatchQueue,            // Données en attente de `flush`.
+    pub(crate) processor: ProcessorSlot,        // Traitement des données après les callbacks.
+}
+
+/// Ancien nom de [`CallbackRegistry`].
+#[deprecated(
+    since = "0.2.0",
+    note = "utilisez `CallbackRegistry` à la place de `MyStruct`"
+)]
+pub type MyStruct<'a, T, D = [u8]> = CallbackRegistry<'a, T, D>;
+
+/// Registre dont les données sont un tampon de taille fixe `N`, connue à la compilation.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{FixedRegistry, Callback, CallbackPayload, CallbackHost};
+///
+/// let frame = [0u8; 8]; // Une trame CAN.
+/// let mut registry: FixedRegistry<CallbackPayload, 8> = FixedRegistry::with_data(&frame);
+/// registry.set_callback(Callback::new(|data: &CallbackPayload| assert_eq!(data.as_bytes().len(), 8)));
+/// registry.do_something();
+/// ```
+pub type FixedRegistry<'a, T, const N: usize> = CallbackRegistry<'a, T, [u8; N]>;
+
+/// Registre qui possède ses données et peut donc être stocké dans un état applicatif de longue durée.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackPayload, CallbackHost, OwnedRegistry};
+///
+/// struct App {
+///     registry: OwnedRegistry<CallbackPayload>,
+/// }
+///
+/// let mut app = App {
+///     registry: OwnedRegistry::with_owned_data(vec![1, 2, 3]),
+/// };
+/// app.registry.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+/// app.registry.do_something();
+/// app.registry.set_data(vec![4, 5, 6, 7]);
+/// app.registry.do_something();
+/// ```
+pub type OwnedRegistry<T> = CallbackRegistry<'static, T>;
+
+impl<'a, T: CallbackData + ?Sized, R> CallbackRegistry<'a, T, [u8], R> {
+    /// Renvoie un [`CallbackRegistryBuilder`] pour construire le registre étape par étape.
+    pub fn builder() -> CallbackRegistryBuilder<'a, T, [u8], R> {
+        CallbackRegistryBuilder::new()
+    }
+
+    /// Crée un registre sans callback ni données, pour des payloads passés à
+    /// [`CallbackHost::dispatch`] ou [`dispatch_value`](Self::dispatch_value).
+    pub fn new() -> Self {
+        Self::from_slot(DataSlot::Owned(Box::default()))
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, R> Default for CallbackRegistry<'a, T, [u8], R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Crée un registre sans callback autour de l'emplacement de données `data`.
+    pub(crate) fn from_slot(data: DataSlot<'a, D>) -> Self {
+        CallbackRegistry {
+            callbacks: EntrySlab::new(Rc::default()),
+            data,
+            paused: false,
+            dropped: Cell::new(0),
+            max_callbacks: None,
+            dispatch_seq: Cell::new(0),
+            context: ContextSlot::default(),
+            source: SourceSlot::default(),
+            failure_threshold: None,
+            slow_threshold: None,
+            mutators: Vec::new(),
+            parallel: ParallelCallbacks::default(),
+            #[cfg(feature = "async")]
+            async_callbacks: AsyncCallbacks::default(),
+            deferred: Rc::default(),
+            validators: Vec::new(),
+            on_invalid: None,
+            checksum: None,
+            transforms: Vec::new(),
+            last_payload: None,
+            history: None,
+            sticky: None,
+            dedup: None,
+            sampler: None,
+            coalesced: Cell::new(1),
+            batch: Cell::new(None),
+            framer: None,
+            max_payload_size: None,
+            on_rejected: None,
+            sequence: None,
+            rate_limit: None,
+            timers: Timers::default(),
+            queue: DispatchQueue::default(),
+            processor: ProcessorSlot::default(),
+        }
+    }
+
+    /// Crée un registre sans callback sur les données empruntées `data`.
+    ///
+    /// Le type du conteneur est déduit de `data` : un tableau `[u8; N]` donne un [`FixedRegistry`].
+    pub fn with_data(data: &'a D) -> Self {
+        Self::from_slot(DataSlot::Borrowed(data))
+    }
+
+    /// Crée un registre sans callback qui possède ses données, par exemple un `Vec<u8>`.
+    pub fn with_owned_data(data: impl Into<Box<D>>) -> Self {
+        Self::from_slot(DataSlot::Owned(data.into()))
+    }
+
+    /// Crée un registre sans callback dont les données sont partagées via un `Arc`.
+    pub fn with_shared_data(data: Arc<D>) -> Self {
+        Self::from_slot(DataSlot::Shared(data))
+    }
+
+    /// Insère `cb` dans l'ordre d'appel selon sa priorité et renvoie son nouvel identifiant.
+    pub(crate) fn push_callback(&mut self, cb: Callback<T, R>) -> CallbackId {
+        self.push_entry(|id| Entry::new(id, cb))
+    }
+
+    /// Insère l'entrée construite par `make` après toutes les entrées de priorité inférieure ou égale.
+    ///
+    /// Le vecteur reste ainsi trié par priorité, l'ordre d'enregistrement départageant les égalités,
+    /// sans qu'il faille le retrier à chaque appel de `do_something`.
+    ///
+    /// # Panics
+    ///
+    /// Panique si le registre a atteint son nombre maximal de callbacks.
+    pub(crate) fn push_entry(
+        &mut self,
+        make: impl FnOnce(CallbackId) -> Entry<T, R>,
+    ) -> CallbackId {
+        if let Err(full) = self.check_capacity() {
+            panic!("{}", full);
+        }
+        let id = self.next_id();
+        self.insert_entry(make(id));
+        id
+    }
+
+    /// Ajoute `entry`, dont l'identifiant est déjà réservé, comme `push_entry`.
+    ///
+    /// # Panics
+    ///
+    /// Panique si le registre a atteint son nombre maximal de callbacks.
+    pub(crate) fn push_reserved_entry(&mut self, entry: Entry<T, R>) {
+        if let Err(full) = self.check_capacity() {
+            self.callbacks.ids().borrow_mut().release(entry.id);
+            panic!("{}", full);
+        }
+        self.insert_entry(entry);
+    }
+
+    /// Livre les données collantes à `entry`, puis l'insère selon sa priorité ou libère son
+    /// identifiant s'il est déjà épuisé.
+    fn insert_entry(&mut self, entry: Entry<T, R>) {
+        // En mode collant, l'entrée reçoit d'abord les dernières données ; un callback unique
+        // ainsi satisfait n'est pas enregistré.
+        self.deliver_sticky(&entry);
+        if entry.is_live() {
+            self.callbacks.insert_by_priority(entry);
+        } else {
+            self.callbacks.ids().borrow_mut().release(entry.id);
+        }
+    }
+
+    /// Réserve un identifiant auprès de l'allocateur du registre.
+    pub(crate) fn next_id(&mut self) -> CallbackId {
+        self.callbacks.ids().borrow_mut().next_id()
+    }
+
+    /// Retire le callback `id` sans modifier l'ordre relatif des autres callbacks, en O(1).
+    ///
+    /// Renvoie `None` si `id` est inconnu, déjà retiré ou épuisé.
+    pub(crate) fn remove_entry(&mut self, id: CallbackId) -> Option<Entry<T, R>> {
+        self.callbacks.remove(id).filter(Entry::is_live)
+    }
+
+    /// Supprime les callbacks épuisés ou dont le `SubscriptionGuard` a été détruit, que `do_something` ne peut pas retirer
+    /// lui-même puisqu'il n'emprunte le registre qu'en lecture.
+    fn prune_spent(&mut self) {
+        self.callbacks.retain(Entry::is_live);
+    }
+
+    /// Itère sur les callbacks encore appelables, dans l'ordre d'appel.
+    pub(crate) fn live_entries(&self) -> impl Iterator<Item = &Entry<T, R>> {
+        self.callbacks.iter().filter(|entry| entry.is_live())
+    }
+
+    /// Itère sur les callbacks que `do_something` doit appeler, dans l'ordre d'appel.
+    pub(crate) fn active_entries(&self) -> impl Iterator<Item = &Entry<T, R>> {
+        self.callbacks.iter().filter(|entry| entry.is_active())
+    }
+
+    /// Renvoie l'entrée du callback `id`, s'il est toujours enregistré.
+    pub(crate) fn entry_mut(&mut self, id: CallbackId) -> Option<&mut Entry<T, R>> {
+        self.callbacks.get_mut(id).filter(|entry| entry.is_live())
+    }
+
+    /// Remplace les données du registre par des données partagées via un `Arc`.
+    pub fn set_shared_data(&mut self, data: Arc<D>) {
+        self.data = DataSlot::Shared(data);
+    }
+
+    /// Remplace les données du registre par `data`, désormais possédées par le registre.
+    ///
+    /// Les prochains appels à `do_something` transmettront ces nouvelles données aux callbacks.
+    pub fn set_data(&mut self, data: impl Into<Box<D>>) {
+        self.data = DataSlot::Owned(data.into());
+    }
+
+    /// Renvoie les données actuelles du registre.
+    pub fn data(&self) -> &D {
+        self.data.get()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb` quel que soit le type du payload et renvoie son identifiant ; pour un
+    /// registre de bytes, c'est l'équivalent de [`CallbackHost::set_callback`].
+    pub fn add_callback(&mut self, cb: impl IntoCallback<T, R>) -> CallbackId {
+        self.push_callback(cb.into_callback())
+    }
+
+    /// Appelle chaque callback actif avec `payload`, quel que soit son type, sans passer par les
+    /// données du registre.
+    ///
+    /// Comme `do_something`, l'appel respecte la pause, l'ordre de priorité, les prédicats et
+    /// les callbacks désactivés ; les valeurs renvoyées par les callbacks sont ignorées. Les
+    /// validateurs, transformations et autres traitements des bytes ne s'appliquent pas : pour
+    /// des bytes, préférez [`CallbackHost::dispatch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{callback_data, CallbackRegistry};
+    ///
+    /// struct SensorReading {
+    ///     id: u16,
+    ///     value: f32,
+    /// }
+    /// callback_data!(SensorReading);
+    ///
+    /// let mut registry = CallbackRegistry::new();
+    /// registry.add_callback(|reading: &SensorReading| println!("{} : {}", reading.id, reading.value));
+    /// registry.dispatch_value(&SensorReading { id: 7, value: 21.5 });
+    /// ```
+    pub fn dispatch_value(&self, payload: &T) {
+        if self.begin_dispatch() {
+            self.dispatch_payload(payload, |_| true, Entry::invoke, ignore_result);
+        }
+    }
+
+    /// Itère sur chaque callback actif retenu par `select`, l'appelle avec `payload` via `invoke`,
+    /// puis transmet son identifiant et sa valeur à `sink`, qui peut interrompre l'itération.
+    /// L'appelant a déjà vérifié la pause via `begin_dispatch`.
+    pub(crate) fn dispatch_payload<V>(
+        &self,
+        payload: &T,
+        select: impl Fn(&Entry<T, R>) -> bool,
+        invoke: impl Fn(&Entry<T, R>, &T) -> Option<V>,
+        mut sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+    ) {
+        let (dispatch_seq, timestamp, wall_time) = self.next_dispatch();
+        let entries = self.active_entries().filter(|entry| select(entry));
+        for (callback_index, entry) in entries.enumerate() {
+            self.context.set(Some(CallbackContext {
+                dispatch_seq,
+                callback_id: entry.id,
+                timestamp,
+                wall_time,
+                callback_index,
+                coalesced: self.coalesced.get(),
+                batch: self.batch.get(),
+            }));
+            if let Some(result) = invoke(entry, payload) {
+                if sink(entry.id, result).is_break() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Affiche les callbacks (identifiant, nom ou `<anonymous>`, priorité et état d'activation)
+/// ainsi que les données du registre.
+impl<'a, T: CallbackData + ?Sized, D: fmt::Debug + ?Sized, R> fmt::Debug
+    for CallbackRegistry<'a, T, D, R>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let callbacks: Vec<_> = self.live_entries().collect();
+        f.debug_struct("CallbackRegistry")
+            .field("callbacks", &callbacks)
+            .field("data", &self.data.get())
+            .field("paused", &self.paused)
+            .finish()
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Itère sur chaque callback actif retenu par `select`, l'exécute avec les données actuelles
+    /// et transmet son identifiant et sa valeur à `sink`, qui peut interrompre l'itération.
+    pub(crate) fn dispatch_where(
+        &self,
+        select: impl Fn(&Entry<CallbackPayload, R>) -> bool,
+        sink: impl FnMut(CallbackId, R) -> ControlFlow<()>,
+    ) {
+        self.dispatch_with(select, Entry::invoke, sink);
+    }
+
+    /// Comme `dispatch_where`, mais appelle chaque entrée via `invoke`, qui peut par exemple
+    /// intercepter une panique ; `sink` reçoit alors la valeur renvoyée par `invoke`.
+    pub(crate) fn dispatch_with<V>(
+        &self,
+        select: impl Fn(&Entry<CallbackPayload, R>) -> bool,
+        invoke: impl Fn(&Entry<CallbackPayload, R>, &CallbackPayload) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+    ) {
+        // Les données refusées par un validateur ne sont transmises à aucun callback.
+        if let Err(error) = self.dispatch_checked(select, invoke, sink, false) {
+            self.report_invalid(error);
+        }
+    }
+
+    /// Comme `do_something`, mais renvoie les valeurs des callbacks, dans l'ordre d'appel.
+    ///
+    /// Les callbacks désactivés, refusés par leur prédicat ou épuisés ne contribuent aucune valeur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| data.as_bytes().len()));
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| data.as_bytes()[0] as usize));
+    /// assert_eq!(registry.dispatch_collect(), vec![3, 1]);
+    /// ```
+    pub fn dispatch_collect(&self) -> Vec<R> {
+        let mut results = Vec::new();
+        self.dispatch_where(
+            |_| true,
+            |_, result| {
+                results.push(result);
+                ControlFlow::Continue(())
+            },
+        );
+        results
+    }
+}
+
+/// Sink de `dispatch_where` qui ignore les valeurs des callbacks.
+pub(crate) fn ignore_result<R>(_id: CallbackId, _result: R) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
+
+/// Implémentation du trait `CallbackHost` pour `CallbackRegistry` utilisant `CallbackPayload`.
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackHost<'a, CallbackPayload, R>
+    for CallbackRegistry<'a, CallbackPayload, D, R>
+{
+    // Ajoute un `Callback` au vecteur de callbacks.
+    fn set_callback(&mut self, cb: impl IntoCallback<CallbackPayload, R>) -> CallbackId {
+        self.push_callback(cb.into_callback())
+    }
+
+    // Retire un callback en conservant l'ordre des autres.
+    fn remove_callback(&mut self, id: CallbackId) -> bool {
+        self.remove_entry(id).is_some() || self.remove_periodic(id)
+    }
+
+    // Détruit tous les callbacks, et donc l'état qu'ils capturent.
+    fn clear_callbacks(&mut self) {
+        self.callbacks.clear();
+    }
+
+    fn callback_count(&self) -> usize {
+        self.live_entries().count()
+    }
+
+    // Exécute chaque callback actif avec les données actuelles ; leurs valeurs sont ignorées.
+    fn do_something(&self) {
+        self.dispatch_where(|_| true, ignore_result);
+    }
+
+    // Comme `do_something`, avec `data` à la place des données actuelles.
+    fn dispatch(&self, data: &[u8]) -> Result<(), PayloadTooLarge> {
+        self.check_payload_size(data.len())
+            .inspect_err(|error| self.report_rejected(error))?;
+        if let Err(error) = self.dispatch_bytes(data, |_| true, Entry::invoke, ignore_result, false)
+        {
+            self.report_invalid(error);
+        }
+        Ok(())
+    }
+
+    fn dispatch_value(&self, value: &CallbackPayload) {
+        CallbackRegistry::dispatch_value(self, value);
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Itère sur chaque callback actif retenu par `select`, lui transmet un clone de l'`Arc` des
+    /// données et transmet son identifiant et sa valeur à `sink`, qui peut interrompre l'itération.
+    pub(crate) fn dispatch_where(
+        &self,
+        select: impl Fn(&Entry<ArcCallbackPayload, R>) -> bool,
+        sink: impl FnMut(CallbackId, R) -> ControlFlow<()>,
+    ) {
+        self.dispatch_with(select, Entry::invoke, sink);
+    }
+
+    /// Comme `dispatch_where`, mais appelle chaque entrée via `invoke`, qui peut par exemple
+    /// intercepter une panique ; `sink` reçoit alors la valeur renvoyée par `invoke`.
+    pub(crate) fn dispatch_with<V>(
+        &self,
+        select: impl Fn(&Entry<ArcCallbackPayload, R>) -> bool,
+        invoke: impl Fn(&Entry<ArcCallbackPayload, R>, &ArcCallbackPayload) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+    ) {
+        // Les données refusées par un validateur ne sont transmises à aucun callback.
+        if let Err(error) = self.dispatch_checked(select, invoke, sink, false) {
+            self.report_invalid(error);
+        }
+    }
+
+    /// Comme `do_something`, mais renvoie les valeurs des callbacks, dans l'ordre d'appel.
+    pub fn dispatch_collect(&self) -> Vec<R> {
+        let mut results = Vec::new();
+        self.dispatch_where(
+            |_| true,
+            |_, result| {
+                results.push(result);
+                ControlFlow::Continue(())
+            },
+        );
+        results
+    }
+}
+
+/// Implémentation du trait `CallbackHost` pour `CallbackRegistry` utilisant `ArcCallbackPayload`.
+///
+/// Chaque callback reçoit son propre clone de l'`Arc` et peut donc conserver les bytes
+/// après la fin de `do_something`. Les bytes ne sont copiés qu'une fois par appel, et pas du tout
+/// si les données du registre sont déjà partagées (voir [`CallbackRegistry::with_shared_data`]).
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{ArcCallbackPayload, Callback, CallbackRegistry, CallbackHost};
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use std::sync::Arc;
+///
+/// let queue: Rc<RefCell<Vec<Arc<[u8]>>>> = Rc::default();
+/// let queue_in_cb = Rc::clone(&queue);
+/// let mut registry = CallbackRegistry::with_owned_data(vec![1, 2, 3]);
+/// registry.set_callback(Callback::new(move |data: &ArcCallbackPayload| queue_in_cb.borrow_mut().push(data.to_arc())));
+/// registry.do_something();
+/// drop(registry);
+/// assert_eq!(&queue.borrow()[0][..], &[1, 2, 3]);
+/// ```
+impl<'a, R> CallbackHost<'a, ArcCallbackPayload, R>
+    for CallbackRegistry<'a, ArcCallbackPayload, [u8], R>
+{
+    // Ajoute un `Callback` au vecteur de callbacks.
+    fn set_callback(&mut self, cb: impl IntoCallback<ArcCallbackPayload, R>) -> CallbackId {
+        self.push_callback(cb.into_callback())
+    }
+
+    // Retire un callback en conservant l'ordre des autres.
+    fn remove_callback(&mut self, id: CallbackId) -> bool {
+        self.remove_entry(id).is_some() || self.remove_periodic(id)
+    }
+
+    // Détruit tous les callbacks, et donc l'état qu'ils capturent.
+    fn clear_callbacks(&mut self) {
+        self.callbacks.clear();
+    }
+
+    fn callback_count(&self) -> usize {
+        self.live_entries().count()
+    }
+
+    // Exécute chaque callback actif avec un clone de l'`Arc` des données ; leurs valeurs sont ignorées.
+    fn do_something(&self) {
+        self.dispatch_where(|_| true, ignore_result);
+    }
+
+    // Comme `do_something`, avec une copie partagée de `data` à la place des données actuelles.
+    fn dispatch(&self, data: &[u8]) -> Result<(), PayloadTooLarge> {
+        self.check_payload_size(data.len())
+            .inspect_err(|error| self.report_rejected(error))?;
+        let dispatched = self.dispatch_shared(
+            data,
+            || Arc::from(data),
+            |_| true,
+            Entry::invoke,
+            ignore_result,
+            false,
+        );
+        if let Err(error) = dispatched {
+            self.report_invalid(error);
+        }
+        Ok(())
+    }
+
+    fn dispatch_value(&self, value: &ArcCallbackPayload) {
+        CallbackRegistry::dispatch_value(self, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Payload structuré, sans aucun byte.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct SensorReading {
+        id: u16,
+        value: f32,
+    }
+    crate::callback_data!(SensorReading);
+
+    /// Teste qu'un payload structuré est transmis tel quel aux callbacks, dans l'ordre de priorité.
+    #[test]
+    fn test_dispatch_struct_payload() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<SensorReading> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry
+            .add_callback(move |reading: &SensorReading| seen_in_cb.borrow_mut().push(*reading));
+        let seen_in_cb = Rc::clone(&seen);
+        registry.add_callback(Callback::with_priority(
+            -1,
+            move |reading: &SensorReading| {
+                seen_in_cb.borrow_mut().push(SensorReading {
+                    id: reading.id,
+                    value: -reading.value,
+                })
+            },
+        ));
+
+        registry.dispatch_value(&SensorReading { id: 3, value: 1.5 });
+        registry.pause();
+        registry.dispatch_value(&SensorReading { id: 4, value: 2.0 });
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                SensorReading { id: 3, value: -1.5 },
+                SensorReading { id: 3, value: 1.5 },
+            ]
+        );
+    }
+
+    /// Teste que `dispatch` transmet des bytes différents à chaque appel, sans les conserver.
+    #[test]
+    fn test_dispatch_external_bytes() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        }));
+
+        for event in [vec![1u8, 2], vec![3]] {
+            registry.dispatch(&event).unwrap();
+        }
+        registry.set_max_payload_size(1);
+        assert_eq!(
+            registry.dispatch(&[4, 5]),
+            Err(PayloadTooLarge { size: 2, limit: 1 })
+        );
+
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![3]]);
+        assert!(registry.data().is_empty());
+    }
+
+    /// Teste que `dispatch` sur un registre de données partagées transmet un `Arc` des bytes.
+    #[test]
+    fn test_dispatch_external_bytes_shared() {
+        let seen: Rc<RefCell<Vec<Arc<[u8]>>>> = Rc::default();
+        let mut registry: OwnedRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &ArcCallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.to_arc())
+        }));
+
+        registry.dispatch(&[7, 8]).unwrap();
+        registry.dispatch(&[9]).unwrap();
+
+        let seen = seen.borrow();
+        assert_eq!((&seen[0][..], &seen[1][..]), (&[7, 8][..], &[9][..]));
+    }
+
+    /// Teste la fonctionnalité `set_callback` pour s'assurer qu'elle ajoute correctement un callback au vecteur.
+    #[test]
+    fn test_set_callback() {
+        let data = &[1, 2, 3];
+        let mut registry = CallbackRegistry::with_data(data);
+
+        registry.set_callback(Callback::new(|_data: &CallbackPayload| {}));
+
+        assert_eq!(registry.callbacks.len(), 1);
+    }
+
+    thread_local! {
+        // Nombre d'appels à `count_call`, propre au thread du test.
+        static FN_PTR_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Fonction libre enregistrée comme pointeur de fonction.
+    fn count_call(_data: &CallbackPayload) {
+        FN_PTR_CALLS.with(|calls| calls.set(calls.get() + 1));
+    }
+
+    /// Teste qu'un pointeur de fonction et une closure cohabitent dans le même registre.
+    #[test]
+    fn test_fn_ptr_and_closure_in_same_registry() {
+        let closure_calls = Rc::new(Cell::new(0));
+        let closure_calls_in_cb = Rc::clone(&closure_calls);
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(Callback::from_ptr(count_call));
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            closure_calls_in_cb.set(closure_calls_in_cb.get() + 1)
+        }));
+
+        registry.do_something();
+        registry.do_something();
+
+        assert_eq!(FN_PTR_CALLS.with(Cell::get), 2);
+        assert_eq!(closure_calls.get(), 2);
+    }
+
+    /// Teste qu'un même callback partagé, enregistré dans deux registres, est appelé par chacun.
+    #[test]
+    fn test_shared_callback_in_two_registries() {
+        use crate::callback::SharedCallback;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let count_in_cb = Arc::clone(&count);
+        let shared = SharedCallback::new(move |_data: &CallbackPayload| {
+            count_in_cb.fetch_add(1, Ordering::SeqCst);
+        });
+        let mut first = CallbackRegistry::with_data(&[1u8][..]);
+        let mut second: OwnedRegistry<CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![2u8]);
+        first.set_callback(shared.clone());
+        second.set_callback(shared);
+
+        first.do_something();
+        second.do_something();
+
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    /// Marque l'appel de `mark_called` dans un compteur propre au thread du test.
+    fn mark_called(_data: &CallbackPayload) {
+        FN_PTR_CALLS.with(|calls| calls.set(calls.get() + 10));
+    }
+
+    /// Teste que `set_callback` accepte une closure, une fonction et un `Callback` déjà construit.
+    #[test]
+    fn test_set_callback_accepts_into_callback() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (closure_calls, prebuilt_calls) = (Rc::clone(&calls), Rc::clone(&calls));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            closure_calls.borrow_mut().push("closure")
+        });
+        registry.set_callback(mark_called);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            prebuilt_calls.borrow_mut().push("prebuilt")
+        }));
+
+        registry.do_something();
+
+        assert_eq!(registry.callback_count(), 3);
+        assert_eq!(*calls.borrow(), vec!["closure", "prebuilt"]);
+        assert_eq!(FN_PTR_CALLS.with(Cell::get), 10);
+    }
+
+    /// Gestionnaire qui note chaque étape de son cycle de vie dans `log`.
+    struct Lifecycle {
+        log: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Handler<CallbackPayload> for Lifecycle {
+        fn call(&mut self, _data: &CallbackPayload) {
+            self.log.borrow_mut().push("dispatch");
+        }
+
+        fn on_register(&mut self) {
+            self.log.borrow_mut().push("register");
+        }
+
+        fn on_unregister(&mut self) {
+            self.log.borrow_mut().push("unregister");
+        }
+    }
+
+    /// Teste la séquence enregistrement, appel puis retrait d'un gestionnaire.
+    #[test]
+    fn test_handler_lifecycle_on_remove() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let id = registry.set_handler(Box::new(Lifecycle {
+            log: Rc::clone(&log),
+        }));
+
+        registry.do_something();
+        assert!(registry.remove_callback(id));
+
+        assert_eq!(*log.borrow(), vec!["register", "dispatch", "unregister"]);
+    }
+
+    /// Teste que `on_unregister` est appelé par `clear_callbacks` et par la destruction du registre.
+    #[test]
+    fn test_handler_unregistered_on_clear_and_drop() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        let handler = || {
+            Box::new(Lifecycle {
+                log: Rc::clone(&log),
+            })
+        };
+        registry.set_handler(handler());
+        registry.clear_callbacks();
+        assert_eq!(*log.borrow(), vec!["register", "unregister"]);
+
+        registry.set_handler(handler());
+        drop(registry);
+        assert_eq!(
+            *log.borrow(),
+            vec!["register", "unregister", "register", "unregister"]
+        );
+    }
+
+    /// Teste que `do_something` appelle chaque callback avec les données du registre.
+    #[test]
+    fn test_do_something_calls_callbacks() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::builder()
+            .with_data(&[1, 2, 3])
+            .build()
+            .unwrap();
+
+        for _ in 0..2 {
+            let seen = Rc::clone(&seen);
+            registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+                seen.borrow_mut().push(data.as_bytes().to_vec())
+            }));
+        }
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![vec![1, 2, 3], vec![1, 2, 3]]);
+    }
+
+    /// Construit un registre sur `data` dont l'unique callback mémorise les bytes reçus.
+    fn recording_registry(
+        data: &[u8],
+    ) -> (CallbackRegistry<'_, CallbackPayload>, Rc<RefCell<Vec<u8>>>) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_in_cb = Rc::clone(&seen);
+        let mut registry = CallbackRegistry::builder().with_data(data).build().unwrap();
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().extend_from_slice(data.as_bytes())
+        }));
+        (registry, seen)
+    }
+
+    /// Teste qu'un slice vide est transmis tel quel aux callbacks.
+    #[test]
+    fn test_do_something_with_empty_slice() {
+        let (registry, seen) = recording_registry(&[]);
+        registry.do_something();
+        assert!(seen.borrow().is_empty());
+    }
+
+    /// Teste qu'un tampon de la taille d'un paquet Ethernet (1500 bytes) est transmis intégralement.
+    #[test]
+    fn test_do_something_with_large_slice() {
+        let packet: Vec<u8> = (0..1500).map(|i| (i % 256) as u8).collect();
+        let (registry, seen) = recording_registry(&packet);
+        registry.do_something();
+        assert_eq!(*seen.borrow(), packet);
+    }
+
+    /// Teste que le cas historique à trois bytes fonctionne toujours.
+    #[test]
+    fn test_do_something_with_three_bytes() {
+        let (registry, seen) = recording_registry(&[1, 2, 3]);
+        registry.do_something();
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+    }
+
+    /// Enregistre un callback qui mémorise la longueur du slice reçu.
+    fn record_len<D: AsRef<[u8]> + ?Sized>(
+        registry: &mut CallbackRegistry<'_, CallbackPayload, D>,
+        lengths: &Rc<RefCell<Vec<usize>>>,
+    ) {
+        let lengths = Rc::clone(lengths);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            lengths.borrow_mut().push(data.as_bytes().len())
+        }));
+    }
+
+    /// Teste deux registres de tailles fixes différentes dans le même programme.
+    #[test]
+    fn test_fixed_registries_with_different_sizes() {
+        let can_frame = [0x11u8; 8];
+        let sensor_block = [0x22u8; 64];
+        let lengths = Rc::new(RefCell::new(Vec::new()));
+
+        let mut can: FixedRegistry<CallbackPayload, 8> = FixedRegistry::with_data(&can_frame);
+        let mut sensor: FixedRegistry<CallbackPayload, 64> =
+            FixedRegistry::with_data(&sensor_block);
+        record_len(&mut can, &lengths);
+        record_len(&mut sensor, &lengths);
+        can.do_something();
+        sensor.do_something();
+
+        assert_eq!(*lengths.borrow(), vec![8, 64]);
+    }
+
+    /// Teste qu'un registre de taille fixe expose bien le contenu du tampon sous forme de slice.
+    #[test]
+    fn test_fixed_registry_exposes_slice_view() {
+        let frame = [1u8, 2, 3, 4];
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_in_cb = Rc::clone(&seen);
+        let mut registry: FixedRegistry<CallbackPayload, 4> = CallbackRegistry::with_data(&frame);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().extend_from_slice(data.as_bytes())
+        }));
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), frame);
+    }
+
+    /// Teste qu'un registre possédant ses données voit les nouvelles données après `set_data`.
+    #[test]
+    fn test_owned_registry_set_data_between_dispatches() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_in_cb = Rc::clone(&seen);
+        let mut registry: OwnedRegistry<CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1, 2, 3]);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        }));
+
+        registry.do_something();
+        registry.set_data(vec![4, 5, 6, 7]);
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![vec![1, 2, 3], vec![4, 5, 6, 7]]);
+    }
+
+    /// Teste qu'un registre emprunté peut passer à des données possédées.
+    #[test]
+    fn test_set_data_replaces_borrowed_data() {
+        let initial = [1, 2, 3];
+        let mut registry: CallbackRegistry<CallbackPayload> =
+            CallbackRegistry::with_data(&initial[..]);
+        registry.set_data(vec![9]);
+        assert_eq!(registry.data(), &[9]);
+    }
+
+    /// Teste qu'un callback peut conserver l'`Arc` des données hors du registre.
+    #[test]
+    fn test_arc_callback_keeps_bytes_after_dispatch() {
+        let stash: Rc<RefCell<Vec<Arc<[u8]>>>> = Rc::default();
+        let stash_in_cb = Rc::clone(&stash);
+        let bytes = vec![10, 20, 30];
+        let mut registry = CallbackRegistry::with_data(&bytes[..]);
+        registry.set_callback(Callback::new(move |data: &ArcCallbackPayload| {
+            stash_in_cb.borrow_mut().push(data.to_arc())
+        }));
+
+        registry.do_something();
+        drop(registry);
+        drop(bytes);
+
+        let stash = stash.borrow();
+        assert_eq!(stash.len(), 1);
+        assert_eq!(&stash[0][..], &[10, 20, 30]);
+    }
+
+    /// Teste que des données partagées ne sont jamais copiées et peuvent partir sur un autre thread.
+    #[test]
+    fn test_arc_callback_with_shared_data_is_zero_copy() {
+        let shared: Arc<[u8]> = Arc::from(&[1u8, 2, 3, 4][..]);
+        let stash: Rc<RefCell<Vec<Arc<[u8]>>>> = Rc::default();
+        let stash_in_cb = Rc::clone(&stash);
+        let mut registry = CallbackRegistry::with_shared_data(Arc::clone(&shared));
+        registry.set_callback(Callback::new(move |data: &ArcCallbackPayload| {
+            stash_in_cb.borrow_mut().push(data.to_arc())
+        }));
+        registry.do_something();
+
+        let kept = stash.borrow_mut().pop().unwrap();
+        assert!(Arc::ptr_eq(&kept, &shared));
+        let len = std::thread::spawn(move || kept.len()).join().unwrap();
+        assert_eq!(len, 4);
+    }
+
+    /// Teste que la distribution des données empruntées se fait sans copie.
+    #[test]
+    fn test_do_something_borrowed_path_is_zero_copy() {
+        let bytes = [1u8, 2, 3];
+        let seen_ptr = Rc::new(RefCell::new(None));
+        let seen_in_cb = Rc::clone(&seen_ptr);
+        let mut registry = CallbackRegistry::with_data(&bytes[..]);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            *seen_in_cb.borrow_mut() = Some(data.as_bytes().as_ptr())
+        }));
+        registry.do_something();
+
+        assert_eq!(*seen_ptr.borrow(), Some(bytes.as_ptr()));
+    }
+
+    /// Teste que chaque enregistrement renvoie un identifiant distinct.
+    #[test]
+    fn test_set_callback_returns_unique_ids() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let first = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let calls_in_cb = Rc::clone(&calls);
+        let second = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b"));
+        assert_ne!(first, second);
+    }
+
+    /// Teste que le retrait conserve l'ordre relatif des callbacks restants.
+    #[test]
+    fn test_remove_callback_preserves_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let calls_in_cb = Rc::clone(&calls);
+        let b = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("c"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("d"));
+
+        assert!(registry.remove_callback(b));
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["a", "c", "d"]);
+    }
+
+    /// Teste que retirer un identifiant inconnu ou déjà retiré renvoie `false`.
+    #[test]
+    fn test_remove_unknown_or_removed_id_returns_false() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let mut other = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        other.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("x"));
+        let calls_in_cb = Rc::clone(&calls);
+        let foreign =
+            other.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("y"));
+
+        assert!(registry.remove_callback(id));
+        assert!(!registry.remove_callback(id));
+        assert!(!registry.remove_callback(foreign));
+    }
+
+    /// Teste que `do_something` n'appelle plus un callback retiré.
+    #[test]
+    fn test_removed_callback_is_not_invoked() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("removed"));
+
+        registry.do_something();
+        registry.remove_callback(id);
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["removed"]);
+    }
+
+    /// Incrémente un compteur partagé lorsqu'il est détruit.
+    struct DropCounter(Rc<RefCell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            *self.0.borrow_mut() += 1;
+        }
+    }
+
+    /// Teste que `clear_callbacks` détruit les closures et l'état qu'elles capturent.
+    #[test]
+    fn test_clear_callbacks_drops_captured_state() {
+        let drops = Rc::new(RefCell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        for _ in 0..3 {
+            let guard = DropCounter(Rc::clone(&drops));
+            registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+                let _ = &guard;
+            }));
+        }
+
+        assert_eq!(*drops.borrow(), 0);
+        registry.clear_callbacks();
+        assert_eq!(*drops.borrow(), 3);
+        assert_eq!(registry.callback_count(), 0);
+    }
+
+    /// Teste `callback_count` et `has_callbacks` au fil des enregistrements et des retraits.
+    #[test]
+    fn test_callback_count_and_has_callbacks() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        assert_eq!(registry.callback_count(), 0);
+        assert!(!registry.has_callbacks());
+
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b"));
+        assert_eq!(registry.callback_count(), 2);
+        assert!(registry.has_callbacks());
+
+        registry.remove_callback(id);
+        assert_eq!(registry.callback_count(), 1);
+    }
+
+    /// Teste que le registre est réutilisable après `clear_callbacks`, et que `do_something` sur un registre vide ne fait rien.
+    #[test]
+    fn test_registry_reusable_after_clear() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("first scenario")
+        });
+        registry.clear_callbacks();
+        registry.do_something();
+        assert!(calls.borrow().is_empty());
+
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("second scenario")
+        });
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["second scenario"]);
+    }
+
+    /// Enregistre un callback unique qui ajoute `label` à `calls`.
+    fn push_once_label(
+        registry: &mut CallbackRegistry<'_, CallbackPayload>,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        label: &'static str,
+    ) -> CallbackId {
+        let calls = Rc::clone(calls);
+        registry.set_callback_once(move |_data: &CallbackPayload| calls.borrow_mut().push(label))
+    }
+
+    /// Teste qu'un callback unique entouré de callbacks persistants n'est appelé qu'une fois,
+    /// sans faire sauter ni rappeler ses voisins.
+    #[test]
+    fn test_once_between_persistent_callbacks() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("before"));
+        push_once_label(&mut registry, &calls, "once");
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("after"));
+        assert_eq!(registry.callback_count(), 3);
+
+        registry.do_something();
+        assert_eq!(registry.callback_count(), 2);
+        registry.do_something();
+
+        assert_eq!(
+            *calls.borrow(),
+            vec!["before", "once", "after", "before", "after"]
+        );
+    }
+
+    /// Teste plusieurs callbacks uniques consécutifs autour d'un callback persistant.
+    #[test]
+    fn test_consecutive_once_callbacks() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_once_label(&mut registry, &calls, "once a");
+        push_once_label(&mut registry, &calls, "once b");
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("persistent")
+        });
+
+        registry.do_something();
+        registry.do_something();
+
+        assert_eq!(
+            *calls.borrow(),
+            vec!["once a", "once b", "persistent", "persistent"]
+        );
+        assert_eq!(registry.callback_count(), 1);
+    }
+
+    /// Teste qu'un callback unique déjà appelé ne peut plus être retiré, et que ses données capturées sont détruites.
+    #[test]
+    fn test_once_callback_is_dropped_after_call() {
+        let drops = Rc::new(RefCell::new(0));
+        let guard = DropCounter(Rc::clone(&drops));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let id = registry.set_callback_once(move |_data: &CallbackPayload| {
+            let _guard = &guard;
+        });
+
+        registry.do_something();
+
+        assert_eq!(*drops.borrow(), 1);
+        assert!(!registry.remove_callback(id));
+    }
+
+    /// Enregistre un callback de priorité `priority` qui ajoute `label` à `calls`.
+    fn push_priority_label(
+        registry: &mut CallbackRegistry<'_, CallbackPayload>,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        priority: i32,
+        label: &'static str,
+    ) -> CallbackId {
+        let calls = Rc::clone(calls);
+        registry.set_callback(Callback::with_priority(
+            priority,
+            move |_data: &CallbackPayload| calls.borrow_mut().push(label),
+        ))
+    }
+
+    /// Teste que les callbacks sont appelés par priorité croissante, quel que soit l'ordre d'enregistrement.
+    #[test]
+    fn test_priority_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_priority_label(&mut registry, &calls, 10, "cleanup");
+        push_priority_label(&mut registry, &calls, 0, "default");
+        push_priority_label(&mut registry, &calls, -10, "logging");
+
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["logging", "default", "cleanup"]);
+    }
+
+    /// Teste qu'à priorité égale, l'ordre d'enregistrement est conservé.
+    #[test]
+    fn test_equal_priorities_keep_insertion_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_priority_label(&mut registry, &calls, 0, "default 1");
+        push_priority_label(&mut registry, &calls, 10, "cleanup 1");
+        push_priority_label(&mut registry, &calls, -10, "logging 1");
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("default 2")
+        });
+        push_priority_label(&mut registry, &calls, 10, "cleanup 2");
+        push_priority_label(&mut registry, &calls, -10, "logging 2");
+
+        registry.do_something();
+
+        assert_eq!(
+            *calls.borrow(),
+            vec![
+                "logging 1",
+                "logging 2",
+                "default 1",
+                "default 2",
+                "cleanup 1",
+                "cleanup 2"
+            ]
+        );
+    }
+
+    /// Teste qu'un callback `FnMut` compte exactement le nombre d'appels à `do_something`.
+    #[test]
+    fn test_fn_mut_counter_matches_dispatches() {
+        let seen = Rc::new(RefCell::new(0));
+        let seen_in_cb = Rc::clone(&seen);
+        let mut dispatches = 0;
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(Callback::from_fn_mut(move |_data: &CallbackPayload| {
+            dispatches += 1;
+            *seen_in_cb.borrow_mut() = dispatches;
+        }));
+
+        for _ in 0..5 {
+            registry.do_something();
+        }
+
+        assert_eq!(*seen.borrow(), 5);
+    }
+
+    /// Gestionnaire qui conserve les payloads reçus et les publie dans `report` lorsqu'il est détruit.
+    struct PayloadRecorder {
+        received: Vec<Vec<u8>>,
+        report: Rc<RefCell<Vec<Vec<u8>>>>,
+    }
+
+    impl Handler<CallbackPayload> for PayloadRecorder {
+        fn call(&mut self, data: &CallbackPayload) {
+            self.received.push(data.as_bytes().to_vec());
+        }
+    }
+
+    impl Drop for PayloadRecorder {
+        fn drop(&mut self) {
+            self.report.borrow_mut().append(&mut self.received);
+        }
+    }
+
+    /// Teste qu'un gestionnaire structuré et une closure sont appelés de la même façon par `do_something`.
+    #[test]
+    fn test_handler_and_closure_dispatched_uniformly() {
+        let report = Rc::new(RefCell::new(Vec::new()));
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8, 2]);
+        registry.set_handler(Box::new(PayloadRecorder {
+            received: Vec::new(),
+            report: Rc::clone(&report),
+        }));
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("closure"));
+
+        registry.do_something();
+        registry.set_data(vec![3u8]);
+        registry.do_something();
+        registry.clear_callbacks();
+
+        assert_eq!(*report.borrow(), vec![vec![1u8, 2], vec![3]]);
+        assert_eq!(*calls.borrow(), vec!["closure", "closure"]);
+    }
+
+    /// Teste que `dispatch_collect` renvoie les valeurs dans l'ordre d'appel, sans les callbacks désactivés ou filtrés.
+    #[test]
+    fn test_dispatch_collect_lengths() {
+        let mut registry = CallbackRegistry::with_data(&[4u8, 5, 6][..]);
+        registry.set_callback(Callback::new(|data: &CallbackPayload| {
+            data.as_bytes().len()
+        }));
+        registry.set_callback(Callback::with_priority(-1, |data: &CallbackPayload| {
+            data.as_bytes().len() * 10
+        }));
+        registry.set_callback(Callback::new(|data: &CallbackPayload| {
+            data.as_bytes().len() + 1
+        }));
+        let disabled = registry.set_callback(Callback::new(|_data: &CallbackPayload| 99));
+        registry.set_callback_filtered(
+            |data: &CallbackPayload| data.as_bytes().is_empty(),
+            Callback::new(|_data: &CallbackPayload| 100),
+        );
+        registry.disable_callback(disabled);
+
+        assert_eq!(registry.dispatch_collect(), vec![30, 3, 4]);
+    }
+
+    /// Teste `dispatch_collect` avec des données partagées via `Arc`.
+    #[test]
+    fn test_dispatch_collect_arc_payload() {
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8, 2]);
+        registry.set_callback(Callback::new(|data: &ArcCallbackPayload| data.to_arc()));
+
+        let results = registry.dispatch_collect();
+
+        assert_eq!(&results[0][..], &[1, 2]);
+    }
+}
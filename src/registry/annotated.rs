@@ -0,0 +1,227 @@
+//! Métadonnées d'appel (heure, origine et numéro) jointes aux données des callbacks annotés.
+
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+/// Origine des données, partagée entre le registre et ses callbacks annotés.
+pub(crate) type SourceSlot = Rc<RefCell<Option<Rc<str>>>>;
+
+/// Données d'un appel accompagnées de leurs métadonnées, reçues par les callbacks enregistrés
+/// avec [`CallbackRegistry::set_callback_annotated`].
+///
+/// Les métadonnées sont fixées une fois par appel à `do_something` : tous les callbacks d'un
+/// même appel voient les mêmes. `AnnotatedData` déréférence vers les données.
+#[derive(Debug, Clone)]
+pub struct AnnotatedData<'d, T: ?Sized> {
+    data: &'d T,             // Les données de l'appel.
+    timestamp: SystemTime,   // Heure système du début de l'appel.
+    source: Option<Rc<str>>, // Origine des données, voir `CallbackRegistry::set_source`.
+    seq: u64,                // Numéro de l'appel, à partir de 1.
+}
+
+impl<'d, T: ?Sized> AnnotatedData<'d, T> {
+    /// Renvoie les données de l'appel.
+    pub fn data(&self) -> &'d T {
+        self.data
+    }
+
+    /// Renvoie l'heure système du début de l'appel.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Renvoie l'origine des données, ou `None` si le registre n'en a pas.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Renvoie le numéro de l'appel, qui augmente d'un à chaque `do_something`.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+}
+
+impl<T: ?Sized> Deref for AnnotatedData<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data
+    }
+}
+
+/// Sérialise les données et leurs métadonnées en `{ data, timestamp, source, seq }`.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + ?Sized> serde::Serialize for AnnotatedData<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut annotated = serializer.serialize_struct("AnnotatedData", 4)?;
+        annotated.serialize_field("data", self.data)?;
+        annotated.serialize_field("timestamp", &self.timestamp)?;
+        annotated.serialize_field("source", &self.source())?;
+        annotated.serialize_field("seq", &self.seq)?;
+        annotated.end()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Définit l'origine des données, transmise aux callbacks annotés à partir du prochain appel.
+    pub fn set_source(&mut self, source: impl Into<String>) {
+        *self.source.borrow_mut() = Some(Rc::from(source.into()));
+    }
+
+    /// Oublie l'origine des données.
+    pub fn clear_source(&mut self) {
+        self.source.borrow_mut().take();
+    }
+
+    /// Renvoie l'origine des données, ou `None` si elle n'a pas été définie.
+    pub fn source(&self) -> Option<Rc<str>> {
+        self.source.borrow().clone()
+    }
+
+    /// Enregistre `f`, qui reçoit les données accompagnées de l'heure, de l'origine et du numéro
+    /// de l'appel. Les callbacks ordinaires ne paient rien pour ces métadonnées.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{AnnotatedData, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_source("capteur-1");
+    /// registry.set_callback_annotated(|data: &AnnotatedData<'_, CallbackPayload>| {
+    ///     assert_eq!(data.source(), Some("capteur-1"));
+    ///     println!("appel n°{} : {:?}", data.seq(), data.as_bytes());
+    /// });
+    /// registry.do_something();
+    /// ```
+    pub fn set_callback_annotated(
+        &mut self,
+        f: impl Fn(&AnnotatedData<'_, T>) -> R + 'static,
+    ) -> CallbackId
+    where
+        T: 'static,
+    {
+        let context = Rc::clone(&self.context);
+        let source = Rc::clone(&self.source);
+        self.push_callback(Callback::new(move |data: &T| {
+            // Le contexte est défini par le registre juste avant chaque appel.
+            let ctx = context.get().expect("callback appelé hors d'un dispatch");
+            f(&AnnotatedData {
+                data,
+                timestamp: ctx.wall_time,
+                source: source.borrow().clone(),
+                seq: ctx.dispatch_seq,
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+
+    /// Teste que le numéro augmente d'un par appel à `do_something`.
+    #[test]
+    fn test_sequence_increments_across_dispatches() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_annotated(move |data: &AnnotatedData<'_, CallbackPayload>| {
+            seen_in_cb.borrow_mut().push(data.seq())
+        });
+
+        registry.do_something();
+        registry.do_something();
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+    }
+
+    /// Teste que deux callbacks d'un même appel voient exactement les mêmes métadonnées.
+    #[test]
+    fn test_same_dispatch_sees_identical_metadata() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_source("bus-can");
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_annotated(move |data: &AnnotatedData<'_, CallbackPayload>| {
+            seen_in_cb.borrow_mut().push((
+                data.seq(),
+                data.timestamp(),
+                data.source().map(str::to_string),
+            ))
+        });
+        registry.set_callback(|_data: &CallbackPayload| {});
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_annotated(move |data: &AnnotatedData<'_, CallbackPayload>| {
+            seen_in_cb.borrow_mut().push((
+                data.seq(),
+                data.timestamp(),
+                data.source().map(str::to_string),
+            ))
+        });
+
+        registry.do_something();
+
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0], seen[1]);
+        assert_eq!(seen[0].2.as_deref(), Some("bus-can"));
+    }
+
+    /// Teste que l'origine suit `set_source` et `clear_source` et que les données restent accessibles.
+    #[test]
+    fn test_source_changes_between_dispatches() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[7u8, 8][..]);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_annotated(move |data: &AnnotatedData<'_, CallbackPayload>| {
+            assert_eq!(data.as_bytes(), &[7, 8]);
+            seen_in_cb
+                .borrow_mut()
+                .push(data.source().map(str::to_string))
+        });
+
+        registry.do_something();
+        registry.set_source("fichier");
+        registry.do_something();
+        registry.clear_source();
+        registry.do_something();
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![None, Some("fichier".to_string()), None]
+        );
+        assert_eq!(registry.source(), None);
+    }
+
+    /// Teste que les métadonnées sont sérialisées avec les données.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_annotated_data_serializes_metadata() {
+        let json = Rc::new(RefCell::new(String::new()));
+        let mut registry = CallbackRegistry::with_data(&[0x01u8, 0x02][..]);
+        registry.set_source("capteur");
+        let json_in_cb = Rc::clone(&json);
+        registry.set_callback_annotated(move |data: &AnnotatedData<'_, CallbackPayload>| {
+            *json_in_cb.borrow_mut() = serde_json::to_string(data).unwrap()
+        });
+
+        registry.do_something();
+
+        let value: serde_json::Value = serde_json::from_str(&json.borrow()).unwrap();
+        assert_eq!(value["data"], "0102");
+        assert_eq!(value["source"], "capteur");
+        assert_eq!(value["seq"], 1);
+        assert!(value["timestamp"]["secs_since_epoch"].as_u64().unwrap() > 0);
+    }
+}
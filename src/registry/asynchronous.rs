@@ -0,0 +1,252 @@
+//! Callbacks asynchrones, attendus par `dispatch_async` (feature `async`).
+//!
+//! Aucun exécuteur n'est imposé : le futur renvoyé par `dispatch_async` s'attend avec celui de
+//! l'application, par exemple `tokio`.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::callback::{CallbackData, CallbackId};
+use crate::data::ArcCallbackPayload;
+
+/// Futur dans une boîte, renvoyé par un [`AsyncCallback`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Closure d'un [`AsyncCallback`], qui crée le futur de chaque appel.
+type AsyncFn<T> = Box<dyn for<'d> Fn(&'d T) -> BoxFuture<'d, ()> + Send + Sync>;
+
+/// Callback asynchrone : une closure qui renvoie le futur à attendre pour chaque donnée.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{ArcCallbackPayload, AsyncCallback};
+///
+/// let callback = AsyncCallback::new(|data: &ArcCallbackPayload| {
+///     Box::pin(async move { println!("{:?}", data.as_bytes()) })
+/// });
+/// ```
+pub struct AsyncCallback<T: ?Sized> {
+    f: AsyncFn<T>, // Crée le futur de chaque appel.
+}
+
+impl<T: ?Sized> AsyncCallback<T> {
+    /// Crée un callback asynchrone à partir de la closure `f`.
+    pub fn new(f: impl for<'d> Fn(&'d T) -> BoxFuture<'d, ()> + Send + Sync + 'static) -> Self {
+        AsyncCallback { f: Box::new(f) }
+    }
+
+    /// Renvoie le futur de l'appel du callback avec `data`.
+    pub fn call<'d>(&self, data: &'d T) -> BoxFuture<'d, ()> {
+        (self.f)(data)
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for AsyncCallback<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncCallback").finish_non_exhaustive()
+    }
+}
+
+/// Comment `dispatch_async` attend les callbacks asynchrones, voir
+/// [`CallbackRegistry::set_async_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsyncMode {
+    /// Chaque callback est attendu avant d'appeler le suivant, dans l'ordre d'enregistrement.
+    #[default]
+    Sequential,
+    /// Tous les callbacks sont appelés d'un coup, puis attendus ensemble.
+    Concurrent,
+}
+
+/// Callbacks de `dispatch_async` et la façon de les attendre.
+pub(crate) struct AsyncCallbacks<T: ?Sized> {
+    pub(crate) callbacks: Vec<(CallbackId, AsyncCallback<T>)>, // Dans l'ordre d'enregistrement.
+    mode: AsyncMode,                                           // Attente en série ou ensemble.
+}
+
+impl<T: ?Sized> Default for AsyncCallbacks<T> {
+    fn default() -> Self {
+        AsyncCallbacks {
+            callbacks: Vec::new(),
+            mode: AsyncMode::default(),
+        }
+    }
+}
+
+/// Futur qui attend tous les `futures`, dans n'importe quel ordre.
+struct JoinAll<'a> {
+    futures: Vec<Option<BoxFuture<'a, ()>>>, // `None` une fois le futur terminé.
+}
+
+impl Future for JoinAll<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut pending = false;
+        for slot in &mut self.futures {
+            if let Some(future) = slot {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(()) => *slot = None,
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre le callback asynchrone `callback`, attendu par `dispatch_async`.
+    ///
+    /// Ces callbacks forment une chaîne distincte : ils ne sont ni comptés par `callback_count`,
+    /// ni appelés par `do_something` ; utilisez
+    /// [`remove_async_callback`](Self::remove_async_callback) pour les retirer.
+    pub fn set_async_callback(&mut self, callback: AsyncCallback<T>) -> CallbackId {
+        let id = self.next_id();
+        self.async_callbacks.callbacks.push((id, callback));
+        id
+    }
+
+    /// Retire le callback asynchrone `id` et indique s'il était enregistré.
+    pub fn remove_async_callback(&mut self, id: CallbackId) -> bool {
+        let callbacks = &mut self.async_callbacks.callbacks;
+        let before = callbacks.len();
+        callbacks.retain(|(other, _)| *other != id);
+        let removed = before != callbacks.len();
+        if removed {
+            self.callbacks.ids().borrow_mut().release(id);
+        }
+        removed
+    }
+
+    /// Choisit comment `dispatch_async` attend les callbacks asynchrones, en série par défaut.
+    pub fn set_async_mode(&mut self, mode: AsyncMode) {
+        self.async_callbacks.mode = mode;
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Transmet `data` comme [`dispatch`](super::CallbackHost::dispatch) aux callbacks
+    /// ordinaires, puis attend les callbacks asynchrones avec les mêmes données, selon le mode
+    /// choisi par [`set_async_mode`](Self::set_async_mode).
+    ///
+    /// Les callbacks asynchrones reçoivent les données validées et transformées ; ils ne sont pas
+    /// appelés quand l'appel entier est ignoré (pause, validation, données inchangées).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{ArcCallbackPayload, AsyncCallback, CallbackRegistry};
+    /// use std::sync::Arc;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut registry: CallbackRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+    /// registry.set_async_callback(AsyncCallback::new(|data: &ArcCallbackPayload| {
+    ///     Box::pin(async move { println!("{:?}", data.as_bytes()) })
+    /// }));
+    /// registry.dispatch_async(Arc::from(&[1u8, 2][..])).await;
+    /// # });
+    /// ```
+    pub async fn dispatch_async(&self, data: Arc<[u8]>) {
+        let cb_data = match self.prepare_shared(&data, || Arc::clone(&data), false) {
+            Ok(Some(cb_data)) => cb_data,
+            Ok(None) => return,
+            Err(error) => {
+                self.report_invalid(error);
+                return;
+            }
+        };
+        self.dispatch_processed(
+            &cb_data,
+            cb_data.as_bytes(),
+            |_| true,
+            Entry::invoke,
+            ignore_result,
+        );
+        let callbacks = &self.async_callbacks.callbacks;
+        match self.async_callbacks.mode {
+            AsyncMode::Sequential => {
+                for (_, callback) in callbacks {
+                    callback.call(&cb_data).await;
+                }
+            }
+            AsyncMode::Concurrent => {
+                let futures = callbacks
+                    .iter()
+                    .map(|(_, callback)| Some(callback.call(&cb_data)))
+                    .collect();
+                JoinAll { futures }.await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// Crée un registre avec un callback ordinaire, un callback asynchrone qui attend 50 ms puis
+    /// écrit `"lent"`, et un autre qui écrit le premier byte des données, dans `log`.
+    fn logging_registry(
+        log: &Arc<Mutex<Vec<String>>>,
+    ) -> CallbackRegistry<'static, ArcCallbackPayload> {
+        let mut registry = CallbackRegistry::new();
+        let sync_log = Arc::clone(log);
+        registry.set_callback(Callback::new(move |_data: &ArcCallbackPayload| {
+            sync_log.lock().unwrap().push("ordinaire".to_string())
+        }));
+        let slow_log = Arc::clone(log);
+        registry.set_async_callback(AsyncCallback::new(move |_data: &ArcCallbackPayload| {
+            let log = Arc::clone(&slow_log);
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                log.lock().unwrap().push("lent".to_string());
+            })
+        }));
+        let fast_log = Arc::clone(log);
+        registry.set_async_callback(AsyncCallback::new(move |data: &ArcCallbackPayload| {
+            let log = Arc::clone(&fast_log);
+            Box::pin(async move { log.lock().unwrap().push(data.as_bytes()[0].to_string()) })
+        }));
+        registry
+    }
+
+    /// Teste qu'en série, le callback ordinaire puis chaque callback asynchrone sont appelés
+    /// dans l'ordre d'enregistrement.
+    #[tokio::test]
+    async fn test_sequential_dispatch_async() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let registry = logging_registry(&log);
+        registry.dispatch_async(Arc::from(&[7u8][..])).await;
+        assert_eq!(*log.lock().unwrap(), vec!["ordinaire", "lent", "7"]);
+    }
+
+    /// Teste qu'ensemble, le callback rapide se termine pendant l'attente du callback lent.
+    #[tokio::test]
+    async fn test_concurrent_dispatch_async() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = logging_registry(&log);
+        registry.set_async_mode(AsyncMode::Concurrent);
+        let start = Instant::now();
+        registry.dispatch_async(Arc::from(&[7u8][..])).await;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(*log.lock().unwrap(), vec!["ordinaire", "7", "lent"]);
+
+        registry.pause();
+        registry.dispatch_async(Arc::from(&[8u8][..])).await;
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,164 @@
+//! Appel des callbacks pour un lot de données, avec un seul bilan et un seul traitement.
+
+use std::sync::Arc;
+
+use super::{ignore_result, BatchContext, CallbackRegistry, DispatchReport, Entry};
+use crate::callback::CallbackData;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Appelle `dispatch` pour chaque donnée de `payloads`, avec son [`BatchContext`], et renvoie
+    /// le bilan de tout le lot. Le traitement des données est fait une fois par lot en mode
+    /// [`ProcessingMode::PerBatch`](crate::ProcessingMode::PerBatch).
+    fn dispatch_each(
+        &self,
+        payloads: &[&[u8]],
+        dispatch: impl Fn(&Self, &[u8], &dyn Fn(&Entry<T, R>, &T) -> Option<Result<R, ()>>),
+    ) -> DispatchReport {
+        if payloads.is_empty() {
+            return DispatchReport::default();
+        }
+        self.processor.begin_batch();
+        let report = self.measure_dispatches(payloads.len(), |invoke| {
+            for (index, payload) in payloads.iter().enumerate() {
+                self.batch.set(Some(BatchContext {
+                    index,
+                    len: payloads.len(),
+                }));
+                dispatch(self, payload, invoke);
+            }
+        });
+        self.batch.set(None);
+        self.processor.end_batch();
+        report
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Appelle les callbacks pour chaque donnée de `payloads`, dans l'ordre, comme
+    /// [`dispatch_report`](Self::dispatch_report), et renvoie le bilan de tout le lot.
+    ///
+    /// Le bilan et la mesure de la durée sont faits une fois pour tout le lot, ainsi que le
+    /// traitement des données en mode [`ProcessingMode::PerBatch`](crate::ProcessingMode::PerBatch).
+    /// Chaque donnée reste un appel à part entière, avec son propre numéro d'appel ; les callbacks
+    /// contextuels y trouvent sa position dans [`CallbackContext::batch`](crate::CallbackContext::batch).
+    /// Un lot vide n'appelle rien.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackContext, CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_callback_with_ctx(|ctx: &CallbackContext, data: &CallbackPayload| {
+    ///     let batch = ctx.batch.unwrap();
+    ///     println!("{}/{} : {:?}", batch.index + 1, batch.len, data);
+    /// });
+    /// let report = registry.dispatch_batch(&[&[1, 2], &[3], &[4, 5, 6]]);
+    /// assert_eq!(report.callbacks_invoked, 3);
+    /// ```
+    pub fn dispatch_batch(&self, payloads: &[&[u8]]) -> DispatchReport {
+        self.dispatch_each(payloads, |registry, payload, invoke| {
+            let dispatched =
+                registry.dispatch_bytes(payload, |_| true, invoke, ignore_result, false);
+            if let Err(error) = dispatched {
+                registry.report_invalid(error);
+            }
+        })
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Appelle les callbacks pour chaque donnée de `payloads`, dans l'ordre, et renvoie le bilan
+    /// de tout le lot, voir le `dispatch_batch` des registres de [`CallbackPayload`].
+    pub fn dispatch_batch(&self, payloads: &[&[u8]]) -> DispatchReport {
+        self.dispatch_each(payloads, |registry, payload, invoke| {
+            let dispatched = registry.dispatch_shared(
+                payload,
+                || Arc::from(payload),
+                |_| true,
+                invoke,
+                ignore_result,
+                false,
+            );
+            if let Err(error) = dispatched {
+                registry.report_invalid(error);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::error::ProcessError;
+    use crate::registry::{CallbackContext, CallbackHost, ProcessingMode};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste le nombre d'appels et les positions vues par un callback contextuel.
+    #[test]
+    fn test_invocation_counts_and_batch_context() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_with_ctx(move |ctx: &CallbackContext, data: &CallbackPayload| {
+            seen_in_cb
+                .borrow_mut()
+                .push((ctx.batch, data.as_bytes()[0]))
+        });
+        registry.set_callback_filtered(
+            |data: &CallbackPayload| data.as_bytes()[0] == 2,
+            Callback::new(|_data: &CallbackPayload| {}),
+        );
+
+        let report = registry.dispatch_batch(&[&[1], &[2], &[3]]);
+        assert_eq!((report.callbacks_invoked, report.skipped), (4, 2));
+        let batch = |index| Some(BatchContext { index, len: 3 });
+        assert_eq!(
+            *seen.borrow(),
+            vec![(batch(0), 1), (batch(1), 2), (batch(2), 3)]
+        );
+
+        registry.dispatch(&[9]).unwrap();
+        assert_eq!(*seen.borrow().last().unwrap(), (None, 9));
+    }
+
+    /// Teste qu'un lot vide n'appelle rien, pas même le traitement.
+    #[test]
+    fn test_empty_batch_is_a_no_op() {
+        let processed = Rc::new(RefCell::new(0));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        registry.set_callback(Callback::new(|_data: &CallbackPayload| unreachable!()));
+        let processed_in_processor = Rc::clone(&processed);
+        registry.set_processor(Box::new(move |_data: &[u8]| {
+            *processed_in_processor.borrow_mut() += 1;
+            Ok::<(), ProcessError>(())
+        }));
+        assert_eq!(registry.dispatch_batch(&[]), DispatchReport::default());
+        assert_eq!(*processed.borrow(), 0);
+    }
+
+    /// Teste le traitement une fois par donnée, puis une fois par lot.
+    #[test]
+    fn test_processing_per_item_or_per_batch() {
+        let processed = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, ArcCallbackPayload> = CallbackRegistry::new();
+        registry.set_callback(Callback::new(|_data: &ArcCallbackPayload| {}));
+        let processed_in_processor = Rc::clone(&processed);
+        registry.set_processor(Box::new(move |data: &[u8]| {
+            processed_in_processor.borrow_mut().push(data.to_vec());
+            Ok(())
+        }));
+
+        registry.dispatch_batch(&[&[1], &[2, 3]]);
+        assert_eq!(*processed.borrow(), vec![vec![1], vec![2, 3]]);
+
+        processed.borrow_mut().clear();
+        registry.set_processing_mode(ProcessingMode::PerBatch);
+        registry.dispatch_batch(&[&[1], &[2, 3]]);
+        registry.dispatch(&[4]).unwrap();
+        assert_eq!(*processed.borrow(), vec![vec![1, 2, 3], vec![4]]);
+    }
+}
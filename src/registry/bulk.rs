@@ -0,0 +1,98 @@
+//! Enregistrement de plusieurs callbacks en une fois.
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre tous les callbacks de `cbs`, dans l'ordre, et renvoie leurs identifiants.
+    ///
+    /// La place nécessaire est réservée d'un coup d'après la taille annoncée par l'itérateur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let ids = registry.set_callbacks(
+    ///     (0..30).map(|i| Callback::new(move |_data: &CallbackPayload| println!("handler {}", i))),
+    /// );
+    /// assert_eq!(ids.len(), 30);
+    /// assert_eq!(registry.callback_count(), 30);
+    /// ```
+    pub fn set_callbacks(
+        &mut self,
+        cbs: impl IntoIterator<Item = Callback<T, R>>,
+    ) -> Vec<CallbackId> {
+        let cbs = cbs.into_iter();
+        self.callbacks.reserve(cbs.size_hint().0);
+        cbs.map(|cb| self.push_callback(cb)).collect()
+    }
+}
+
+/// Enregistre chaque callback de l'itérateur, comme [`CallbackRegistry::set_callbacks`].
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> Extend<Callback<T, R>>
+    for CallbackRegistry<'a, T, D, R>
+{
+    fn extend<I: IntoIterator<Item = Callback<T, R>>>(&mut self, iter: I) {
+        self.set_callbacks(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Crée `count` callbacks qui ajoutent leur rang à `calls`.
+    fn numbered(
+        calls: &Rc<RefCell<Vec<usize>>>,
+        count: usize,
+    ) -> impl Iterator<Item = Callback<CallbackPayload>> + '_ {
+        (0..count).map(move |i| {
+            let calls = Rc::clone(calls);
+            Callback::new(move |_data: &CallbackPayload| calls.borrow_mut().push(i))
+        })
+    }
+
+    /// Teste que l'ordre d'enregistrement est conservé et que les identifiants sont renvoyés dans l'ordre.
+    #[test]
+    fn test_set_callbacks_preserves_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+
+        let ids = registry.set_callbacks(numbered(&calls, 5));
+        registry.do_something();
+
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(*calls.borrow(), vec![0, 1, 2, 3, 4]);
+    }
+
+    /// Teste que la capacité n'augmente qu'une fois pour un itérateur de taille connue.
+    #[test]
+    fn test_set_callbacks_reserves_once() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+
+        registry.set_callbacks(numbered(&calls, 30));
+
+        // Trente `push` successifs auraient fait passer la capacité par 4, 8, 16 puis 32.
+        assert_eq!(registry.callbacks.capacity(), 30);
+    }
+
+    /// Teste que `Extend` enregistre les callbacks à la suite des existants.
+    #[test]
+    fn test_extend_appends_callbacks() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.extend(numbered(&calls, 2));
+        registry.extend(numbered(&calls, 1));
+
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec![0, 1, 0]);
+    }
+}
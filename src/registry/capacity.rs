@@ -0,0 +1,122 @@
+//! Nombre maximal de callbacks et enregistrement faillible.
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::error::RegistryFull;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Limite le registre à `limit` callbacks, ou retire la limite avec `None`.
+    ///
+    /// Une fois la limite atteinte, [`try_set_callback`](Self::try_set_callback) renvoie une erreur et
+    /// les autres méthodes d'enregistrement, dont `set_callback`, paniquent. Les callbacks déjà
+    /// enregistrés au-delà d'une nouvelle limite sont conservés.
+    pub fn set_max_callbacks(&mut self, limit: Option<usize>) {
+        self.max_callbacks = limit;
+    }
+
+    /// Renvoie le nombre maximal de callbacks, ou `None` si le registre est illimité.
+    pub fn max_callbacks(&self) -> Option<usize> {
+        self.max_callbacks
+    }
+
+    /// Enregistre `cb`, sauf si le registre a atteint son nombre maximal de callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`RegistryFull`] si le registre est plein ; il n'est alors pas modifié.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::builder()
+    ///     .with_max_callbacks(1)
+    ///     .with_data(&[1, 2, 3])
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(registry.try_set_callback(Callback::new(|_data: &CallbackPayload| {})).is_ok());
+    /// let full = registry.try_set_callback(Callback::new(|_data: &CallbackPayload| {})).unwrap_err();
+    /// assert_eq!((full.count, full.limit), (1, 1));
+    /// ```
+    pub fn try_set_callback(&mut self, cb: Callback<T, R>) -> Result<CallbackId, RegistryFull> {
+        self.check_capacity()?;
+        Ok(self.push_callback(cb))
+    }
+
+    /// Vérifie qu'un callback de plus peut être enregistré, après avoir libéré les entrées épuisées.
+    pub(crate) fn check_capacity(&mut self) -> Result<(), RegistryFull> {
+        self.prune_spent();
+        match self.max_callbacks {
+            Some(limit) if self.callbacks.len() >= limit => Err(RegistryFull {
+                count: self.callbacks.len(),
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste qu'un troisième enregistrement échoue sans toucher aux deux premiers.
+    #[test]
+    fn test_third_registration_fails() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_max_callbacks(Some(2));
+        for label in ["a", "b"] {
+            let calls_in_cb = Rc::clone(&calls);
+            registry
+                .try_set_callback(Callback::new(move |_data: &CallbackPayload| {
+                    calls_in_cb.borrow_mut().push(label)
+                }))
+                .unwrap();
+        }
+
+        let error = registry
+            .try_set_callback(Callback::new(|_data: &CallbackPayload| {}))
+            .unwrap_err();
+        registry.do_something();
+
+        assert_eq!(error, RegistryFull { count: 2, limit: 2 });
+        assert_eq!(registry.callback_count(), 2);
+        assert_eq!(*calls.borrow(), vec!["a", "b"]);
+    }
+
+    /// Teste qu'une place libérée par un retrait peut être réutilisée.
+    #[test]
+    fn test_removal_frees_a_slot() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_max_callbacks(Some(2));
+        let id = registry
+            .try_set_callback(Callback::new(|_data: &CallbackPayload| {}))
+            .unwrap();
+        registry
+            .try_set_callback(Callback::new(|_data: &CallbackPayload| {}))
+            .unwrap();
+
+        registry.remove_callback(id);
+
+        assert!(registry
+            .try_set_callback(Callback::new(|_data: &CallbackPayload| {}))
+            .is_ok());
+    }
+
+    /// Teste que `set_callback` panique sur un registre plein.
+    #[test]
+    #[should_panic(expected = "le registre est plein")]
+    fn test_set_callback_panics_when_full() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_max_callbacks(Some(2));
+        registry.set_callback(|_data: &CallbackPayload| {});
+        registry.set_callback(|_data: &CallbackPayload| {});
+        registry.set_callback(|_data: &CallbackPayload| {});
+    }
+}
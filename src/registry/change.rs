@@ -0,0 +1,175 @@
+//! Détection des changements : ne rappelle les callbacks que si les données ont changé.
+
+use std::cell::RefCell;
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::callback::CallbackData;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Dernières données transmises aux callbacks, `None` avant le premier appel.
+pub(crate) type LastPayload = RefCell<Option<Vec<u8>>>;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Active ou désactive la détection des changements.
+    ///
+    /// Une fois activée, `do_something` n'appelle les callbacks que si les données qu'ils
+    /// recevraient (après somme de contrôle et transformations) diffèrent des dernières qui leur
+    /// ont été transmises. Un appel ignoré ne modifie donc pas cette référence : la séquence
+    /// A, A, B, A appelle les callbacks pour le premier A, pour B puis pour le dernier A. Les
+    /// données refusées par la validation et les appels pendant une pause ne la modifient pas
+    /// non plus. Désactiver la détection oublie la référence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, OwnedRegistry};
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// let calls = Rc::new(Cell::new(0));
+    /// let calls_in_cb = Rc::clone(&calls);
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![1, 2, 3]);
+    /// registry.dispatch_on_change(true);
+    /// registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.set(calls_in_cb.get() + 1));
+    /// registry.do_something();
+    /// registry.do_something(); // Mêmes données : ignoré.
+    /// registry.force_dispatch();
+    /// assert_eq!(calls.get(), 2);
+    /// ```
+    pub fn dispatch_on_change(&mut self, enabled: bool) {
+        self.last_payload = enabled.then(LastPayload::default);
+    }
+
+    /// Indique si les données `data` doivent être transmises aux callbacks, et les retient comme
+    /// dernières données transmises si c'est le cas. Sans détection des changements, renvoie
+    /// toujours `true`. Avec `force`, les données sont transmises même si elles n'ont pas changé.
+    pub(crate) fn record_change(&self, data: &[u8], force: bool) -> bool {
+        let Some(last) = &self.last_payload else {
+            return true;
+        };
+        let mut last = last.borrow_mut();
+        match last.as_mut() {
+            // La comparaison de slices vérifie d'abord la longueur.
+            Some(previous) if previous.as_slice() == data => force,
+            Some(previous) => {
+                previous.clear();
+                previous.extend_from_slice(data);
+                true
+            }
+            None => {
+                *last = Some(data.to_vec());
+                true
+            }
+        }
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Comme `do_something`, mais appelle les callbacks même si les données n'ont pas changé
+    /// depuis le dernier appel, voir [`dispatch_on_change`](Self::dispatch_on_change).
+    pub fn force_dispatch(&self) {
+        if let Err(error) = self.dispatch_checked(|_| true, Entry::invoke, ignore_result, true) {
+            self.report_invalid(error);
+        }
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Comme `do_something`, mais appelle les callbacks même si les données n'ont pas changé
+    /// depuis le dernier appel, voir [`dispatch_on_change`](Self::dispatch_on_change).
+    pub fn force_dispatch(&self) {
+        if let Err(error) = self.dispatch_checked(|_| true, Entry::invoke, ignore_result, true) {
+            self.report_invalid(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use crate::ValidationError;
+    use std::rc::Rc;
+
+    /// Teste la séquence A, A, B, A : seul le deuxième A est ignoré.
+    #[test]
+    fn test_sequence_a_a_b_a() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.dispatch_on_change(true);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        });
+        for payload in [b"AAA", b"AAA", b"BBB", b"AAA"] {
+            registry.set_data(payload.to_vec());
+            registry.do_something();
+        }
+        assert_eq!(
+            *seen.borrow(),
+            vec![b"AAA".to_vec(), b"BBB".to_vec(), b"AAA".to_vec()]
+        );
+    }
+
+    /// Teste qu'un préfixe des dernières données est considéré comme un changement.
+    #[test]
+    fn test_length_change_is_a_change() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.dispatch_on_change(true);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        });
+        for payload in [&[1u8, 2][..], &[1], &[1], &[]] {
+            registry.set_data(payload.to_vec());
+            registry.do_something();
+        }
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![1], vec![]]);
+    }
+
+    /// Teste que `force_dispatch` appelle les callbacks sans changement, et que les données
+    /// refusées ne remplacent pas la référence.
+    #[test]
+    fn test_force_and_rejected_payloads() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.dispatch_on_change(true);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().to_vec())
+        });
+        registry.set_validator(|data: &[u8]| match data {
+            b"BAD" => Err(ValidationError::new("refusé")),
+            _ => Ok(()),
+        });
+        for payload in [b"AAA", b"BAD", b"AAA"] {
+            registry.set_data(payload.to_vec());
+            registry.do_something();
+        }
+        registry.force_dispatch();
+        assert_eq!(*seen.borrow(), vec![b"AAA".to_vec(), b"AAA".to_vec()]);
+
+        registry.dispatch_on_change(false);
+        registry.do_something();
+        assert_eq!(seen.borrow().len(), 3);
+    }
+
+    /// Teste la détection des changements sur des données partagées.
+    #[test]
+    fn test_arc_registry_skips_unchanged() {
+        let calls = Rc::new(RefCell::new(0));
+        let mut registry = CallbackRegistry::with_owned_data(vec![5u8]);
+        registry.dispatch_on_change(true);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &ArcCallbackPayload| *calls_in_cb.borrow_mut() += 1);
+
+        registry.do_something();
+        registry.do_something();
+        registry.force_dispatch();
+        assert_eq!(*calls.borrow(), 2);
+    }
+}
@@ -0,0 +1,126 @@
+//! Transmission des données aux callbacks en morceaux de taille fixe.
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::data::chunk::{split, PartialChunk};
+use crate::data::CallbackPayload;
+use crate::error::ChunkError;
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Découpe les données en morceaux de `chunk_size` bytes et appelle les callbacks une fois par
+    /// morceau, dans l'ordre, comme autant d'appels à `do_something`.
+    ///
+    /// Les données sont validées et transformées d'un bloc avant le découpage ; `partial` choisit
+    /// le sort d'un dernier morceau incomplet.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`ChunkError::ZeroSize`] si `chunk_size` vaut 0, ou [`ChunkError::Partial`] si le
+    /// dernier morceau est incomplet avec [`PartialChunk::Error`] ; aucun callback n'a alors été
+    /// appelé.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry, PartialChunk};
+    ///
+    /// // Trois enregistrements de 4 bytes.
+    /// let mut registry = CallbackRegistry::with_data(&[0u8; 12][..]);
+    /// registry.set_callback(Callback::new(|record: &CallbackPayload| {
+    ///     assert_eq!(record.as_bytes().len(), 4)
+    /// }));
+    /// registry.dispatch_chunks(4, PartialChunk::Error).unwrap();
+    /// ```
+    pub fn dispatch_chunks(
+        &self,
+        chunk_size: usize,
+        partial: PartialChunk,
+    ) -> Result<(), ChunkError> {
+        if chunk_size == 0 {
+            return Err(ChunkError::ZeroSize);
+        }
+        let raw = self.data.get().as_ref();
+        if !self.begin_dispatch() || !self.admit_payload_size(raw.len()) || !self.admit_rate(raw) {
+            return Ok(());
+        }
+        let data = match self.validate(raw).and_then(|data| self.transform(data)) {
+            Ok(data) => data,
+            Err(error) => {
+                self.report_invalid(error);
+                return Ok(());
+            }
+        };
+        for chunk in split(&data, chunk_size, partial)? {
+            self.dispatch_processed(
+                CallbackPayload::new(chunk),
+                chunk,
+                |_| true,
+                Entry::invoke,
+                ignore_result,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste un multiple exact puis des données plus courtes qu'un morceau.
+    #[test]
+    fn test_exact_multiple_and_smaller_payload() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut exact = CallbackRegistry::with_data(&[1u8, 2, 3, 4, 5, 6][..]);
+        let seen_in_cb = Rc::clone(&seen);
+        exact.set_callback(Callback::new(move |chunk: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(chunk.as_bytes().to_vec())
+        }));
+        exact.dispatch_chunks(3, PartialChunk::Error).unwrap();
+        assert_eq!(*seen.borrow(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        seen.borrow_mut().clear();
+        let mut smaller = CallbackRegistry::with_data(&[1u8, 2][..]);
+        let seen_in_cb = Rc::clone(&seen);
+        smaller.set_callback(Callback::new(move |chunk: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(chunk.as_bytes().to_vec())
+        }));
+        smaller.dispatch_chunks(8, PartialChunk::Deliver).unwrap();
+        assert_eq!(*seen.borrow(), vec![vec![1, 2]]);
+    }
+
+    /// Teste chaque politique sur un dernier morceau incomplet, et le refus d'une taille nulle.
+    #[test]
+    fn test_trailing_remainder_policies() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3, 4, 5][..]);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |chunk: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(chunk.as_bytes().to_vec())
+        }));
+
+        registry.dispatch_chunks(2, PartialChunk::Deliver).unwrap();
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        seen.borrow_mut().clear();
+        registry.dispatch_chunks(2, PartialChunk::Drop).unwrap();
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![3, 4]]);
+
+        seen.borrow_mut().clear();
+        assert_eq!(
+            registry.dispatch_chunks(2, PartialChunk::Error),
+            Err(ChunkError::Partial {
+                len: 1,
+                chunk_size: 2
+            })
+        );
+        assert_eq!(
+            registry.dispatch_chunks(0, PartialChunk::Deliver),
+            Err(ChunkError::ZeroSize)
+        );
+        assert!(seen.borrow().is_empty());
+    }
+}
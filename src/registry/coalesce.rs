@@ -0,0 +1,143 @@
+//! Fusion d'une rafale de données en un seul appel des callbacks.
+
+use super::{CallbackHost, CallbackRegistry};
+use crate::callback::CallbackData;
+
+/// Fonction qui fusionne les données d'une rafale, de la plus ancienne à la plus récente.
+pub(crate) type MergeFn = Box<dyn Fn(&[&[u8]]) -> Vec<u8>>;
+
+/// Manière de fusionner une rafale de données, voir [`CallbackRegistry::dispatch_coalesced`].
+pub enum Coalesce {
+    /// Ne garde que les données les plus récentes.
+    Last,
+    /// Met bout à bout les bytes de toutes les données, dans l'ordre.
+    Concat,
+    /// Fusionne les données avec la fonction donnée, qui les reçoit de la plus ancienne à la plus
+    /// récente.
+    Custom(MergeFn),
+}
+
+impl Coalesce {
+    /// Fusionne `payloads`, qui n'est pas vide.
+    fn merge(&self, payloads: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Coalesce::Last => payloads.last().map_or_else(Vec::new, |data| data.to_vec()),
+            Coalesce::Concat => payloads.concat(),
+            Coalesce::Custom(merge) => merge(payloads),
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, R> CallbackRegistry<'a, T, [u8], R>
+where
+    Self: CallbackHost<'a, T, R>,
+{
+    /// Fusionne la rafale `payloads` selon `strategy`, en fait les nouvelles données du registre
+    /// et appelle une seule fois les callbacks, comme `do_something`.
+    ///
+    /// Pendant cet appel, [`CallbackContext::coalesced`](crate::CallbackContext::coalesced) vaut
+    /// le nombre de données fusionnées. Une rafale vide ne change pas les données et n'appelle
+    /// aucun callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, Coalesce, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| {
+    ///     assert_eq!(data.as_bytes(), &[1, 2, 3]);
+    /// }));
+    /// registry.dispatch_coalesced(&[&[1], &[2, 3]], Coalesce::Concat);
+    /// ```
+    pub fn dispatch_coalesced(&mut self, payloads: &[&[u8]], strategy: Coalesce) {
+        if payloads.is_empty() {
+            return;
+        }
+        self.set_data(strategy.merge(payloads));
+        self.coalesced.set(payloads.len());
+        self.do_something();
+        self.coalesced.set(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::data::{ArcCallbackPayload, CallbackPayload};
+    use crate::registry::CallbackContext;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Données et nombre de données fusionnées reçus par un callback.
+    type Seen = (Vec<u8>, usize);
+
+    /// Crée un registre dont le callback contextuel note dans `seen` ce qu'il reçoit.
+    fn recording_registry(
+        seen: &Rc<RefCell<Vec<Seen>>>,
+    ) -> CallbackRegistry<'static, CallbackPayload> {
+        let mut registry = CallbackRegistry::new();
+        let seen = Rc::clone(seen);
+        registry.set_callback_with_ctx(move |ctx: &CallbackContext, data: &CallbackPayload| {
+            seen.borrow_mut()
+                .push((data.as_bytes().to_vec(), ctx.coalesced))
+        });
+        registry
+    }
+
+    /// Teste `Last` et `Concat`, sur une rafale de plusieurs données puis d'une seule.
+    #[test]
+    fn test_last_and_concat() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = recording_registry(&seen);
+
+        registry.dispatch_coalesced(&[&[1], &[2, 3], &[4]], Coalesce::Last);
+        registry.dispatch_coalesced(&[&[1], &[2, 3], &[4]], Coalesce::Concat);
+        registry.dispatch_coalesced(&[&[5, 6]], Coalesce::Last);
+        registry.dispatch_coalesced(&[&[7]], Coalesce::Concat);
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                (vec![4], 3),
+                (vec![1, 2, 3, 4], 3),
+                (vec![5, 6], 1),
+                (vec![7], 1),
+            ]
+        );
+        assert_eq!(registry.data(), &[7]);
+    }
+
+    /// Teste une fusion personnalisée et le retour à 1 du nombre de données fusionnées.
+    #[test]
+    fn test_custom_strategy() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = recording_registry(&seen);
+        let sum = Coalesce::Custom(Box::new(|payloads: &[&[u8]]| {
+            vec![payloads.iter().flat_map(|data| data.iter()).sum()]
+        }));
+
+        registry.dispatch_coalesced(&[&[1, 2], &[3], &[4]], sum);
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![(vec![10], 3), (vec![10], 1)]);
+    }
+
+    /// Teste qu'une rafale vide n'appelle aucun callback et ne change pas les données.
+    #[test]
+    fn test_empty_burst_does_not_dispatch() {
+        let calls = Rc::new(RefCell::new(0));
+        let mut registry: CallbackRegistry<'static, ArcCallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![9]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &ArcCallbackPayload| {
+            *calls_in_cb.borrow_mut() += 1
+        }));
+
+        registry.dispatch_coalesced(&[], Coalesce::Concat);
+
+        assert_eq!(*calls.borrow(), 0);
+        assert_eq!(registry.data(), &[9]);
+    }
+}
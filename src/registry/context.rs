@@ -0,0 +1,123 @@
+//! Contexte d'appel transmis aux callbacks en plus des données.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Instant, SystemTime};
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+/// Contexte d'un appel de callback, reçu par les callbacks enregistrés avec
+/// [`CallbackRegistry::set_callback_with_ctx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallbackContext {
+    pub dispatch_seq: u64, // Numéro de l'appel à `do_something`, à partir de 1.
+    pub callback_id: CallbackId, // Identifiant du callback appelé.
+    pub timestamp: Instant, // Instant du début de l'appel à `do_something`.
+    pub wall_time: SystemTime, // Heure système du début de l'appel à `do_something`.
+    pub callback_index: usize, // Rang du callback parmi ceux appelés par cet appel, à partir de 0.
+    pub coalesced: usize, // Nombre de données fusionnées dans cet appel, 1 hors `dispatch_coalesced`.
+    pub batch: Option<BatchContext>, // Position des données dans leur lot, `None` hors `dispatch_batch`.
+}
+
+/// Position des données de l'appel en cours dans le lot de
+/// [`CallbackRegistry::dispatch_batch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchContext {
+    pub index: usize, // Rang des données dans le lot, à partir de 0.
+    pub len: usize,   // Nombre de données du lot.
+}
+
+/// Contexte de l'appel en cours, partagé entre le registre et ses callbacks contextuels.
+pub(crate) type ContextSlot = Rc<Cell<Option<CallbackContext>>>;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `f`, qui reçoit le [`CallbackContext`] de chaque appel en plus des données.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackContext, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback_with_ctx(|ctx: &CallbackContext, data: &CallbackPayload| {
+    ///     println!("appel n°{} : {:?}", ctx.dispatch_seq, data);
+    /// });
+    /// registry.do_something();
+    /// ```
+    pub fn set_callback_with_ctx(
+        &mut self,
+        f: impl Fn(&CallbackContext, &T) -> R + 'static,
+    ) -> CallbackId
+    where
+        T: 'static,
+    {
+        let context = Rc::clone(&self.context);
+        self.push_callback(Callback::new(move |data: &T| {
+            // Le contexte est défini par le registre juste avant chaque appel.
+            let ctx = context.get().expect("callback appelé hors d'un dispatch");
+            f(&ctx, data)
+        }))
+    }
+
+    /// Commence un nouvel appel à `do_something` et renvoie son numéro, son instant de début et
+    /// l'heure système correspondante.
+    pub(crate) fn next_dispatch(&self) -> (u64, Instant, SystemTime) {
+        let seq = self.dispatch_seq.get() + 1;
+        self.dispatch_seq.set(seq);
+        (seq, Instant::now(), SystemTime::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ArcCallbackPayload, CallbackPayload};
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+
+    /// Teste que deux callbacks d'un même appel partagent `dispatch_seq` mais pas `callback_index`.
+    #[test]
+    fn test_same_dispatch_shares_sequence() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let seen_in_cb = Rc::clone(&seen);
+        let first = registry.set_callback_with_ctx(
+            move |ctx: &CallbackContext, _data: &CallbackPayload| {
+                seen_in_cb.borrow_mut().push(*ctx)
+            },
+        );
+        let seen_in_cb = Rc::clone(&seen);
+        let second = registry.set_callback_with_ctx(
+            move |ctx: &CallbackContext, _data: &CallbackPayload| {
+                seen_in_cb.borrow_mut().push(*ctx)
+            },
+        );
+
+        registry.do_something();
+
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].dispatch_seq, seen[1].dispatch_seq);
+        assert_eq!(seen[0].timestamp, seen[1].timestamp);
+        assert_eq!((seen[0].callback_index, seen[1].callback_index), (0, 1));
+        assert_eq!((seen[0].callback_id, seen[1].callback_id), (first, second));
+    }
+
+    /// Teste que le numéro d'appel augmente d'un par `do_something`, et non par callback.
+    #[test]
+    fn test_sequence_increments_once_per_dispatch() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_with_ctx(move |ctx: &CallbackContext, _data: &ArcCallbackPayload| {
+            seen_in_cb.borrow_mut().push(ctx.dispatch_seq)
+        });
+        registry.set_callback_with_ctx(|_ctx: &CallbackContext, _data: &ArcCallbackPayload| {});
+
+        registry.do_something();
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+    }
+}
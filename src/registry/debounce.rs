@@ -0,0 +1,319 @@
+//! Anti-rebond : ne transmettre une donnée qu'une fois les données suivantes arrêtées.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::callback::Callback;
+use crate::clock::{Clock, SystemClock};
+use crate::data::CallbackPayload;
+
+/// Moment où un [`Debouncer`] transmet une rafale de données.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebounceEdge {
+    /// La première donnée de la rafale est transmise dès son arrivée ; les suivantes sont
+    /// ignorées jusqu'à un silence.
+    Leading,
+    /// La dernière donnée de la rafale est transmise par [`Debouncer::poll_due`] une fois le
+    /// silence écoulé.
+    #[default]
+    Trailing,
+}
+
+/// État d'un anti-rebond entre deux appels.
+#[derive(Debug, Default)]
+struct DebounceState {
+    pending: Option<Vec<u8>>, // Dernière donnée en attente de transmission en fin de rafale.
+    last_arrival: Option<Instant>, // Heure de la dernière donnée reçue, `None` avant la première.
+}
+
+/// Partie d'un anti-rebond partagée avec son callback et son thread de minuterie.
+struct DebounceInner<F> {
+    deliver: F,                          // Reçoit les données retenues.
+    quiet: Duration,                     // Silence qui termine une rafale.
+    edge: DebounceEdge,                  // Donnée retenue de chaque rafale.
+    clock: Box<dyn Clock + Send + Sync>, // Source de l'heure.
+    state: Mutex<DebounceState>,         // Donnée en attente et heure de la dernière arrivée.
+}
+
+impl<F> DebounceInner<F> {
+    /// Verrouille l'état, même si un thread a paniqué en le tenant : il reste cohérent.
+    fn lock(&self) -> MutexGuard<'_, DebounceState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Anti-rebond : ne transmet qu'une donnée par rafale, une rafale se terminant après `quiet`
+/// sans nouvelle donnée.
+///
+/// Le callback renvoyé par [`callback`](Self::callback) s'enregistre dans un registre ; en mode
+/// [`DebounceEdge::Trailing`], la dernière donnée de chaque rafale est transmise par
+/// [`poll_due`](Self::poll_due), à appeler régulièrement, ou par un thread de minuterie démarré
+/// par [`spawn_timer`](Self::spawn_timer).
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackHost, CallbackPayload, ManualClock, OwnedRegistry};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let debouncer = Callback::new(|data: &CallbackPayload| println!("{:?}", data))
+///     .debounced(Duration::from_millis(50))
+///     .with_clock(clock.clone());
+/// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+/// registry.set_callback(debouncer.callback());
+///
+/// registry.dispatch(&[1]).unwrap();
+/// registry.dispatch(&[2]).unwrap();
+/// assert!(!debouncer.poll_due());
+/// clock.advance(Duration::from_millis(50));
+/// assert!(debouncer.poll_due()); // Transmet [2].
+/// ```
+pub struct Debouncer<F> {
+    inner: Arc<DebounceInner<F>>, // Partagé avec le callback et le thread de minuterie.
+}
+
+impl<F: Fn(&CallbackPayload)> Debouncer<F> {
+    /// Crée un anti-rebond qui transmet à `deliver` la dernière donnée de chaque rafale, une
+    /// rafale se terminant après `quiet` sans nouvelle donnée.
+    pub fn new(quiet: Duration, deliver: F) -> Self {
+        Debouncer {
+            inner: Arc::new(DebounceInner {
+                deliver,
+                quiet,
+                edge: DebounceEdge::default(),
+                clock: Box::new(SystemClock),
+                state: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Choisit la donnée retenue de chaque rafale.
+    ///
+    /// # Panics
+    ///
+    /// Panique si [`callback`](Self::callback) ou [`spawn_timer`](Self::spawn_timer) a déjà été
+    /// appelé.
+    pub fn with_edge(mut self, edge: DebounceEdge) -> Self {
+        self.inner_mut().edge = edge;
+        self
+    }
+
+    /// Lit l'heure sur `clock` plutôt que sur l'horloge du système.
+    ///
+    /// # Panics
+    ///
+    /// Panique si [`callback`](Self::callback) ou [`spawn_timer`](Self::spawn_timer) a déjà été
+    /// appelé.
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.inner_mut().clock = Box::new(clock);
+        self
+    }
+
+    /// Renvoie l'anti-rebond, qui ne doit pas encore être partagé.
+    fn inner_mut(&mut self) -> &mut DebounceInner<F> {
+        Arc::get_mut(&mut self.inner).expect("anti-rebond déjà partagé par `callback`")
+    }
+
+    /// Renvoie un callback qui transmet les données reçues à l'anti-rebond.
+    pub fn callback(&self) -> Callback<CallbackPayload>
+    where
+        F: 'static,
+    {
+        let debouncer = Debouncer {
+            inner: Arc::clone(&self.inner),
+        };
+        Callback::new(move |data: &CallbackPayload| debouncer.push(data.as_bytes()))
+    }
+
+    /// Reçoit `data` : en mode [`DebounceEdge::Leading`], la transmet si elle commence une
+    /// rafale ; en mode [`DebounceEdge::Trailing`], la garde en attente de la fin de la rafale.
+    pub fn push(&self, data: &[u8]) {
+        let inner = &*self.inner;
+        let now = inner.clock.now();
+        let mut state = inner.lock();
+        let quiet = state
+            .last_arrival
+            .is_none_or(|last| now.saturating_duration_since(last) >= inner.quiet);
+        state.last_arrival = Some(now);
+        match inner.edge {
+            DebounceEdge::Leading => {
+                drop(state);
+                if quiet {
+                    (inner.deliver)(CallbackPayload::new(data));
+                }
+            }
+            DebounceEdge::Trailing => state.pending = Some(data.to_vec()),
+        }
+    }
+
+    /// Transmet la donnée en attente si le silence qui termine sa rafale est écoulé, et indique
+    /// si elle a été transmise.
+    pub fn poll_due(&self) -> bool {
+        let inner = &*self.inner;
+        let now = inner.clock.now();
+        let mut state = inner.lock();
+        let due = state
+            .last_arrival
+            .is_some_and(|last| now.saturating_duration_since(last) >= inner.quiet);
+        let Some(data) = state.pending.take_if(|_| due) else {
+            return false;
+        };
+        drop(state);
+        (inner.deliver)(CallbackPayload::new(&data));
+        true
+    }
+
+    /// Indique si une donnée attend la fin de sa rafale.
+    pub fn is_pending(&self) -> bool {
+        self.inner.lock().pending.is_some()
+    }
+
+    /// Démarre un thread qui appelle [`poll_due`](Self::poll_due) toutes les `interval`, jusqu'à
+    /// la destruction de la minuterie renvoyée.
+    pub fn spawn_timer(&self, interval: Duration) -> DebounceTimer
+    where
+        F: Send + Sync + 'static,
+    {
+        let debouncer = Debouncer {
+            inner: Arc::clone(&self.inner),
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !stopped.load(Ordering::Acquire) {
+                thread::park_timeout(interval);
+                debouncer.poll_due();
+            }
+        });
+        DebounceTimer {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<F> fmt::Debug for Debouncer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debouncer")
+            .field("quiet", &self.inner.quiet)
+            .field("edge", &self.inner.edge)
+            .field("state", &*self.inner.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Thread de minuterie d'un [`Debouncer`], arrêté à sa destruction.
+#[derive(Debug)]
+pub struct DebounceTimer {
+    stop: Arc<AtomicBool>,          // Levé pour arrêter le thread.
+    thread: Option<JoinHandle<()>>, // Le thread, `None` une fois attendu.
+}
+
+impl Drop for DebounceTimer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            // La panique de la livraison a déjà été affichée par le thread.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Callback<CallbackPayload> {
+    /// Place le callback derrière un [`Debouncer`] qui ne lui transmet que la dernière donnée de
+    /// chaque rafale. Le callback n'étant pas `Send`, l'anti-rebond n'a pas de thread de
+    /// minuterie : appelez [`Debouncer::poll_due`].
+    pub fn debounced(self, quiet: Duration) -> Debouncer<impl Fn(&CallbackPayload)> {
+        Debouncer::new(quiet, move |data: &CallbackPayload| self.invoke(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::registry::{CallbackHost, OwnedRegistry};
+
+    /// Crée un anti-rebond de 50 ms sur `clock` qui note le premier byte des données transmises.
+    fn recording_debouncer(
+        edge: DebounceEdge,
+        clock: &ManualClock,
+        seen: &Arc<Mutex<Vec<u8>>>,
+    ) -> Debouncer<impl Fn(&CallbackPayload) + Send + Sync> {
+        let seen = Arc::clone(seen);
+        Debouncer::new(Duration::from_millis(50), move |data: &CallbackPayload| {
+            seen.lock().unwrap().push(data.as_bytes()[0])
+        })
+        .with_edge(edge)
+        .with_clock(clock.clone())
+    }
+
+    /// Envoie au registre une rafale de `values`, espacées de 10 ms.
+    fn burst(registry: &OwnedRegistry<CallbackPayload>, clock: &ManualClock, values: &[u8]) {
+        for value in values {
+            registry.dispatch(&[*value]).unwrap();
+            clock.advance(Duration::from_millis(10));
+        }
+    }
+
+    /// Teste qu'en fin de rafale, seule la dernière donnée est transmise, une fois par silence.
+    #[test]
+    fn test_trailing_edge_delivers_last_payload() {
+        let (clock, seen) = (ManualClock::new(), Arc::default());
+        let debouncer = recording_debouncer(DebounceEdge::Trailing, &clock, &seen);
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        registry.set_callback(debouncer.callback());
+
+        burst(&registry, &clock, &[1, 2, 3]);
+        assert!(!debouncer.poll_due());
+        clock.advance(Duration::from_millis(40));
+        assert!(debouncer.poll_due());
+        assert!(!debouncer.poll_due());
+
+        burst(&registry, &clock, &[4, 5]);
+        clock.advance(Duration::from_secs(1));
+        assert!(debouncer.poll_due());
+        assert_eq!(*seen.lock().unwrap(), vec![3, 5]);
+    }
+
+    /// Teste qu'en début de rafale, seule la première donnée est transmise, dès son arrivée.
+    #[test]
+    fn test_leading_edge_delivers_first_payload() {
+        let (clock, seen) = (ManualClock::new(), Arc::default());
+        let debouncer = recording_debouncer(DebounceEdge::Leading, &clock, &seen);
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        registry.set_callback(debouncer.callback());
+
+        burst(&registry, &clock, &[1, 2, 3]);
+        assert_eq!(*seen.lock().unwrap(), vec![1]);
+        clock.advance(Duration::from_millis(50));
+        burst(&registry, &clock, &[4, 5]);
+        assert!(!debouncer.poll_due());
+        assert_eq!(*seen.lock().unwrap(), vec![1, 4]);
+    }
+
+    /// Teste que le thread de minuterie transmet la donnée en attente sans appel à `poll_due`.
+    #[test]
+    fn test_timer_thread_delivers() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_cb = Arc::clone(&seen);
+        let debouncer = Debouncer::new(Duration::from_millis(10), move |data: &CallbackPayload| {
+            seen_in_cb.lock().unwrap().push(data.as_bytes()[0])
+        });
+        let timer = debouncer.spawn_timer(Duration::from_millis(5));
+        debouncer.push(&[7]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while debouncer.is_pending() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        drop(timer);
+        assert_eq!(*seen.lock().unwrap(), vec![7]);
+    }
+}
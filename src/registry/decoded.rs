@@ -0,0 +1,61 @@
+//! Callbacks qui reçoivent les données lues avec un [`Schema`].
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackId};
+use crate::data::{CallbackPayload, Record, Schema};
+
+impl<'a, D: ?Sized> CallbackRegistry<'a, CallbackPayload, D> {
+    /// Enregistre `f`, qui reçoit les données lues avec `schema`.
+    ///
+    /// Les données qui ne correspondent pas au schéma, trop courtes, ne sont pas transmises à `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, Record, Schema};
+    ///
+    /// let mut registry: CallbackRegistry<CallbackPayload> = CallbackRegistry::with_data(&[1, 2, 3][..]);
+    /// registry.set_decoded_callback(Schema::new().u8("op").u16_be("id"), |record: &Record| {
+    ///     println!("op {} id {}", record.get("op").unwrap(), record.get("id").unwrap())
+    /// });
+    /// registry.do_something();
+    /// ```
+    pub fn set_decoded_callback(
+        &mut self,
+        schema: Schema,
+        f: impl Fn(&Record<'_>) + 'static,
+    ) -> CallbackId {
+        self.push_callback(Callback::new(move |data: &CallbackPayload| {
+            if let Ok(record) = schema.decode(data.as_bytes()) {
+                f(&record);
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste la lecture des données par défaut `[1, 2, 3]`, puis de données trop courtes.
+    #[test]
+    fn test_decoded_callback_on_default_payload() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1, 2, 3]);
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_decoded_callback(Schema::new().u8("op").u16_be("id"), move |record| {
+            seen_in_cb
+                .borrow_mut()
+                .push((record.get("op").unwrap(), record.get("id").unwrap()))
+        });
+
+        registry.do_something();
+        registry.set_data(vec![4]);
+        registry.do_something();
+        assert_eq!(*seen.borrow(), vec![(1, 0x0203)]);
+    }
+}
@@ -0,0 +1,256 @@
+//! Déduplication : ne pas retransmettre des données déjà transmises récemment.
+
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hasher;
+use std::time::{Duration, Instant};
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData};
+use crate::clock::{Clock, SystemClock};
+use crate::data::CallbackPayload;
+
+/// Données retenues par un [`DedupFilter`].
+#[derive(Debug, Clone, Copy)]
+struct Seen {
+    delivered: Instant, // Heure de la dernière transmission.
+    used: u64,          // Numéro du dernier passage, pour oublier la moins récemment vue.
+}
+
+/// Filtre qui écarte les données déjà transmises depuis moins de `ttl`, reconnues à leur hash.
+///
+/// Le filtre retient au plus `max_entries` données : quand il est plein, il oublie d'abord les
+/// données expirées, puis la moins récemment vue. Deux données différentes de même hash sont
+/// confondues ; avec un hash de 64 bits, c'est improbable.
+///
+/// Un filtre s'utilise pour tout le registre avec [`CallbackRegistry::set_dedup_filter`], ou
+/// pour un seul callback avec [`wrap`](Self::wrap).
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackHost, CallbackPayload, DedupFilter, OwnedRegistry};
+/// use std::time::Duration;
+///
+/// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![1, 2]);
+/// registry.set_dedup_filter(DedupFilter::new(Duration::from_secs(60), 1024));
+/// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+/// registry.do_something();
+/// registry.do_something(); // Déjà transmis il y a moins d'une minute : ignoré.
+/// ```
+pub struct DedupFilter {
+    ttl: Duration,         // Durée pendant laquelle une donnée transmise est écartée.
+    max_entries: usize,    // Nombre maximal de données retenues.
+    clock: Box<dyn Clock>, // Donne l'heure actuelle.
+    seen: RefCell<HashMap<u64, Seen>>, // Données retenues, par hash.
+    passes: Cell<u64>,     // Nombre de données présentées au filtre.
+}
+
+impl DedupFilter {
+    /// Crée un filtre qui écarte pendant `ttl` les données transmises, en en retenant au plus
+    /// `max_entries`.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `max_entries` vaut 0.
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        assert!(
+            max_entries > 0,
+            "un filtre de déduplication doit retenir au moins 1 donnée"
+        );
+        DedupFilter {
+            ttl,
+            max_entries,
+            clock: Box::new(SystemClock),
+            seen: RefCell::new(HashMap::new()),
+            passes: Cell::new(0),
+        }
+    }
+
+    /// Remplace l'horloge du filtre par `clock`, par exemple une [`ManualClock`](crate::ManualClock)
+    /// pour avancer le temps dans un test.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Indique si `data` doit être transmise, c'est-à-dire si elle n'a pas été transmise depuis
+    /// moins de `ttl`, et retient-la dans ce cas. Une donnée écartée ne prolonge pas son délai.
+    pub fn check(&self, data: &[u8]) -> bool {
+        self.check_at(data, false)
+    }
+
+    /// Comme `check`, mais avec `force`, retient `data` et la transmet même si elle est récente.
+    pub(crate) fn check_at(&self, data: &[u8], force: bool) -> bool {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(data);
+        let hash = hasher.finish();
+        let now = self.clock.now();
+        let used = self.passes.get();
+        self.passes.set(used + 1);
+
+        let mut seen = self.seen.borrow_mut();
+        if let Some(entry) = seen.get_mut(&hash) {
+            entry.used = used;
+            if !force && now.saturating_duration_since(entry.delivered) < self.ttl {
+                return false;
+            }
+            entry.delivered = now;
+            return true;
+        }
+        if seen.len() == self.max_entries {
+            seen.retain(|_, entry| now.saturating_duration_since(entry.delivered) < self.ttl);
+        }
+        if seen.len() == self.max_entries {
+            let oldest = seen
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(hash, _)| *hash);
+            if let Some(oldest) = oldest {
+                seen.remove(&oldest);
+            }
+        }
+        seen.insert(
+            hash,
+            Seen {
+                delivered: now,
+                used,
+            },
+        );
+        true
+    }
+
+    /// Renvoie le nombre de données retenues, expirées comprises.
+    pub fn len(&self) -> usize {
+        self.seen.borrow().len()
+    }
+
+    /// Indique si le filtre ne retient aucune donnée.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Oublie toutes les données retenues.
+    pub fn clear(&self) {
+        self.seen.borrow_mut().clear();
+    }
+
+    /// Renvoie un callback qui n'appelle `cb` que pour les données acceptées par le filtre, avec
+    /// la priorité et le nom de `cb`.
+    pub fn wrap(self, cb: Callback<CallbackPayload>) -> Callback<CallbackPayload> {
+        cb.filter(move |data: &CallbackPayload| self.check(data.as_bytes()))
+    }
+}
+
+impl fmt::Debug for DedupFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DedupFilter")
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// N'appelle les callbacks que pour les données acceptées par `filter`, après somme de
+    /// contrôle, transformations et détection des changements. Remplace le filtre précédent.
+    ///
+    /// Le filtre s'applique aux registres de [`CallbackPayload`] et d'`ArcCallbackPayload` ;
+    /// `force_dispatch` transmet les données même si le filtre les écarterait.
+    pub fn set_dedup_filter(&mut self, filter: DedupFilter) {
+        self.dedup = Some(filter);
+    }
+
+    /// Retire le filtre de déduplication et renvoie-le.
+    pub fn take_dedup_filter(&mut self) -> Option<DedupFilter> {
+        self.dedup.take()
+    }
+
+    /// Indique si `data` doit être transmise aux callbacks selon le filtre de déduplication.
+    pub(crate) fn dedup_dispatch(&self, data: &[u8], force: bool) -> bool {
+        self.dedup
+            .as_ref()
+            .is_none_or(|filter| filter.check_at(data, force))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Teste que les données sont écartées pendant le délai, puis retransmises après.
+    #[test]
+    fn test_suppressed_within_ttl_then_redelivered() {
+        let clock = ManualClock::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.set_dedup_filter(
+            DedupFilter::new(Duration::from_secs(10), 8).with_clock(clock.clone()),
+        );
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+
+        // Secondes écoulées depuis la donnée précédente, et la donnée.
+        for (step, value) in [(0, 1u8), (1, 2), (4, 1), (4, 2), (1, 1), (1, 2), (1, 1)] {
+            clock.advance(Duration::from_secs(step));
+            registry.set_data(vec![value]);
+            registry.do_something();
+        }
+        assert_eq!(*seen.borrow(), vec![1, 2, 1, 2]);
+
+        registry.force_dispatch();
+        assert_eq!(*seen.borrow(), vec![1, 2, 1, 2, 1]);
+    }
+
+    /// Teste que le filtre plein oublie d'abord les données expirées, puis la moins récemment vue.
+    #[test]
+    fn test_eviction_under_size_cap() {
+        let clock = ManualClock::new();
+        let filter = DedupFilter::new(Duration::from_secs(10), 2).with_clock(clock.clone());
+        assert!(filter.check(b"a"));
+        assert!(filter.check(b"b"));
+        assert!(!filter.check(b"a")); // `b` devient la moins récemment vue.
+        assert!(filter.check(b"c")); // Oublie `b`.
+        assert_eq!(filter.len(), 2);
+        assert!(!filter.check(b"a"));
+        assert!(filter.check(b"b")); // Oublie `c`.
+        assert!(!filter.check(b"a"));
+
+        clock.advance(Duration::from_secs(10));
+        assert!(filter.check(b"d")); // `a` et `b` ont expiré : plus de place pour `e`.
+        assert_eq!(filter.len(), 1);
+        assert!(filter.check(b"e"));
+        assert!(!filter.check(b"d"));
+    }
+
+    /// Teste un filtre utilisé pour un seul callback.
+    #[test]
+    fn test_wrapped_callback() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![3u8]);
+        let deduplicated_calls = Rc::clone(&calls);
+        registry.set_callback(
+            DedupFilter::new(Duration::from_secs(60), 4).wrap(Callback::new(
+                move |_data: &CallbackPayload| deduplicated_calls.borrow_mut().push("dedup"),
+            )),
+        );
+        let all_calls = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            all_calls.borrow_mut().push("all")
+        }));
+
+        registry.do_something();
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["dedup", "all", "all"]);
+    }
+}
@@ -0,0 +1,241 @@
+//! Réentrance coopérative : un callback peut enregistrer ou retirer des callbacks du registre
+//! qui l'appelle, via un [`RegistryHandle`].
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::slab::SharedIds;
+use super::{CallbackHost, CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId, IntoCallback};
+
+/// Opération demandée via un [`RegistryHandle`], appliquée par `apply_deferred`.
+pub(crate) enum DeferredOp<T: CallbackData + ?Sized, R> {
+    Add(CallbackId, Callback<T, R>), // Enregistrement, avec l'identifiant déjà attribué.
+    Remove(CallbackId),              // Retrait.
+}
+
+/// File des opérations différées, partagée entre le registre et ses [`RegistryHandle`].
+pub(crate) type DeferredQueue<T, R> = Rc<RefCell<Vec<DeferredOp<T, R>>>>;
+
+/// Poignée renvoyée par [`CallbackRegistry::handle`], que les callbacks peuvent capturer pour
+/// modifier le registre pendant qu'il les appelle.
+///
+/// La poignée ne fait que mettre les opérations en file : elles sont appliquées, dans l'ordre de
+/// leur demande, à la fin de [`do_something_reentrant`](CallbackRegistry::do_something_reentrant)
+/// ou lors d'un appel explicite à [`apply_deferred`](CallbackRegistry::apply_deferred). Ainsi :
+///
+/// - un callback enregistré pendant un appel n'est pas appelé par cet appel, mais par le suivant ;
+/// - un callback retiré pendant un appel, y compris par lui-même, termine son exécution, et les
+///   callbacks retirés qui le suivent sont encore appelés par cet appel.
+///
+/// La poignée ne référence pas le registre et peut lui survivre ; ses opérations sont alors perdues.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry};
+///
+/// let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+/// let handle = registry.handle();
+/// registry.set_callback(move |_data: &CallbackPayload| {
+///     handle.set_callback(|_data: &CallbackPayload| println!("Ajouté pendant l'appel"));
+/// });
+/// registry.do_something_reentrant();
+/// assert_eq!(registry.callback_count(), 2);
+/// ```
+pub struct RegistryHandle<T: CallbackData + ?Sized, R = ()> {
+    ids: SharedIds,             // Allocateur des identifiants du registre.
+    queue: DeferredQueue<T, R>, // File des opérations du registre.
+}
+
+impl<T: CallbackData + ?Sized, R> RegistryHandle<T, R> {
+    /// Demande l'enregistrement de `cb` et renvoie l'identifiant qu'il aura dans le registre.
+    pub fn set_callback(&self, cb: impl IntoCallback<T, R>) -> CallbackId {
+        let id = self.ids.borrow_mut().next_id();
+        self.queue
+            .borrow_mut()
+            .push(DeferredOp::Add(id, cb.into_callback()));
+        id
+    }
+
+    /// Demande le retrait du callback `id`, y compris s'il n'a pas encore été enregistré.
+    pub fn remove_callback(&self, id: CallbackId) {
+        self.queue.borrow_mut().push(DeferredOp::Remove(id));
+    }
+
+    /// Renvoie le nombre d'opérations en attente.
+    pub fn pending(&self) -> usize {
+        self.queue.borrow().len()
+    }
+}
+
+impl<T: CallbackData + ?Sized, R> Clone for RegistryHandle<T, R> {
+    fn clone(&self) -> Self {
+        RegistryHandle {
+            ids: Rc::clone(&self.ids),
+            queue: Rc::clone(&self.queue),
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Renvoie une [`RegistryHandle`] qui met en file des enregistrements et des retraits.
+    pub fn handle(&self) -> RegistryHandle<T, R> {
+        RegistryHandle {
+            ids: Rc::clone(self.callbacks.ids()),
+            queue: Rc::clone(&self.deferred),
+        }
+    }
+
+    /// Applique, dans l'ordre de leur demande, les opérations mises en file par les
+    /// [`RegistryHandle`] du registre, et renvoie leur nombre.
+    ///
+    /// Un callback enregistré puis retiré avant l'application n'est jamais ajouté. Retirer un
+    /// identifiant inconnu n'a aucun effet. En mode collant, chaque callback ajouté reçoit
+    /// aussitôt les dernières données, comme avec `set_callback`.
+    ///
+    /// # Panics
+    ///
+    /// Panique si un enregistrement dépasse le nombre maximal de callbacks du registre.
+    pub fn apply_deferred(&mut self) -> usize {
+        let ops = std::mem::take(&mut *self.deferred.borrow_mut());
+        let count = ops.len();
+        for op in ops {
+            match op {
+                DeferredOp::Add(id, cb) => self.push_reserved_entry(Entry::new(id, cb)),
+                DeferredOp::Remove(id) => {
+                    self.remove_entry(id);
+                }
+            }
+        }
+        count
+    }
+
+    /// Comme `do_something`, puis applique les opérations demandées pendant l'appel,
+    /// voir [`RegistryHandle`].
+    pub fn do_something_reentrant(&mut self)
+    where
+        Self: CallbackHost<'a, T, R>,
+    {
+        self.do_something();
+        self.apply_deferred();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use std::cell::Cell;
+
+    /// Teste qu'un callback qui se retire lui-même termine son appel et n'est plus appelé ensuite.
+    #[test]
+    fn test_callback_removes_itself() {
+        let calls = Rc::new(Cell::new(0));
+        let own_id = Rc::new(Cell::new(None));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let handle = registry.handle();
+        let (calls_in_cb, id_in_cb) = (Rc::clone(&calls), Rc::clone(&own_id));
+        let id = registry.set_callback(move |_data: &CallbackPayload| {
+            calls_in_cb.set(calls_in_cb.get() + 1);
+            handle.remove_callback(id_in_cb.get().unwrap());
+        });
+        own_id.set(Some(id));
+
+        registry.do_something_reentrant();
+        registry.do_something_reentrant();
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(registry.callback_count(), 0);
+    }
+
+    /// Teste qu'un callback enregistré pendant un appel n'est appelé qu'à l'appel suivant.
+    #[test]
+    fn test_registered_callback_runs_on_next_dispatch() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let handle = registry.handle();
+        let order_in_cb = Rc::clone(&order);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            order_in_cb.borrow_mut().push("parent");
+            if order_in_cb.borrow().len() == 1 {
+                let order = Rc::clone(&order_in_cb);
+                handle
+                    .set_callback(move |_data: &CallbackPayload| order.borrow_mut().push("enfant"));
+            }
+        });
+
+        registry.do_something_reentrant();
+        assert_eq!(*order.borrow(), vec!["parent"]);
+        registry.do_something_reentrant();
+        assert_eq!(*order.borrow(), vec!["parent", "parent", "enfant"]);
+    }
+
+    /// Teste qu'un callback retiré par un callback précédent est encore appelé par le même appel.
+    #[test]
+    fn test_removal_applies_after_dispatch() {
+        let calls = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let handle = registry.handle();
+        let target = handle.set_callback({
+            let calls = Rc::clone(&calls);
+            move |_data: &CallbackPayload| calls.set(calls.get() + 1)
+        });
+        registry.set_callback(move |_data: &CallbackPayload| handle.remove_callback(target));
+        assert_eq!(registry.apply_deferred(), 1);
+
+        registry.do_something_reentrant();
+        registry.do_something_reentrant();
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(registry.callback_count(), 1);
+    }
+
+    /// Teste qu'un enregistrement annulé avant l'application n'ajoute aucun callback.
+    #[test]
+    fn test_add_then_remove_before_apply() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let handle = registry.handle();
+        let id = handle.set_callback(|_data: &CallbackPayload| {});
+        handle.remove_callback(id);
+        assert_eq!(handle.pending(), 2);
+
+        assert_eq!(registry.apply_deferred(), 2);
+        assert_eq!(handle.pending(), 0);
+        assert_eq!(registry.callback_count(), 0);
+    }
+
+    /// Teste qu'un callback ajouté via la poignée reçoit les données collantes à l'application.
+    #[test]
+    fn test_deferred_add_receives_sticky_data() {
+        let calls = Rc::new(Cell::new(0));
+        let mut registry: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_data(&[1u8][..]);
+        registry.enable_sticky();
+        let handle = registry.handle();
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| {
+            if handle.pending() == 0 && calls_in_cb.get() == 0 {
+                let calls = Rc::clone(&calls_in_cb);
+                handle.set_callback(move |_data: &CallbackPayload| calls.set(calls.get() + 1));
+            }
+        });
+
+        registry.do_something_reentrant();
+        assert_eq!(calls.get(), 1);
+        registry.do_something_reentrant();
+        assert_eq!(calls.get(), 2);
+    }
+
+    /// Teste qu'un ajout via la poignée respecte le nombre maximal de callbacks.
+    #[test]
+    #[should_panic(expected = "le registre est plein")]
+    fn test_deferred_add_panics_when_full() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_max_callbacks(Some(1));
+        registry.set_callback(|_data: &CallbackPayload| {});
+        let handle = registry.handle();
+        handle.set_callback(|_data: &CallbackPayload| {});
+        registry.apply_deferred();
+    }
+}
@@ -0,0 +1,464 @@
+//! Appel des callbacks sur un thread dédié, qui reçoit les données par une file partagée.
+//!
+//! La file remplace un canal `mpsc` pour que l'envoi puisse oublier la donnée la plus ancienne
+//! d'une file bornée, voir [`Backpressure::DropOldest`].
+
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::sync::mpsc::TrySendError;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+#[cfg(feature = "async")]
+use std::task::Poll;
+use std::task::Waker;
+use std::thread::{self, JoinHandle};
+
+use super::{CallbackRegistry, DispatchReport};
+use crate::data::CallbackPayload;
+
+/// Que faire d'une donnée envoyée quand la file d'un thread d'appel est pleine, voir
+/// [`CallbackRegistry::spawn_dispatcher_bounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backpressure {
+    /// `send` attend qu'une place se libère.
+    #[default]
+    Block,
+    /// La donnée envoyée est oubliée.
+    DropNewest,
+    /// La donnée la plus ancienne de la file est oubliée pour faire de la place.
+    DropOldest,
+    /// `send` renvoie la donnée dans une erreur [`TrySendError::Full`].
+    Fail,
+}
+
+/// État de la file partagée entre les producteurs et le consommateur.
+#[derive(Debug)]
+pub(crate) struct QueueState<P> {
+    pub(crate) payloads: VecDeque<P>, // Données en attente, de la plus ancienne à la plus récente.
+    pub(crate) closed: bool,          // `true` une fois les producteurs arrêtés.
+    pub(crate) receiver_gone: bool, // `true` une fois le consommateur terminé, y compris par panique.
+    pub(crate) dropped: u64,        // Nombre de données oubliées parce que la file était pleine.
+    pub(crate) waker: Option<Waker>, // Réveille un consommateur asynchrone en attente d'une donnée.
+}
+
+/// File partagée entre les producteurs et le consommateur : le thread d'appel d'un
+/// [`DispatchHandle`] ou un flux d'événements.
+#[derive(Debug)]
+pub(crate) struct Queue<P> {
+    state: Mutex<QueueState<P>>, // Données en attente et états de fermeture.
+    not_empty: Condvar,          // Signalée quand une donnée arrive ou que la file se ferme.
+    not_full: Condvar, // Signalée quand une place se libère ou que le consommateur se termine.
+    capacity: Option<NonZeroUsize>, // Nombre maximal de données en attente, `None` si illimité.
+    policy: Backpressure, // Que faire d'une donnée quand la file est pleine.
+}
+
+impl<P> Queue<P> {
+    /// Crée une file vide de `capacity` données au plus, qui applique `policy` aux données de trop.
+    pub(crate) fn new(capacity: Option<NonZeroUsize>, policy: Backpressure) -> Self {
+        Queue {
+            state: Mutex::new(QueueState {
+                payloads: VecDeque::new(),
+                closed: false,
+                receiver_gone: false,
+                dropped: 0,
+                waker: None,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            policy,
+        }
+    }
+
+    /// Verrouille l'état, même si un thread a paniqué en le tenant : il reste cohérent.
+    pub(crate) fn lock(&self) -> MutexGuard<'_, QueueState<P>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Ferme la file du côté des producteurs et réveille le consommateur.
+    pub(crate) fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    /// Signale la fin du consommateur et réveille les producteurs qui attendent une place.
+    pub(crate) fn disconnect(&self) {
+        self.lock().receiver_gone = true;
+        self.not_full.notify_all();
+    }
+
+    /// Ajoute `payload` à la file selon sa politique.
+    pub(crate) fn push(&self, payload: P) -> Result<(), TrySendError<P>> {
+        let mut state = self.lock();
+        loop {
+            if state.receiver_gone || state.closed {
+                return Err(TrySendError::Disconnected(payload));
+            }
+            let full = self
+                .capacity
+                .is_some_and(|capacity| state.payloads.len() >= capacity.get());
+            if !full {
+                break;
+            }
+            match self.policy {
+                Backpressure::Block => {
+                    state = self
+                        .not_full
+                        .wait(state)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                }
+                Backpressure::DropNewest => {
+                    state.dropped += 1;
+                    return Ok(());
+                }
+                Backpressure::DropOldest => {
+                    state.dropped += 1;
+                    state.payloads.pop_front();
+                    break;
+                }
+                Backpressure::Fail => return Err(TrySendError::Full(payload)),
+            }
+        }
+        state.payloads.push_back(payload);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Retire la donnée la plus ancienne sans attendre, ou renvoie `Ready(None)` une fois la
+    /// file fermée et vide ; sinon, `waker` sera réveillé à l'arrivée d'une donnée.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_pop(&self, waker: &Waker) -> Poll<Option<P>> {
+        let mut state = self.lock();
+        if let Some(payload) = state.payloads.pop_front() {
+            self.not_full.notify_one();
+            return Poll::Ready(Some(payload));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(waker.clone());
+        Poll::Pending
+    }
+
+    /// Attend et retire la donnée la plus ancienne, ou renvoie `None` une fois la file fermée et vide.
+    fn pop(&self) -> Option<P> {
+        let mut state = self.lock();
+        loop {
+            if let Some(payload) = state.payloads.pop_front() {
+                self.not_full.notify_one();
+                return Some(payload);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+}
+
+/// Signale la fin du thread d'appel aux producteurs, même s'il se termine par une panique.
+struct ReceiverGuard(Arc<Queue<Vec<u8>>>);
+
+impl Drop for ReceiverGuard {
+    fn drop(&mut self) {
+        self.0.disconnect();
+    }
+}
+
+/// Poignée d'un thread d'appel des callbacks, créée par [`CallbackRegistry::spawn_dispatcher`].
+///
+/// Plusieurs threads peuvent envoyer des données par une même poignée. Détruire la poignée
+/// arrête le thread comme [`shutdown`](Self::shutdown), en ignorant son bilan.
+#[derive(Debug)]
+pub struct DispatchHandle {
+    queue: Arc<Queue<Vec<u8>>>, // File partagée avec le thread.
+    thread: Option<JoinHandle<DispatchReport>>, // Le thread, `None` une fois attendu.
+}
+
+impl DispatchHandle {
+    /// Envoie `payload` au thread, qui appellera les callbacks avec, après les données déjà
+    /// envoyées. Si la file est pleine, applique sa politique [`Backpressure`].
+    ///
+    /// # Errors
+    ///
+    /// - Renvoie `payload` dans [`TrySendError::Full`] si la file est pleine et que sa politique
+    ///   est [`Backpressure::Fail`].
+    /// - Renvoie `payload` dans [`TrySendError::Disconnected`] si le thread s'est arrêté, parce
+    ///   que le traitement des données a paniqué.
+    pub fn send(&self, payload: Vec<u8>) -> Result<(), TrySendError<Vec<u8>>> {
+        self.queue.push(payload)
+    }
+
+    /// Renvoie le nombre de données oubliées parce que la file était pleine.
+    pub fn dropped(&self) -> u64 {
+        self.queue.lock().dropped
+    }
+
+    /// Ferme la file, attend que le thread ait transmis toutes les données déjà envoyées, puis
+    /// renvoie le cumul des bilans de leurs appels.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie la panique du thread si le traitement des données a paniqué.
+    pub fn shutdown(mut self) -> thread::Result<DispatchReport> {
+        self.stop()
+    }
+
+    /// Ferme la file et attend la fin du thread.
+    fn stop(&mut self) -> thread::Result<DispatchReport> {
+        self.queue.close();
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(DispatchReport::default()),
+        }
+    }
+}
+
+impl Drop for DispatchHandle {
+    fn drop(&mut self) {
+        // La panique du thread a déjà été affichée par celui-ci.
+        let _ = self.stop();
+    }
+}
+
+impl<D: AsRef<[u8]> + ?Sized + 'static, R: 'static>
+    CallbackRegistry<'static, CallbackPayload, D, R>
+{
+    /// Démarre un thread qui appelle les callbacks du registre construit par `build` avec chaque
+    /// donnée envoyée par la poignée renvoyée, dans l'ordre de réception. La file n'est pas
+    /// bornée, voir [`spawn_dispatcher_bounded`](Self::spawn_dispatcher_bounded).
+    ///
+    /// Un registre n'est pas `Send` : il est donc construit directement sur le thread, par
+    /// `build`, et tout ce qu'il capture doit être `Send + 'static`. Chaque donnée est transmise
+    /// comme par [`dispatch`](super::CallbackHost::dispatch), la panique d'un callback étant
+    /// isolée comme par [`dispatch_report`](Self::dispatch_report).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, OwnedRegistry};
+    ///
+    /// let handle = OwnedRegistry::spawn_dispatcher(|| {
+    ///     let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    ///     registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    ///     registry
+    /// });
+    /// handle.send(vec![1, 2, 3]).unwrap();
+    /// assert_eq!(handle.shutdown().unwrap().callbacks_invoked, 1);
+    /// ```
+    pub fn spawn_dispatcher(build: impl FnOnce() -> Self + Send + 'static) -> DispatchHandle {
+        Self::spawn_with_queue(None, Backpressure::default(), build)
+    }
+
+    /// Comme [`spawn_dispatcher`](Self::spawn_dispatcher), mais la file garde au plus `capacity`
+    /// données en attente et applique `policy` aux données de trop.
+    ///
+    /// La capacité est non nulle : une file qui ne garde rien bloquerait pour toujours l'envoi
+    /// avec [`Backpressure::Block`].
+    pub fn spawn_dispatcher_bounded(
+        capacity: NonZeroUsize,
+        policy: Backpressure,
+        build: impl FnOnce() -> Self + Send + 'static,
+    ) -> DispatchHandle {
+        Self::spawn_with_queue(Some(capacity), policy, build)
+    }
+
+    /// Démarre le thread d'appel avec une file de `capacity` données au plus.
+    fn spawn_with_queue(
+        capacity: Option<NonZeroUsize>,
+        policy: Backpressure,
+        build: impl FnOnce() -> Self + Send + 'static,
+    ) -> DispatchHandle {
+        let queue = Arc::new(Queue::new(capacity, policy));
+        let guard = ReceiverGuard(Arc::clone(&queue));
+        let thread = thread::spawn(move || {
+            let registry = build();
+            let mut report = DispatchReport::default();
+            while let Some(payload) = guard.0.pop() {
+                report += registry.report_bytes(&payload);
+            }
+            report
+        });
+        DispatchHandle {
+            queue,
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::{CallbackHost, OwnedRegistry};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+
+    /// Démarre un thread dont les deux callbacks comptent leurs appels dans `calls`.
+    fn counting_dispatcher(calls: &Arc<AtomicUsize>) -> DispatchHandle {
+        let calls = Arc::clone(calls);
+        OwnedRegistry::spawn_dispatcher(move || {
+            let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+            for _ in 0..2 {
+                let calls = Arc::clone(&calls);
+                registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                }));
+            }
+            registry
+        })
+    }
+
+    /// Teste que les données de deux threads producteurs sont toutes transmises avant la fin.
+    #[test]
+    fn test_two_producers_then_shutdown() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handle = counting_dispatcher(&calls);
+        thread::scope(|scope| {
+            for producer in 0..2u8 {
+                let handle = &handle;
+                scope.spawn(move || {
+                    for seq in 0..50 {
+                        handle.send(vec![producer, seq]).unwrap();
+                    }
+                });
+            }
+        });
+
+        let report = handle.shutdown().unwrap();
+        assert_eq!(report.callbacks_invoked, 200);
+        assert_eq!(calls.load(Ordering::SeqCst), 200);
+    }
+
+    /// Teste que détruire la poignée attend la transmission des données déjà envoyées.
+    #[test]
+    fn test_drop_drains_and_joins() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handle = counting_dispatcher(&calls);
+        for seq in 0..10 {
+            handle.send(vec![seq]).unwrap();
+        }
+        drop(handle);
+        assert_eq!(calls.load(Ordering::SeqCst), 20);
+    }
+
+    /// Thread d'appel borné dont le callback, lent, attend d'être libéré à chaque donnée.
+    struct SlowDispatcher {
+        handle: DispatchHandle,      // Poignée du thread.
+        started: Receiver<u8>,       // Reçoit chaque donnée quand son appel commence.
+        release: Option<Sender<()>>, // Détruit pour libérer le callback.
+        seen: Arc<Mutex<Vec<u8>>>,   // Données reçues par le callback.
+    }
+
+    impl SlowDispatcher {
+        /// Démarre le thread avec une file de 2 données et la politique `policy`, puis lui envoie
+        /// une première donnée qu'il garde en cours d'appel : la file est alors vide.
+        fn start(policy: Backpressure) -> Self {
+            let (started_tx, started) = mpsc::channel();
+            let (release, release_rx) = mpsc::channel::<()>();
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            let seen_in_cb = Arc::clone(&seen);
+            let handle = OwnedRegistry::spawn_dispatcher_bounded(
+                NonZeroUsize::new(2).unwrap(),
+                policy,
+                move || {
+                    let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+                    registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+                        let _ = started_tx.send(data.as_bytes()[0]);
+                        // Attend que le test détruise `release`.
+                        let _ = release_rx.recv();
+                        seen_in_cb.lock().unwrap().push(data.as_bytes()[0]);
+                    }));
+                    registry
+                },
+            );
+            handle.send(vec![0]).unwrap();
+            assert_eq!(started.recv().unwrap(), 0);
+            SlowDispatcher {
+                handle,
+                started,
+                release: Some(release),
+                seen,
+            }
+        }
+
+        /// Libère le callback, arrête le thread et renvoie les données reçues et oubliées.
+        fn finish(mut self) -> (Vec<u8>, u64) {
+            self.release = None;
+            let dropped = self.handle.dropped();
+            self.handle.shutdown().unwrap();
+            drop(self.started);
+            let seen = self.seen.lock().unwrap().clone();
+            (seen, dropped)
+        }
+    }
+
+    /// Teste les politiques qui n'attendent pas sur une file pleine.
+    #[test]
+    fn test_non_blocking_policies() {
+        let cases = [
+            (Backpressure::DropNewest, vec![0, 1, 2], 1),
+            (Backpressure::DropOldest, vec![0, 2, 3], 1),
+            (Backpressure::Fail, vec![0, 1, 2], 0),
+        ];
+        for (policy, delivered, dropped) in cases {
+            let dispatcher = SlowDispatcher::start(policy);
+            dispatcher.handle.send(vec![1]).unwrap();
+            dispatcher.handle.send(vec![2]).unwrap();
+            let sent = dispatcher.handle.send(vec![3]);
+            if policy == Backpressure::Fail {
+                assert_eq!(sent, Err(TrySendError::Full(vec![3])));
+            } else {
+                assert_eq!(sent, Ok(()));
+            }
+            assert_eq!(dispatcher.finish(), (delivered, dropped), "{:?}", policy);
+        }
+    }
+
+    /// Teste qu'un envoi sur une file pleine attend une place, sans rien oublier.
+    #[test]
+    fn test_block_policy_waits_for_room() {
+        let dispatcher = SlowDispatcher::start(Backpressure::Block);
+        dispatcher.handle.send(vec![1]).unwrap();
+        dispatcher.handle.send(vec![2]).unwrap();
+        thread::scope(|scope| {
+            let blocked = scope.spawn(|| dispatcher.handle.send(vec![3]));
+            // Le premier appel terminé libère une place pour l'envoi en attente.
+            let _ = dispatcher.release.as_ref().unwrap().send(());
+            assert_eq!(blocked.join().unwrap(), Ok(()));
+        });
+        assert_eq!(dispatcher.finish(), (vec![0, 1, 2, 3], 0));
+    }
+
+    /// Teste qu'une file d'une seule place avec `Block` transmet toutes les données.
+    #[test]
+    fn test_capacity_one_blocks_without_deadlock() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let one = NonZeroUsize::new(1).unwrap();
+        let handle = OwnedRegistry::spawn_dispatcher_bounded(one, Backpressure::Block, move || {
+            let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+            registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+                counted.fetch_add(1, Ordering::SeqCst);
+            }));
+            registry
+        });
+        for seq in 0..20 {
+            handle.send(vec![seq]).unwrap();
+        }
+        assert_eq!(handle.shutdown().unwrap().callbacks_invoked, 20);
+        assert_eq!(calls.load(Ordering::SeqCst), 20);
+    }
+}
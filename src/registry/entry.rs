@@ -0,0 +1,229 @@
+//! Entrée interne du registre : un callback et les métadonnées que le registre lui associe.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::keyed::DedupKey;
+use super::quarantine::FailureReason;
+use super::sampling::Sampler;
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+/// Prédicat sur les données, évalué avant d'appeler un callback filtré.
+pub(crate) type Filter<T> = Box<dyn Fn(&T) -> bool>;
+
+/// Un callback enregistré, identifié par son [`CallbackId`].
+pub(crate) struct Entry<T: CallbackData + ?Sized, R = ()> {
+    pub(crate) id: CallbackId, // Identifiant renvoyé à l'enregistrement.
+    pub(crate) callback: Callback<T, R>, // Le callback lui-même.
+    pub(crate) name: Option<String>, // Nom facultatif, unique dans le registre.
+    pub(crate) group: Option<String>, // Groupe facultatif, partagé par plusieurs callbacks.
+    pub(crate) enabled: bool,  // `false` si le callback est temporairement désactivé.
+    remaining: Option<Cell<usize>>, // Nombre d'appels restants, `None` si illimité.
+    cancelled: Option<Rc<Cell<bool>>>, // Drapeau levé par le `SubscriptionGuard` associé.
+    owner: Option<Weak<dyn Any>>, // Propriétaire d'un callback faible, voir `set_callback_weak`.
+    detached: Option<Arc<AtomicBool>>, // Drapeau levé depuis un autre thread, par exemple par un flux.
+    filter: Option<Filter<T>>,         // Prédicat facultatif sur les données.
+    sampler: Option<Sampler>, // Échantillonnage facultatif des appels acceptés par le prédicat.
+    pub(crate) key: Option<Box<dyn DedupKey>>, // Clé de déduplication facultative.
+    pub(crate) invocations: Cell<u64>, // Nombre d'appels du callback.
+    pub(crate) filtered_out: Cell<u64>, // Nombre d'appels refusés par le prédicat.
+    failures: Cell<u32>,      // Nombre d'échecs consécutifs.
+    pub(crate) quarantined: RefCell<Option<FailureReason>>, // Cause de la mise en quarantaine.
+}
+
+impl<T: CallbackData + ?Sized, R> Entry<T, R> {
+    /// Crée une entrée pour `callback` sous l'identifiant `id`.
+    pub(crate) fn new(id: CallbackId, callback: Callback<T, R>) -> Self {
+        Entry {
+            id,
+            callback,
+            name: None,
+            group: None,
+            enabled: true,
+            remaining: None,
+            cancelled: None,
+            owner: None,
+            detached: None,
+            filter: None,
+            sampler: None,
+            key: None,
+            invocations: Cell::new(0),
+            filtered_out: Cell::new(0),
+            failures: Cell::new(0),
+            quarantined: RefCell::new(None),
+        }
+    }
+
+    /// Crée une entrée pour `callback` qui sera appelée au plus `limit` fois.
+    pub(crate) fn limited(id: CallbackId, callback: Callback<T, R>, limit: usize) -> Self {
+        Entry {
+            remaining: Some(Cell::new(limit)),
+            ..Entry::new(id, callback)
+        }
+    }
+
+    /// Donne le nom `name` à l'entrée.
+    pub(crate) fn named(self, name: &str) -> Self {
+        Entry {
+            name: Some(name.to_string()),
+            ..self
+        }
+    }
+
+    /// Place l'entrée dans le groupe `group`.
+    pub(crate) fn in_group(self, group: &str) -> Self {
+        Entry {
+            group: Some(group.to_string()),
+            ..self
+        }
+    }
+
+    /// Indique si l'entrée appartient au groupe `group`.
+    pub(crate) fn is_in_group(&self, group: &str) -> bool {
+        self.group.as_deref() == Some(group)
+    }
+
+    /// Associe à l'entrée la clé de déduplication `key`.
+    pub(crate) fn keyed(self, key: Box<dyn DedupKey>) -> Self {
+        Entry {
+            key: Some(key),
+            ..self
+        }
+    }
+
+    /// Associe à l'entrée le drapeau `cancelled` d'un `SubscriptionGuard`.
+    pub(crate) fn guarded(self, cancelled: Rc<Cell<bool>>) -> Self {
+        Entry {
+            cancelled: Some(cancelled),
+            ..self
+        }
+    }
+
+    /// Lie la durée de vie de l'entrée à celle du propriétaire `owner`.
+    pub(crate) fn owned_by(self, owner: Weak<dyn Any>) -> Self {
+        Entry {
+            owner: Some(owner),
+            ..self
+        }
+    }
+
+    /// Associe à l'entrée le drapeau `detached`, qui peut être levé depuis n'importe quel thread.
+    #[cfg(feature = "async")]
+    pub(crate) fn detached_by(self, detached: Arc<AtomicBool>) -> Self {
+        Entry {
+            detached: Some(detached),
+            ..self
+        }
+    }
+
+    /// N'appellera le callback que pour les données acceptées par `filter`.
+    pub(crate) fn filtered(self, filter: Filter<T>) -> Self {
+        Entry {
+            filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// N'appellera le callback qu'une fois sur `every` parmi les appels acceptés par son prédicat.
+    pub(crate) fn sampled(self, every: u32) -> Self {
+        Entry {
+            sampler: Some(Sampler::new(every)),
+            ..self
+        }
+    }
+
+    /// Appelle le callback avec `data`, sauf s'il a épuisé son nombre d'appels, si son
+    /// prédicat refuse `data` ou si l'échantillonnage écarte cet appel. Renvoie la valeur du
+    /// callback s'il a été appelé.
+    pub(crate) fn invoke(&self, data: &T) -> Option<R> {
+        if self.filter.as_ref().is_some_and(|filter| !filter(data)) {
+            self.filtered_out.set(self.filtered_out.get() + 1);
+            return None;
+        }
+        if self.sampler.as_ref().is_some_and(|sampler| !sampler.tick()) {
+            return None;
+        }
+        if let Some(remaining) = &self.remaining {
+            if remaining.get() == 0 {
+                return None;
+            }
+            // Décrémente avant l'appel pour qu'un appel réentrant ne dépasse pas la limite.
+            remaining.set(remaining.get() - 1);
+        }
+        self.invocations.set(self.invocations.get() + 1);
+        Some(self.callback.invoke(data))
+    }
+
+    /// Note un appel réussi, ce qui remet à zéro le nombre d'échecs consécutifs.
+    pub(crate) fn record_success(&self) {
+        self.failures.set(0);
+    }
+
+    /// Note un échec et met l'entrée en quarantaine s'il atteint `threshold` échecs consécutifs.
+    pub(crate) fn record_failure(&self, reason: FailureReason, threshold: Option<u32>) {
+        let failures = self.failures.get() + 1;
+        self.failures.set(failures);
+        if threshold.is_some_and(|threshold| failures >= threshold) {
+            *self.quarantined.borrow_mut() = Some(reason);
+        }
+    }
+
+    /// Sort l'entrée de quarantaine et oublie ses échecs passés.
+    pub(crate) fn reinstate(&self) {
+        self.failures.set(0);
+        self.quarantined.replace(None);
+    }
+
+    /// Indique si l'entrée a été mise en quarantaine après trop d'échecs.
+    pub(crate) fn is_quarantined(&self) -> bool {
+        self.quarantined.borrow().is_some()
+    }
+
+    /// Renvoie le nombre d'appels restants, ou `None` si le callback n'est pas limité.
+    pub(crate) fn remaining(&self) -> Option<usize> {
+        self.remaining.as_ref().map(Cell::get)
+    }
+
+    /// Indique si l'entrée peut encore être appelée.
+    pub(crate) fn is_live(&self) -> bool {
+        let cancelled = self.cancelled.as_ref().is_some_and(|flag| flag.get())
+            || self
+                .detached
+                .as_ref()
+                .is_some_and(|flag| flag.load(Ordering::Acquire));
+        let orphaned = self
+            .owner
+            .as_ref()
+            .is_some_and(|owner| owner.strong_count() == 0);
+        self.remaining() != Some(0) && !cancelled && !orphaned
+    }
+
+    /// Indique si l'entrée doit être appelée par le prochain `do_something`.
+    pub(crate) fn is_active(&self) -> bool {
+        self.enabled && self.is_live() && !self.is_quarantined()
+    }
+
+    /// Renvoie le nom de l'entrée, à défaut celui du callback, ou `"<anonymous>"`.
+    pub(crate) fn label(&self) -> &str {
+        self.name
+            .as_deref()
+            .or(self.callback.label())
+            .unwrap_or("<anonymous>")
+    }
+}
+
+/// Affiche l'identifiant, le nom, la priorité et l'état d'activation du callback.
+impl<T: CallbackData + ?Sized, R> fmt::Debug for Entry<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Callback")
+            .field("id", &self.id)
+            .field("label", &self.label())
+            .field("priority", &self.callback.priority())
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
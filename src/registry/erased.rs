@@ -0,0 +1,82 @@
+//! Événements de types hétérogènes, transportés par un même registre via [`AnyCallbackData`].
+
+use std::any::Any;
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackId};
+use crate::data::AnyCallbackData;
+
+impl<'a, D: ?Sized> CallbackRegistry<'a, AnyCallbackData, D> {
+    /// Enregistre `f`, appelé uniquement pour les événements de type `E`.
+    ///
+    /// Les événements d'un autre type sont ignorés sans appeler `f`, comme ceux refusés par le
+    /// prédicat d'un [`set_callback_filtered`](Self::set_callback_filtered).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{AnyCallbackData, CallbackRegistry};
+    ///
+    /// struct Connected(u32);
+    /// struct Disconnected(u32);
+    ///
+    /// let mut registry: CallbackRegistry<AnyCallbackData> = CallbackRegistry::new();
+    /// registry.set_callback_typed(|event: &Connected| println!("connexion {}", event.0));
+    /// registry.set_callback_typed(|event: &Disconnected| println!("déconnexion {}", event.0));
+    /// registry.dispatch_any(Box::new(Connected(1)));
+    /// ```
+    pub fn set_callback_typed<E: Any>(&mut self, f: impl Fn(&E) + 'static) -> CallbackId {
+        let cb = Callback::new(move |data: &AnyCallbackData| {
+            if let Some(event) = data.downcast_ref::<E>() {
+                f(event);
+            }
+        });
+        self.push_entry(|id| Entry::new(id, cb).filtered(Box::new(AnyCallbackData::is::<E>)))
+    }
+
+    /// Appelle, dans l'ordre d'appel, chaque callback actif dont le type attendu est celui de
+    /// `event`, ainsi que les callbacks enregistrés directement sur [`AnyCallbackData`].
+    pub fn dispatch_any(&self, event: Box<dyn Any + Send>) {
+        self.dispatch_value(&AnyCallbackData(event));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Debug, PartialEq)]
+    struct Pressed(char);
+
+    #[derive(Debug, PartialEq)]
+    struct Moved(i32, i32);
+
+    /// Teste que chaque callback typé ne voit que les événements de son type.
+    #[test]
+    fn test_typed_callbacks_only_see_their_type() {
+        let pressed = Rc::new(RefCell::new(Vec::new()));
+        let moved = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<AnyCallbackData> = CallbackRegistry::new();
+        let pressed_in_cb = Rc::clone(&pressed);
+        let pressed_id = registry
+            .set_callback_typed(move |event: &Pressed| pressed_in_cb.borrow_mut().push(event.0));
+        let moved_in_cb = Rc::clone(&moved);
+        registry.set_callback_typed(move |event: &Moved| {
+            moved_in_cb.borrow_mut().push((event.0, event.1))
+        });
+
+        registry.dispatch_any(Box::new(Pressed('a')));
+        registry.dispatch_any(Box::new(Moved(3, 4)));
+        registry.dispatch_any(Box::new("ni l'un ni l'autre"));
+
+        assert_eq!(*pressed.borrow(), vec!['a']);
+        assert_eq!(*moved.borrow(), vec![(3, 4)]);
+        let info = registry
+            .iter_callbacks()
+            .find(|info| info.id == pressed_id)
+            .unwrap();
+        assert_eq!((info.invocation_count, info.filtered_out_count), (1, 2));
+    }
+}
@@ -0,0 +1,335 @@
+//! Callbacks faillibles : chaque callback renvoie un `Result` et les erreurs sont remontées.
+
+use std::ops::ControlFlow;
+use std::thread;
+use std::time::Duration;
+
+use super::{CallbackRegistry, Entry, FailureReason};
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+use crate::error::CallbackError;
+
+/// Valeur renvoyée par un callback faillible.
+type Outcome = Result<(), CallbackError>;
+
+/// Erreurs remontées par `try_do_something`, avec l'identifiant du callback fautif.
+type Failures = Vec<(CallbackId, CallbackError)>;
+
+/// Politique de nouvelles tentatives d'un callback faillible, voir
+/// [`CallbackRegistry::set_callback_with_retry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,         // Nombre total de tentatives, au moins 1.
+    pub backoff: Option<Duration>, // Attente fixe entre deux tentatives.
+}
+
+impl RetryPolicy {
+    /// Crée une politique d'au plus `max_attempts` tentatives, sans attente entre elles.
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff: None,
+        }
+    }
+
+    /// Attend `backoff` avant chaque nouvelle tentative.
+    pub fn with_backoff(self, backoff: Duration) -> Self {
+        RetryPolicy {
+            backoff: Some(backoff),
+            ..self
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized + 'static, D: ?Sized> CallbackRegistry<'a, T, D, Outcome> {
+    /// Enregistre `cb`, rappelé sur place jusqu'à `policy.max_attempts` fois tant qu'il échoue.
+    ///
+    /// Les nouvelles tentatives ne rappellent pas les autres callbacks. Seule l'erreur de la
+    /// dernière tentative est remontée par `try_do_something`, et ne compte que pour un échec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::time::Duration;
+    /// use rust_reven::{Callback, CallbackError, CallbackPayload, CallbackRegistry, RetryPolicy};
+    ///
+    /// let attempts = Cell::new(0);
+    /// let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+    /// registry.set_callback_with_retry(
+    ///     RetryPolicy::new(3).with_backoff(Duration::from_millis(1)),
+    ///     Callback::new(move |_data: &CallbackPayload| {
+    ///         attempts.set(attempts.get() + 1);
+    ///         if attempts.get() < 2 {
+    ///             return Err(CallbackError::new("socket occupée"));
+    ///         }
+    ///         Ok(())
+    ///     }),
+    /// );
+    /// assert_eq!(registry.try_do_something(), Ok(()));
+    /// ```
+    pub fn set_callback_with_retry(
+        &mut self,
+        policy: RetryPolicy,
+        cb: Callback<T, Outcome>,
+    ) -> CallbackId {
+        let priority = cb.priority();
+        self.push_callback(Callback::with_priority(priority, move |data: &T| {
+            let mut outcome = cb.invoke(data);
+            for _ in 1..policy.max_attempts {
+                if outcome.is_ok() {
+                    break;
+                }
+                if let Some(backoff) = policy.backoff {
+                    thread::sleep(backoff);
+                }
+                outcome = cb.invoke(data);
+            }
+            outcome
+        }))
+    }
+}
+
+/// Appelle `entry` et compte son éventuel échec pour la mise en quarantaine.
+fn invoke_tracked<T: CallbackData + ?Sized>(
+    entry: &Entry<T, Outcome>,
+    data: &T,
+    threshold: Option<u32>,
+) -> Option<Outcome> {
+    let outcome = entry.invoke(data)?;
+    match &outcome {
+        Ok(()) => entry.record_success(),
+        Err(error) => entry.record_failure(FailureReason::Error(error.clone()), threshold),
+    }
+    Some(outcome)
+}
+
+/// Transforme la liste des erreurs en `Result`.
+fn into_result(failures: Failures) -> Result<(), Failures> {
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized> CallbackRegistry<'a, CallbackPayload, D, Outcome> {
+    /// Appelle tous les callbacks, même après un échec, et renvoie toutes les erreurs avec
+    /// l'identifiant du callback qui l'a produite, dans l'ordre d'appel.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie la liste des erreurs si au moins un callback a échoué.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackError, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[][..]);
+    /// let id = registry.set_callback(Callback::new(|data: &CallbackPayload| {
+    ///     if data.as_bytes().is_empty() {
+    ///         return Err(CallbackError::new("payload vide"));
+    ///     }
+    ///     Ok(())
+    /// }));
+    /// let failures = registry.try_do_something().unwrap_err();
+    /// assert_eq!(failures, vec![(id, CallbackError::new("payload vide"))]);
+    /// ```
+    pub fn try_do_something(&self) -> Result<(), Failures> {
+        let mut failures = Vec::new();
+        let threshold = self.failure_threshold;
+        self.dispatch_with(
+            |_| true,
+            |entry, data| invoke_tracked(entry, data, threshold),
+            |id, outcome| {
+                if let Err(error) = outcome {
+                    failures.push((id, error));
+                }
+                ControlFlow::Continue(())
+            },
+        );
+        into_result(failures)
+    }
+
+    /// Appelle les callbacks jusqu'au premier échec ; les callbacks suivants ne sont pas appelés.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie l'erreur du premier callback en échec, avec son identifiant.
+    pub fn try_do_something_fail_fast(&self) -> Result<(), (CallbackId, CallbackError)> {
+        let mut failure = None;
+        let threshold = self.failure_threshold;
+        self.dispatch_with(
+            |_| true,
+            |entry, data| invoke_tracked(entry, data, threshold),
+            |id, outcome| match outcome {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(error) => {
+                    failure = Some((id, error));
+                    ControlFlow::Break(())
+                }
+            },
+        );
+        failure.map_or(Ok(()), Err)
+    }
+}
+
+impl<'a> CallbackRegistry<'a, ArcCallbackPayload, [u8], Outcome> {
+    /// Appelle tous les callbacks, même après un échec, et renvoie toutes les erreurs.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie la liste des erreurs si au moins un callback a échoué.
+    pub fn try_do_something(&self) -> Result<(), Failures> {
+        let mut failures = Vec::new();
+        let threshold = self.failure_threshold;
+        self.dispatch_with(
+            |_| true,
+            |entry, data| invoke_tracked(entry, data, threshold),
+            |id, outcome| {
+                if let Err(error) = outcome {
+                    failures.push((id, error));
+                }
+                ControlFlow::Continue(())
+            },
+        );
+        into_result(failures)
+    }
+
+    /// Appelle les callbacks jusqu'au premier échec ; les callbacks suivants ne sont pas appelés.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie l'erreur du premier callback en échec, avec son identifiant.
+    pub fn try_do_something_fail_fast(&self) -> Result<(), (CallbackId, CallbackError)> {
+        let mut failure = None;
+        let threshold = self.failure_threshold;
+        self.dispatch_with(
+            |_| true,
+            |entry, data| invoke_tracked(entry, data, threshold),
+            |id, outcome| match outcome {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(error) => {
+                    failure = Some((id, error));
+                    ControlFlow::Break(())
+                }
+            },
+        );
+        failure.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Crée un registre avec un callback en échec suivi d'un callback qui réussit ;
+    /// renvoie aussi l'identifiant du callback en échec.
+    fn failing_then_passing(
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+    ) -> (
+        CallbackRegistry<'static, CallbackPayload, [u8], Outcome>,
+        CallbackId,
+    ) {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let failing_calls = Rc::clone(calls);
+        let failing = registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            failing_calls.borrow_mut().push("failing");
+            Err(CallbackError::new("boom"))
+        }));
+        let passing_calls = Rc::clone(calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            passing_calls.borrow_mut().push("passing");
+            Ok(())
+        }));
+        (registry, failing)
+    }
+
+    /// Teste que `try_do_something` appelle tous les callbacks et remonte l'erreur du callback fautif.
+    #[test]
+    fn test_try_do_something_runs_all_and_aggregates() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (registry, failing) = failing_then_passing(&calls);
+
+        let result = registry.try_do_something();
+
+        assert_eq!(result, Err(vec![(failing, CallbackError::new("boom"))]));
+        assert_eq!(*calls.borrow(), vec!["failing", "passing"]);
+    }
+
+    /// Teste que `try_do_something_fail_fast` s'arrête au premier échec.
+    #[test]
+    fn test_fail_fast_stops_at_first_error() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let (registry, failing) = failing_then_passing(&calls);
+
+        let result = registry.try_do_something_fail_fast();
+
+        assert_eq!(result, Err((failing, CallbackError::new("boom"))));
+        assert_eq!(*calls.borrow(), vec!["failing"]);
+    }
+
+    /// Teste qu'un callback qui échoue deux fois puis réussit est appelé trois fois, avec succès.
+    #[test]
+    fn test_retry_until_success() {
+        let attempts = Rc::new(RefCell::new(0));
+        let attempts_in_cb = Rc::clone(&attempts);
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback_with_retry(
+            RetryPolicy::new(3).with_backoff(Duration::from_millis(1)),
+            Callback::new(move |_data: &CallbackPayload| {
+                *attempts_in_cb.borrow_mut() += 1;
+                if *attempts_in_cb.borrow() <= 2 {
+                    return Err(CallbackError::new("socket occupée"));
+                }
+                Ok(())
+            }),
+        );
+
+        assert_eq!(registry.try_do_something(), Ok(()));
+        assert_eq!(*attempts.borrow(), 3);
+    }
+
+    /// Teste que l'erreur finale est remontée et que les autres callbacks ne sont appelés qu'une fois.
+    #[test]
+    fn test_retry_exhausted_reports_last_error() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let passing_calls = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            passing_calls.borrow_mut().push("passing");
+            Ok(())
+        }));
+        let failing_calls = Rc::clone(&calls);
+        let flaky = registry.set_callback_with_retry(
+            RetryPolicy::new(2),
+            Callback::new(move |_data: &CallbackPayload| {
+                failing_calls.borrow_mut().push("flaky");
+                Err(CallbackError::new("toujours en panne"))
+            }),
+        );
+
+        let result = registry.try_do_something();
+
+        assert_eq!(
+            result,
+            Err(vec![(flaky, CallbackError::new("toujours en panne"))])
+        );
+        assert_eq!(*calls.borrow(), vec!["passing", "flaky", "flaky"]);
+    }
+
+    /// Teste que les deux modes réussissent lorsque aucun callback n'échoue.
+    #[test]
+    fn test_success_in_both_modes() {
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_callback(Callback::new(|_data: &ArcCallbackPayload| Ok(())));
+
+        assert_eq!(registry.try_do_something(), Ok(()));
+        assert_eq!(registry.try_do_something_fail_fast(), Ok(()));
+    }
+}
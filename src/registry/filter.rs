@@ -0,0 +1,90 @@
+//! Callbacks conditionnels, appelés seulement pour les données acceptées par un prédicat.
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb`, que `do_something` n'appelle que si `predicate` accepte les données.
+    ///
+    /// Le prédicat reçoit les données par référence partagée et ne peut donc pas les modifier.
+    /// Un appel refusé ne consomme pas d'appel d'un callback limité.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// const OPCODE_PING: u8 = 0x01;
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[OPCODE_PING, 42][..]);
+    /// registry.set_callback_filtered(
+    ///     |data: &CallbackPayload| data.as_bytes().first() == Some(&OPCODE_PING),
+    ///     Callback::new(|data: &CallbackPayload| println!("ping {:?}", data)),
+    /// );
+    /// registry.do_something();
+    /// ```
+    pub fn set_callback_filtered(
+        &mut self,
+        predicate: impl Fn(&T) -> bool + 'static,
+        cb: Callback<T, R>,
+    ) -> CallbackId {
+        self.push_entry(|id| Entry::new(id, cb).filtered(Box::new(predicate)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Enregistre un callback filtré sur le premier byte `opcode` et un callback inconditionnel.
+    fn opcode_registry(
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        opcode: u8,
+    ) -> CallbackRegistry<'static, CallbackPayload> {
+        let mut registry = CallbackRegistry::with_owned_data(vec![0u8]);
+        let filtered_calls = Rc::clone(calls);
+        registry.set_callback_filtered(
+            move |data: &CallbackPayload| data.as_bytes().first() == Some(&opcode),
+            Callback::new(move |_data: &CallbackPayload| {
+                filtered_calls.borrow_mut().push("filtered")
+            }),
+        );
+        let all_calls = Rc::clone(calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            all_calls.borrow_mut().push("unconditional")
+        }));
+        registry
+    }
+
+    /// Teste qu'un callback filtré n'est appelé que pour les données qui correspondent.
+    #[test]
+    fn test_filtered_callback_matching_and_non_matching() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = opcode_registry(&calls, 0x07);
+
+        registry.set_data(vec![0x07, 1, 2]);
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["filtered", "unconditional"]);
+
+        calls.borrow_mut().clear();
+        registry.set_data(vec![0x08, 1, 2]);
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["unconditional"]);
+    }
+
+    /// Teste qu'une donnée vide est refusée sans paniquer par un prédicat sur le premier byte.
+    #[test]
+    fn test_filtered_callback_with_empty_data() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = opcode_registry(&calls, 0x07);
+
+        registry.set_data(Vec::new());
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["unconditional"]);
+    }
+}
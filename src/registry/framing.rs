@@ -0,0 +1,300 @@
+//! Transmission aux callbacks des trames reconstituées par un [`Framer`].
+
+use std::io::{self, ErrorKind, Read};
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::data::CallbackPayload;
+use crate::framer::{FrameTooLarge, Framer};
+
+/// Taille des lectures de [`CallbackRegistry::run_from_reader`].
+const READ_BUFFER_LEN: usize = 8 * 1024;
+
+/// Bilan d'une lecture par [`CallbackRegistry::run_from_reader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DispatchStats {
+    pub reads: usize,  // Nombre de lectures qui ont renvoyé des bytes.
+    pub bytes: usize,  // Nombre de bytes lus.
+    pub frames: usize, // Nombre de trames transmises aux callbacks.
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Découpera en trames, avec `framer`, les bytes reçus par [`feed`](Self::feed).
+    ///
+    /// Les bytes en attente de l'ancien `Framer` sont oubliés.
+    pub fn set_framer(&mut self, framer: Framer) {
+        self.framer = Some(framer);
+    }
+
+    /// Retire le `Framer` et renvoie-le, avec ses bytes en attente.
+    pub fn take_framer(&mut self) -> Option<Framer> {
+        self.framer.take()
+    }
+
+    /// Ajoute `bytes` au flux et appelle les callbacks une fois par trame complétée, comme
+    /// `do_something` sur les bytes de la trame. Renvoie le nombre de trames complétées, sans
+    /// celles refusées car plus grandes que [`set_max_payload_size`](Self::set_max_payload_size).
+    ///
+    /// # Errors
+    ///
+    /// Renvoie l'erreur de [`Framer::push`] si une trame est trop grande.
+    ///
+    /// # Panics
+    ///
+    /// Panique si aucun `Framer` n'a été défini avec [`set_framer`](Self::set_framer).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, Framer, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_framer(Framer::delimited(b'\n'));
+    /// registry.set_callback(Callback::new(|line: &CallbackPayload| println!("{:?}", line)));
+    /// assert_eq!(registry.feed(b"bonjour\nle mo").unwrap(), 1);
+    /// assert_eq!(registry.feed(b"nde\n").unwrap(), 1);
+    /// ```
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<usize, FrameTooLarge> {
+        let mut framer = self
+            .framer
+            .take()
+            .expect("aucun découpage en trames : appelez `set_framer`");
+        let result = self.feed_with(&mut framer, bytes);
+        self.framer = Some(framer);
+        result
+    }
+
+    /// Comme `feed`, mais avec `framer` au lieu du `Framer` du registre.
+    fn feed_with(&self, framer: &mut Framer, bytes: &[u8]) -> Result<usize, FrameTooLarge> {
+        let mut rejected = 0;
+        let frames = framer.push(bytes, |frame| {
+            if self.check_payload_size(frame.len()).is_err() {
+                rejected += 1;
+            }
+            let dispatched =
+                self.dispatch_bytes(frame, |_| true, Entry::invoke, ignore_result, false);
+            if let Err(error) = dispatched {
+                self.report_invalid(error);
+            }
+        })?;
+        Ok(frames - rejected)
+    }
+
+    /// Lit `r` jusqu'à la fin, découpe les bytes lus en trames avec `frame` et appelle les
+    /// callbacks une fois par trame, comme [`feed`](Self::feed). Le `Framer` du registre n'est
+    /// pas utilisé.
+    ///
+    /// # Errors
+    ///
+    /// - Renvoie l'erreur de lecture de `r`, sauf [`ErrorKind::Interrupted`] qui est réessayée.
+    /// - Renvoie une erreur [`ErrorKind::UnexpectedEof`] si le flux se termine au milieu d'une
+    ///   trame, et [`ErrorKind::InvalidData`] autour de [`FrameTooLarge`] si une trame est trop
+    ///   grande.
+    ///
+    /// Les trames lues avant l'erreur ont été transmises aux callbacks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, Framer, OwnedRegistry};
+    /// use std::io::Cursor;
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_callback(Callback::new(|line: &CallbackPayload| println!("{:?}", line)));
+    /// let stats = registry
+    ///     .run_from_reader(Cursor::new(b"un\ndeux\n"), Framer::delimited(b'\n'))
+    ///     .unwrap();
+    /// assert_eq!(stats.frames, 2);
+    /// ```
+    pub fn run_from_reader(
+        &mut self,
+        mut r: impl Read,
+        mut frame: Framer,
+    ) -> io::Result<DispatchStats> {
+        let mut stats = DispatchStats::default();
+        let mut buffer = vec![0; READ_BUFFER_LEN];
+        loop {
+            let len = match r.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            stats.reads += 1;
+            stats.bytes += len;
+            stats.frames += self
+                .feed_with(&mut frame, &buffer[..len])
+                .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+        }
+        match frame.pending() {
+            0 => Ok(stats),
+            pending => Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                format!(
+                    "fin du flux au milieu d'une trame : {} bytes en attente",
+                    pending
+                ),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    /// Crée un registre à trames préfixées par leur taille dont le callback note les trames reçues.
+    fn framed_registry(
+        seen: &Rc<RefCell<Vec<Vec<u8>>>>,
+    ) -> CallbackRegistry<'static, CallbackPayload> {
+        let mut registry = CallbackRegistry::new();
+        registry.set_framer(Framer::length_prefixed().with_max_frame_len(16));
+        let seen = Rc::clone(seen);
+        registry.set_callback(Callback::new(move |frame: &CallbackPayload| {
+            seen.borrow_mut().push(frame.as_bytes().to_vec())
+        }));
+        registry
+    }
+
+    /// Teste un flux préfixé découpé à des endroits malcommodes : au milieu du préfixe, juste après
+    /// lui, et en plein milieu de plusieurs trames.
+    #[test]
+    fn test_length_prefixed_stream_split_awkwardly() {
+        let stream = [
+            0, 3, 1, 2, 3, // Trame [1, 2, 3].
+            0, 0, // Trame vide.
+            0, 1, 4, // Trame [4].
+            0, 2, 5, 6, // Trame [5, 6].
+        ];
+        // Positions des coupures entre les morceaux donnés à `feed`.
+        let cuts: [&[usize]; 4] = [
+            &[1, 6, 12],
+            &[2, 5, 8],
+            &[],
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        ];
+        for cuts in cuts {
+            let seen = Rc::new(RefCell::new(Vec::new()));
+            let mut registry = framed_registry(&seen);
+            let bounds: Vec<usize> = [0]
+                .iter()
+                .chain(cuts)
+                .chain([&stream.len()])
+                .copied()
+                .collect();
+            let frames: usize = bounds
+                .windows(2)
+                .map(|bound| registry.feed(&stream[bound[0]..bound[1]]).unwrap())
+                .sum();
+            assert_eq!(frames, 4);
+            assert_eq!(
+                *seen.borrow(),
+                vec![vec![1, 2, 3], vec![], vec![4], vec![5, 6]]
+            );
+        }
+    }
+
+    /// Teste qu'une trame trop grande est signalée et que le flux reprend ensuite.
+    #[test]
+    fn test_oversized_frame_then_recovery() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = framed_registry(&seen);
+
+        assert_eq!(
+            registry.feed(&[0, 1, 9, 0xff, 0xff]),
+            Err(FrameTooLarge {
+                len: 0xffff,
+                max: 16
+            })
+        );
+        assert_eq!(registry.feed(&[0, 1, 7]), Ok(1));
+        assert_eq!(*seen.borrow(), vec![vec![9], vec![7]]);
+    }
+
+    /// Lecteur qui renvoie les morceaux donnés, un par lecture, puis l'erreur donnée s'il y en a une.
+    struct ScriptedReader {
+        chunks: Vec<&'static [u8]>, // Morceaux encore à lire, dans l'ordre inverse.
+        error: Option<ErrorKind>,   // Erreur renvoyée après le dernier morceau.
+    }
+
+    impl Read for ScriptedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.chunks.pop() {
+                Some(chunk) => {
+                    buf[..chunk.len()].copy_from_slice(chunk);
+                    Ok(chunk.len())
+                }
+                None => self.error.take().map_or(Ok(0), |kind| Err(kind.into())),
+            }
+        }
+    }
+
+    /// Teste la lecture de plusieurs trames jusqu'à une fin de flux propre.
+    #[test]
+    fn test_run_from_reader_until_clean_eof() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = framed_registry(&seen);
+        let stream = Cursor::new(vec![0, 2, 1, 2, 0, 0, 0, 1, 3]);
+
+        let stats = registry
+            .run_from_reader(stream, Framer::length_prefixed())
+            .unwrap();
+
+        assert_eq!(
+            stats,
+            DispatchStats {
+                reads: 1,
+                bytes: 9,
+                frames: 3
+            }
+        );
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![], vec![3]]);
+    }
+
+    /// Teste qu'une fin de flux au milieu d'une trame est distinguée d'une fin propre.
+    #[test]
+    fn test_run_from_reader_eof_mid_frame() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = framed_registry(&seen);
+
+        let error = registry
+            .run_from_reader(
+                Cursor::new(vec![0, 1, 7, 0, 3, 1]),
+                Framer::length_prefixed(),
+            )
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        assert!(error.to_string().contains("3 bytes en attente"));
+        assert_eq!(*seen.borrow(), vec![vec![7]]);
+    }
+
+    /// Teste qu'une interruption est réessayée et qu'une autre erreur de lecture est renvoyée.
+    #[test]
+    fn test_run_from_reader_read_errors() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = framed_registry(&seen);
+        let interrupted = ScriptedReader {
+            chunks: vec![&[1, 8], &[0]],
+            error: Some(ErrorKind::Interrupted),
+        };
+        let stats = registry
+            .run_from_reader(interrupted, Framer::length_prefixed())
+            .unwrap();
+        assert_eq!(stats.reads, 2);
+
+        let broken = ScriptedReader {
+            chunks: vec![&[0, 1]],
+            error: Some(ErrorKind::BrokenPipe),
+        };
+        let error = registry
+            .run_from_reader(broken, Framer::length_prefixed())
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+        assert_eq!(*seen.borrow(), vec![vec![8]]);
+    }
+}
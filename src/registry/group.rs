@@ -0,0 +1,150 @@
+//! Groupes de callbacks, gérés ensemble (par exemple un groupe par sous-système).
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb` dans le groupe `group`.
+    ///
+    /// L'appartenance à un groupe ne change rien pour `do_something`, qui appelle toujours tous
+    /// les callbacks ; elle permet en plus d'agir sur tout le groupe d'un coup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback_in_group("ui", Callback::new(|_data: &CallbackPayload| println!("redessine")));
+    /// registry.set_callback_in_group("net", Callback::new(|_data: &CallbackPayload| println!("envoie")));
+    /// registry.dispatch_group("net"); // N'affiche que « envoie ».
+    /// assert_eq!(registry.remove_group("ui"), 1);
+    /// assert_eq!(registry.callback_count(), 1);
+    /// ```
+    pub fn set_callback_in_group(&mut self, group: &str, cb: Callback<T, R>) -> CallbackId {
+        self.push_entry(|id| Entry::new(id, cb).in_group(group))
+    }
+
+    /// Retire tous les callbacks du groupe `group` et renvoie leur nombre.
+    pub fn remove_group(&mut self, group: &str) -> usize {
+        self.prune_spent();
+        let before = self.callbacks.len();
+        self.callbacks.retain(|entry| !entry.is_in_group(group));
+        before - self.callbacks.len()
+    }
+
+    /// Désactive tous les callbacks du groupe `group` et renvoie leur nombre.
+    pub fn disable_group(&mut self, group: &str) -> usize {
+        self.set_group_enabled(group, false)
+    }
+
+    /// Réactive tous les callbacks du groupe `group` et renvoie leur nombre.
+    pub fn enable_group(&mut self, group: &str) -> usize {
+        self.set_group_enabled(group, true)
+    }
+
+    // Modifie l'état d'activation de chaque callback du groupe `group`.
+    fn set_group_enabled(&mut self, group: &str, enabled: bool) -> usize {
+        let mut count = 0;
+        for entry in self
+            .callbacks
+            .values_mut()
+            .filter(|entry| entry.is_in_group(group))
+        {
+            entry.enabled = enabled;
+            count += 1;
+        }
+        count
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Comme `do_something`, mais n'appelle que les callbacks actifs du groupe `group`.
+    pub fn dispatch_group(&self, group: &str) {
+        self.dispatch_where(|entry| entry.is_in_group(group), ignore_result);
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Comme `do_something`, mais n'appelle que les callbacks actifs du groupe `group`.
+    pub fn dispatch_group(&self, group: &str) {
+        self.dispatch_where(|entry| entry.is_in_group(group), ignore_result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Enregistre dans `group` un callback qui ajoute `label` à `calls`.
+    fn push_in_group(
+        registry: &mut CallbackRegistry<'_, CallbackPayload>,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        group: &str,
+        label: &'static str,
+    ) -> CallbackId {
+        let calls = Rc::clone(calls);
+        registry.set_callback_in_group(
+            group,
+            Callback::new(move |_data: &CallbackPayload| calls.borrow_mut().push(label)),
+        )
+    }
+
+    /// Crée un registre avec deux callbacks « ui » et un callback « net ».
+    fn grouped_registry(
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+    ) -> CallbackRegistry<'static, CallbackPayload> {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_in_group(&mut registry, calls, "ui", "ui 1");
+        push_in_group(&mut registry, calls, "net", "net");
+        push_in_group(&mut registry, calls, "ui", "ui 2");
+        registry
+    }
+
+    /// Teste que retirer le groupe « ui » ne touche pas aux callbacks « net ».
+    #[test]
+    fn test_remove_group_leaves_other_groups() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = grouped_registry(&calls);
+
+        assert_eq!(registry.remove_group("ui"), 2);
+        assert_eq!(registry.remove_group("ui"), 0);
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["net"]);
+    }
+
+    /// Teste que `dispatch_group` n'appelle que les callbacks du groupe, et que `do_something` les appelle tous.
+    #[test]
+    fn test_dispatch_group_only_runs_group() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let registry = grouped_registry(&calls);
+
+        registry.dispatch_group("ui");
+        assert_eq!(*calls.borrow(), vec!["ui 1", "ui 2"]);
+
+        calls.borrow_mut().clear();
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["ui 1", "net", "ui 2"]);
+    }
+
+    /// Teste la désactivation puis la réactivation d'un groupe entier.
+    #[test]
+    fn test_disable_and_enable_group() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = grouped_registry(&calls);
+
+        assert_eq!(registry.disable_group("ui"), 2);
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["net"]);
+
+        calls.borrow_mut().clear();
+        registry.enable_group("ui");
+        registry.dispatch_group("ui");
+        assert_eq!(*calls.borrow(), vec!["ui 1", "ui 2"]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Abonnements RAII : le callback est retiré quand son garde est détruit.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+/// Garde renvoyé par [`CallbackRegistry::set_callback_guarded`] : détruire le garde retire le callback.
+///
+/// Le garde ne référence pas le registre ; il partage seulement un drapeau avec l'entrée du
+/// callback. Le registre peut donc être détruit avant le garde, et le garde ne bloque aucun
+/// emprunt du registre. L'entrée est ignorée par `do_something` dès la destruction du garde,
+/// et libérée lors de la prochaine modification du registre.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+///
+/// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+/// let guard = registry.set_callback_guarded(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+/// assert_eq!(registry.callback_count(), 1);
+/// drop(guard);
+/// assert_eq!(registry.callback_count(), 0);
+/// ```
+#[derive(Debug)]
+#[must_use = "détruire le garde retire immédiatement le callback"]
+pub struct SubscriptionGuard {
+    id: CallbackId,                    // Identifiant du callback gardé.
+    cancelled: Option<Rc<Cell<bool>>>, // `None` après `forget`.
+}
+
+impl SubscriptionGuard {
+    /// Renvoie l'identifiant du callback gardé.
+    pub fn id(&self) -> CallbackId {
+        self.id
+    }
+
+    /// Détruit le garde sans retirer le callback, qui reste enregistré comme avec `set_callback`.
+    pub fn forget(mut self) -> CallbackId {
+        self.cancelled = None;
+        self.id
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        if let Some(cancelled) = &self.cancelled {
+            cancelled.set(true);
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb` et renvoie un [`SubscriptionGuard`] qui le retire lorsqu'il est détruit.
+    pub fn set_callback_guarded(&mut self, cb: Callback<T, R>) -> SubscriptionGuard {
+        let cancelled = Rc::new(Cell::new(false));
+        let flag = Rc::clone(&cancelled);
+        let id = self.push_entry(|id| Entry::new(id, cb).guarded(flag));
+        SubscriptionGuard {
+            id,
+            cancelled: Some(cancelled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+
+    /// Teste que le callback n'est plus appelé une fois son garde détruit.
+    #[test]
+    fn test_callback_stops_after_guard_drop() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let count_in_cb = Rc::clone(&count);
+        let guard = registry.set_callback_guarded(Callback::new(move |_data: &CallbackPayload| {
+            count_in_cb.set(count_in_cb.get() + 1)
+        }));
+
+        registry.do_something();
+        drop(guard);
+        registry.do_something();
+
+        assert_eq!(count.get(), 1);
+        assert!(!registry.has_callbacks());
+    }
+
+    /// Teste que `forget` laisse le callback enregistré.
+    #[test]
+    fn test_forget_keeps_callback() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let count_in_cb = Rc::clone(&count);
+        let id = registry
+            .set_callback_guarded(Callback::new(move |_data: &CallbackPayload| {
+                count_in_cb.set(count_in_cb.get() + 1)
+            }))
+            .forget();
+
+        registry.do_something();
+        registry.do_something();
+
+        assert_eq!(count.get(), 2);
+        assert!(registry.remove_callback(id));
+    }
+
+    /// Teste que le garde peut survivre au registre.
+    #[test]
+    fn test_guard_outlives_registry() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let count_in_cb = Rc::clone(&count);
+        let guard = registry.set_callback_guarded(Callback::new(move |_data: &CallbackPayload| {
+            count_in_cb.set(count_in_cb.get() + 1)
+        }));
+
+        drop(registry);
+        assert_eq!(Rc::strong_count(&count), 1);
+        drop(guard);
+    }
+}
@@ -0,0 +1,228 @@
+//! Historique des dernières données transmises, rejoué aux callbacks enregistrés plus tard.
+
+use std::cell::{Ref, RefCell};
+use std::sync::Arc;
+
+use super::window::PayloadRing;
+use super::CallbackRegistry;
+use crate::callback::{CallbackData, CallbackId, IntoCallback};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Historique d'un registre : les dernières données transmises, et celles arrivées pendant
+/// qu'il était lu.
+#[derive(Debug)]
+pub(crate) struct HistoryRing {
+    ring: RefCell<PayloadRing>, // Les dernières données, de la plus ancienne à la plus récente.
+    pending: RefCell<PayloadRing>, // Données transmises pendant une lecture de `ring`.
+}
+
+impl HistoryRing {
+    /// Ajoute à `ring` les données en attente, s'il n'est pas en cours de lecture.
+    fn flush(&self) {
+        if let Ok(mut ring) = self.ring.try_borrow_mut() {
+            Self::flush_into(&mut ring, &mut self.pending.borrow_mut());
+        }
+    }
+
+    /// Déplace les données en attente à la fin de `ring`.
+    fn flush_into(ring: &mut PayloadRing, pending: &mut PayloadRing) {
+        for data in pending.iter() {
+            ring.push(data);
+        }
+        *pending = PayloadRing::new(pending.capacity());
+    }
+
+    /// Ajoute `data` à `ring`, ou aux données en attente si `ring` est en cours de lecture.
+    fn push(&self, data: &[u8]) {
+        let mut pending = self.pending.borrow_mut();
+        match self.ring.try_borrow_mut() {
+            Ok(mut ring) => {
+                Self::flush_into(&mut ring, &mut pending);
+                ring.push(data);
+            }
+            Err(_) => pending.push(data),
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Conserve une copie des `capacity` dernières données transmises aux callbacks, en oubliant
+    /// l'historique précédent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![1]);
+    /// registry.enable_history(2);
+    /// for value in 1..=3 {
+    ///     registry.set_data(vec![value]);
+    ///     registry.force_dispatch();
+    /// }
+    /// let history: Vec<Vec<u8>> = registry.history().map(|data| data.to_vec()).collect();
+    /// assert_eq!(history, vec![vec![2], vec![3]]);
+    /// ```
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(HistoryRing {
+            ring: RefCell::new(PayloadRing::new(capacity)),
+            pending: RefCell::new(PayloadRing::new(capacity)),
+        });
+    }
+
+    /// Oublie l'historique et n'en conserve plus.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Itère sur l'historique des dernières données transmises, de la plus ancienne à la plus
+    /// récente ; l'itérateur est vide si l'historique n'est pas activé.
+    ///
+    /// Chaque donnée est empruntée à l'historique, sans copie. Le registre peut transmettre des
+    /// données pendant que l'itérateur est gardé : elles n'y apparaissent pas, et rejoignent
+    /// l'historique une fois l'itérateur détruit.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = Ref<'_, [u8]>> {
+        let ring = self.history.as_ref().map(|history| {
+            history.flush();
+            history.ring.borrow()
+        });
+        let len = ring.as_ref().map_or(0, |ring| ring.len());
+        (0..len).map(move |index| {
+            let ring = ring.as_ref().expect("historique non vide, donc activé");
+            Ref::map(Ref::clone(ring), |ring| ring.get(index))
+        })
+    }
+
+    /// Ajoute `data` à l'historique, s'il est activé.
+    pub(crate) fn record_history(&self, data: &[u8]) {
+        if let Some(history) = &self.history {
+            history.push(data);
+        }
+    }
+}
+
+impl<'a, D: ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Appelle `cb` sur chaque donnée de l'historique, de la plus ancienne à la plus récente,
+    /// puis l'enregistre comme [`set_callback`](crate::CallbackHost::set_callback).
+    pub fn set_callback_with_replay(
+        &mut self,
+        cb: impl IntoCallback<CallbackPayload, R>,
+    ) -> CallbackId {
+        let cb = cb.into_callback();
+        for data in self.history() {
+            cb.invoke(CallbackPayload::new(&data));
+        }
+        self.push_callback(cb)
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Appelle `cb` sur chaque donnée de l'historique, de la plus ancienne à la plus récente,
+    /// puis l'enregistre comme [`set_callback`](crate::CallbackHost::set_callback).
+    pub fn set_callback_with_replay(
+        &mut self,
+        cb: impl IntoCallback<ArcCallbackPayload, R>,
+    ) -> CallbackId {
+        let cb = cb.into_callback();
+        for data in self.history() {
+            cb.invoke(&ArcCallbackPayload::new(Arc::from(&*data)));
+        }
+        self.push_callback(cb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Teste qu'un callback enregistré après 5 appels reçoit les 3 derniers, dans l'ordre, puis
+    /// les suivants normalement.
+    #[test]
+    fn test_replay_last_three_of_five() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.enable_history(3);
+        for value in 1..=5u8 {
+            registry.set_data(vec![value]);
+            registry.do_something();
+        }
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_with_replay(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        });
+        assert_eq!(*seen.borrow(), vec![3, 4, 5]);
+
+        registry.set_data(vec![6]);
+        registry.do_something();
+        assert_eq!(*seen.borrow(), vec![3, 4, 5, 6]);
+        let history: Vec<Vec<u8>> = registry.history().map(|data| data.to_vec()).collect();
+        assert_eq!(history, vec![vec![4], vec![5], vec![6]]);
+    }
+
+    /// Teste qu'un historique gardé n'empêche pas de transmettre de nouvelles données.
+    #[test]
+    fn test_history_kept_across_dispatch() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.enable_history(3);
+        registry.do_something();
+
+        let mut history = registry.history();
+        registry.do_something();
+        assert_eq!(&*history.next().unwrap(), &[1]);
+        assert!(history.next().is_none());
+        drop(history);
+
+        registry.set_data(vec![2]);
+        registry.do_something();
+        let history: Vec<Vec<u8>> = registry.history().map(|data| data.to_vec()).collect();
+        assert_eq!(history, vec![vec![1], vec![1], vec![2]]);
+    }
+
+    /// Teste que seules les données transmises aux callbacks entrent dans l'historique.
+    #[test]
+    fn test_history_skips_unchanged_and_paused() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        assert_eq!(registry.history().count(), 0);
+        registry.enable_history(4);
+        registry.dispatch_on_change(true);
+
+        registry.do_something();
+        registry.do_something();
+        registry.pause();
+        registry.set_data(vec![2]);
+        registry.do_something();
+        registry.resume();
+
+        assert_eq!(registry.history().count(), 1);
+        registry.disable_history();
+        assert_eq!(registry.history().count(), 0);
+    }
+
+    /// Teste le rejeu sur un registre à données partagées, sans historique activé.
+    #[test]
+    fn test_replay_without_history_and_on_arc_registry() {
+        let mut registry: CallbackRegistry<'static, ArcCallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![7u8]);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback_with_replay(move |data: &ArcCallbackPayload| {
+            calls_in_cb.borrow_mut().push(data.to_arc())
+        });
+        assert!(calls.borrow().is_empty());
+
+        registry.enable_history(2);
+        registry.do_something();
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback_with_replay(move |data: &ArcCallbackPayload| {
+            calls_in_cb.borrow_mut().push(data.to_arc())
+        });
+        assert_eq!(calls.borrow().len(), 2);
+        assert_eq!(&calls.borrow()[1][..], &[7]);
+    }
+}
@@ -0,0 +1,131 @@
+//! Inspection des callbacks enregistrés, sans accès aux closures elles-mêmes.
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{CallbackData, CallbackId};
+
+/// Vue en lecture seule sur un callback enregistré, renvoyée par [`CallbackRegistry::iter_callbacks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackInfo<'r> {
+    pub id: CallbackId,          // Identifiant du callback.
+    pub name: Option<&'r str>,   // Nom donné par `set_named_callback`.
+    pub priority: i32,           // Priorité du callback.
+    pub enabled: bool,           // `false` si le callback est désactivé.
+    pub group: Option<&'r str>,  // Groupe donné par `set_callback_in_group`.
+    pub invocation_count: u64,   // Nombre d'appels du callback.
+    pub filtered_out_count: u64, // Nombre d'appels refusés par son prédicat.
+}
+
+impl<'r> CallbackInfo<'r> {
+    // Construit la vue sur `entry`.
+    fn of<T: CallbackData + ?Sized, R>(entry: &'r Entry<T, R>) -> Self {
+        CallbackInfo {
+            id: entry.id,
+            name: entry.name.as_deref(),
+            priority: entry.callback.priority(),
+            enabled: entry.enabled,
+            group: entry.group.as_deref(),
+            invocation_count: entry.invocations.get(),
+            filtered_out_count: entry.filtered_out.get(),
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Itère sur les callbacks enregistrés, dans l'ordre d'appel.
+    ///
+    /// Les callbacks retirés ou épuisés n'apparaissent pas ; les callbacks désactivés apparaissent
+    /// avec `enabled == false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_named_callback("logger", Callback::new(|_data: &CallbackPayload| {})).unwrap();
+    /// registry.do_something();
+    /// for info in registry.iter_callbacks() {
+    ///     println!("{:?} {:?} : {} appels", info.id, info.name, info.invocation_count);
+    /// }
+    /// ```
+    pub fn iter_callbacks(&self) -> impl Iterator<Item = CallbackInfo<'_>> {
+        self.live_entries().map(CallbackInfo::of)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+
+    /// Teste que les métadonnées de trois callbacks annotés sont restituées dans l'ordre d'appel.
+    #[test]
+    fn test_iter_callbacks_metadata() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let logger = registry
+            .set_named_callback(
+                "logger",
+                Callback::with_priority(-5, |_data: &CallbackPayload| {}),
+            )
+            .unwrap();
+        let net =
+            registry.set_callback_in_group("net", Callback::new(|_data: &CallbackPayload| {}));
+        let muted = registry.set_callback_filtered(
+            |_data: &CallbackPayload| false,
+            Callback::with_priority(3, |_data: &CallbackPayload| {}),
+        );
+        registry.do_something();
+        registry.disable_callback(muted);
+        registry.do_something();
+
+        let infos: Vec<CallbackInfo<'_>> = registry.iter_callbacks().collect();
+
+        assert_eq!(
+            infos,
+            vec![
+                CallbackInfo {
+                    id: logger,
+                    name: Some("logger"),
+                    priority: -5,
+                    enabled: true,
+                    group: None,
+                    invocation_count: 2,
+                    filtered_out_count: 0,
+                },
+                CallbackInfo {
+                    id: net,
+                    name: None,
+                    priority: 0,
+                    enabled: true,
+                    group: Some("net"),
+                    invocation_count: 2,
+                    filtered_out_count: 0,
+                },
+                CallbackInfo {
+                    id: muted,
+                    name: None,
+                    priority: 3,
+                    enabled: false,
+                    group: None,
+                    invocation_count: 0,
+                    filtered_out_count: 1,
+                },
+            ]
+        );
+    }
+
+    /// Teste que les callbacks retirés n'apparaissent plus.
+    #[test]
+    fn test_iter_callbacks_reflects_removals() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let id = registry.set_callback(Callback::new(|_data: &CallbackPayload| {}));
+        registry.set_callback_once(|_data: &CallbackPayload| {});
+
+        registry.do_something();
+        registry.remove_callback(id);
+
+        assert_eq!(registry.iter_callbacks().count(), 0);
+    }
+}
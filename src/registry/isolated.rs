@@ -0,0 +1,144 @@
+//! Isolation des paniques : un callback qui panique n'interrompt pas l'appel des suivants.
+
+use std::any::Any;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::{CallbackRegistry, Entry, FailureReason};
+use crate::callback::{CallbackData, CallbackId};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Paniques interceptées par `dispatch_isolated`, avec l'identifiant du callback fautif.
+type Panics = Vec<(CallbackId, Box<dyn Any + Send>)>;
+
+/// Appelle `entry` en interceptant une éventuelle panique, comptée comme un échec.
+///
+/// Les callbacks ne sont pas tenus d'être `UnwindSafe` : après une panique, l'état qu'ils
+/// capturent peut être incohérent, mais il n'est observable que par ce même callback.
+pub(crate) fn invoke_isolated<T: CallbackData + ?Sized, R>(
+    entry: &Entry<T, R>,
+    data: &T,
+    threshold: Option<u32>,
+) -> Option<Result<R, Box<dyn Any + Send>>> {
+    match panic::catch_unwind(AssertUnwindSafe(|| entry.invoke(data))) {
+        Ok(result) => {
+            let result = result?;
+            entry.record_success();
+            Some(Ok(result))
+        }
+        Err(payload) => {
+            entry.record_failure(FailureReason::from_panic(payload.as_ref()), threshold);
+            Some(Err(payload))
+        }
+    }
+}
+
+/// Sink de `dispatch_with` qui conserve les paniques dans `panics`.
+fn collect_panics<R>(
+    panics: &mut Panics,
+) -> impl FnMut(CallbackId, Result<R, Box<dyn Any + Send>>) -> ControlFlow<()> + '_ {
+    move |id, outcome| {
+        if let Err(payload) = outcome {
+            panics.push((id, payload));
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Comme `do_something`, mais intercepte la panique d'un callback et continue avec les suivants.
+    ///
+    /// Renvoie les paniques interceptées, dans l'ordre d'appel, avec l'identifiant du callback qui
+    /// a paniqué. `do_something` reste disponible sans ce surcoût lorsque les callbacks sont sûrs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let faulty = registry.set_callback(Callback::new(|_data: &CallbackPayload| panic!("greffon défectueux")));
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+    ///
+    /// let panics = registry.dispatch_isolated();
+    /// assert_eq!(panics.len(), 1);
+    /// assert_eq!(panics[0].0, faulty);
+    /// ```
+    pub fn dispatch_isolated(&self) -> Panics {
+        let mut panics = Vec::new();
+        let threshold = self.failure_threshold;
+        self.dispatch_with(
+            |_| true,
+            |entry, data| invoke_isolated(entry, data, threshold),
+            collect_panics(&mut panics),
+        );
+        panics
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Comme `do_something`, mais intercepte la panique d'un callback et continue avec les suivants.
+    ///
+    /// Renvoie les paniques interceptées, dans l'ordre d'appel, avec l'identifiant du callback fautif.
+    pub fn dispatch_isolated(&self) -> Panics {
+        let mut panics = Vec::new();
+        let threshold = self.failure_threshold;
+        self.dispatch_with(
+            |_| true,
+            |entry, data| invoke_isolated(entry, data, threshold),
+            collect_panics(&mut panics),
+        );
+        panics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste que les callbacks enregistrés après un callback qui panique sont quand même appelés.
+    #[test]
+    fn test_callbacks_after_panic_still_run() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let faulty = registry.set_callback(Callback::new(|_data: &CallbackPayload| panic!("boom")));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("after")
+        }));
+
+        let panics = registry.dispatch_isolated();
+
+        assert_eq!(*calls.borrow(), vec!["after"]);
+        assert_eq!(panics.len(), 1);
+        assert_eq!(panics[0].0, faulty);
+        assert_eq!(panics[0].1.downcast_ref::<&str>(), Some(&"boom"));
+    }
+
+    /// Teste qu'aucune panique n'est signalée lorsque tous les callbacks réussissent.
+    #[test]
+    fn test_no_panics_reported_on_success() {
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_callback(Callback::new(|_data: &ArcCallbackPayload| {}));
+
+        assert!(registry.dispatch_isolated().is_empty());
+    }
+
+    /// Teste qu'un callback `from_fn_mut` reste appelable après avoir paniqué.
+    #[test]
+    fn test_fn_mut_callback_usable_after_panic() {
+        let mut calls = 0;
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(Callback::from_fn_mut(move |_data: &CallbackPayload| {
+            calls += 1;
+            assert!(calls > 1, "premier appel en échec");
+        }));
+
+        assert_eq!(registry.dispatch_isolated().len(), 1);
+        assert!(registry.dispatch_isolated().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! Déduplication des callbacks par clé : réenregistrer une clé remplace l'ancien callback.
+
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+/// Clé de déduplication de type quelconque, comparable à une clé d'un autre type.
+pub(crate) trait DedupKey {
+    /// Renvoie l'empreinte de la clé, comparée avant l'égalité.
+    fn key_hash(&self) -> u64;
+    /// Indique si `other` est une clé du même type et de même valeur.
+    fn key_eq(&self, other: &dyn Any) -> bool;
+    /// Renvoie la clé sous forme de `dyn Any`, pour `key_eq`.
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Clé et empreinte précalculée.
+struct HashedKey<K> {
+    key: K,
+    hash: u64,
+}
+
+impl<K: Hash + Eq + 'static> DedupKey for HashedKey<K> {
+    fn key_hash(&self) -> u64 {
+        self.hash
+    }
+
+    fn key_eq(&self, other: &dyn Any) -> bool {
+        other
+            .downcast_ref::<HashedKey<K>>()
+            .is_some_and(|other| other.key == self.key)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Indique si deux clés de déduplication sont égales.
+pub(crate) fn same_key(key: &dyn DedupKey, other: &dyn DedupKey) -> bool {
+    key.key_hash() == other.key_hash() && key.key_eq(other.as_any())
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb` sous la clé de déduplication `key`.
+    ///
+    /// Si un callback est déjà enregistré sous une clé égale (de même type), il est remplacé par
+    /// `cb`, qui reprend sa place dans l'ordre d'appel lorsque les priorités sont égales.
+    /// Renvoie l'identifiant de `cb` et, le cas échéant, le callback remplacé.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let (_, replaced) = registry.set_callback_keyed("plugin-a", Callback::new(|_data: &CallbackPayload| {}));
+    /// assert!(replaced.is_none());
+    /// // Le plugin est rechargé : son handler remplace l'ancien au lieu de s'ajouter.
+    /// let (_, replaced) = registry.set_callback_keyed("plugin-a", Callback::new(|_data: &CallbackPayload| {}));
+    /// assert!(replaced.is_some());
+    /// assert_eq!(registry.callback_count(), 1);
+    /// ```
+    pub fn set_callback_keyed<K: Hash + Eq + 'static>(
+        &mut self,
+        key: K,
+        cb: Callback<T, R>,
+    ) -> (CallbackId, Option<Callback<T, R>>) {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let key = HashedKey {
+            key,
+            hash: hasher.finish(),
+        };
+
+        let priority = cb.priority();
+        self.prune_spent();
+        let existing =
+            self.callbacks
+                .iter()
+                .find(|entry| {
+                    entry.key.as_ref().is_some_and(|other| {
+                        other.key_hash() == key.hash && key.key_eq(other.as_any())
+                    })
+                })
+                .map(|entry| (entry.id, entry.callback.priority()));
+        let key: Box<dyn DedupKey> = Box::new(key);
+        match existing {
+            // Même priorité : le nouveau callback prend la place de l'ancien.
+            Some((old, old_priority)) if old_priority == priority => {
+                let id = self.next_id();
+                let old = self
+                    .callbacks
+                    .replace(old, Entry::new(id, cb).keyed(key))
+                    .expect("l'entrée vient d'être trouvée");
+                (id, Some(old.callback))
+            }
+            // Priorité différente : le nouveau callback est inséré selon sa priorité.
+            Some((old, _)) => {
+                let old = self
+                    .callbacks
+                    .remove(old)
+                    .expect("l'entrée vient d'être trouvée");
+                let id = self.push_entry(|id| Entry::new(id, cb).keyed(key));
+                (id, Some(old.callback))
+            }
+            None => (self.push_entry(|id| Entry::new(id, cb).keyed(key)), None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste qu'une même clé enregistrée deux fois ne donne qu'un appel, avec la nouvelle closure,
+    /// à la place de l'ancienne.
+    #[test]
+    fn test_same_key_replaces_in_place() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("first")
+        }));
+        let calls_in_cb = Rc::clone(&calls);
+        let (old_id, _) = registry.set_callback_keyed(
+            "plugin",
+            Callback::new(move |_data: &CallbackPayload| {
+                calls_in_cb.borrow_mut().push("old plugin")
+            }),
+        );
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("last")
+        }));
+
+        let calls_in_cb = Rc::clone(&calls);
+        let (new_id, replaced) = registry.set_callback_keyed(
+            "plugin",
+            Callback::new(move |_data: &CallbackPayload| {
+                calls_in_cb.borrow_mut().push("new plugin")
+            }),
+        );
+        registry.do_something();
+
+        assert!(replaced.is_some());
+        assert_ne!(old_id, new_id);
+        assert_eq!(*calls.borrow(), vec!["first", "new plugin", "last"]);
+    }
+
+    /// Teste que des clés différentes, ou de types différents, ne se remplacent pas.
+    #[test]
+    fn test_distinct_keys_are_kept() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback_keyed(
+            "a",
+            Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("str a")),
+        );
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback_keyed(
+            "b",
+            Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("str b")),
+        );
+        let calls_in_cb = Rc::clone(&calls);
+        let (_, replaced) = registry.set_callback_keyed(
+            1u32,
+            Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("u32 1")),
+        );
+
+        assert!(replaced.is_none());
+        assert_eq!(registry.callback_count(), 3);
+    }
+
+    /// Teste qu'un remplacement de priorité différente respecte l'ordre des priorités.
+    #[test]
+    fn test_replacement_with_other_priority_is_reordered() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback_keyed(
+            "plugin",
+            Callback::new(move |_data: &CallbackPayload| {
+                calls_in_cb.borrow_mut().push("old plugin")
+            }),
+        );
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("default")
+        }));
+
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback_keyed(
+            "plugin",
+            Callback::with_priority(10, move |_data: &CallbackPayload| {
+                calls_in_cb.borrow_mut().push("new plugin")
+            }),
+        );
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["default", "new plugin"]);
+    }
+}
@@ -0,0 +1,103 @@
+//! Routage des données brutes selon leur type d'événement, voir [`Event`].
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackId};
+use crate::data::CallbackPayload;
+use crate::event::Event;
+
+impl<'a, D: ?Sized, R: 'static> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Enregistre `f`, appelé avec l'[`Event`] décodé uniquement si son type est `kind`.
+    ///
+    /// Les données vides, qui ne contiennent pas de type d'événement, ne sont transmises à aucun
+    /// callback de ce genre.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackRegistry, Event};
+    ///
+    /// const KIND_PING: u8 = 0x01;
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[KIND_PING, 42][..]);
+    /// registry.set_callback_for_kind(KIND_PING, |event: &Event| println!("ping {:?}", event.payload));
+    /// registry.set_callback_for_any_kind(|event: &Event| println!("événement {}", event.kind));
+    /// registry.do_something();
+    /// ```
+    pub fn set_callback_for_kind(
+        &mut self,
+        kind: u8,
+        f: impl Fn(&Event<'_>) -> R + 'static,
+    ) -> CallbackId {
+        self.push_event_callback(
+            move |data: &CallbackPayload| data.as_bytes().first() == Some(&kind),
+            f,
+        )
+    }
+
+    /// Enregistre `f`, appelé avec l'[`Event`] décodé quel que soit son type.
+    pub fn set_callback_for_any_kind(
+        &mut self,
+        f: impl Fn(&Event<'_>) -> R + 'static,
+    ) -> CallbackId {
+        self.push_event_callback(|data: &CallbackPayload| !data.as_bytes().is_empty(), f)
+    }
+
+    // Enregistre `f` derrière `accepts`, qui garantit que les données contiennent un événement.
+    fn push_event_callback(
+        &mut self,
+        accepts: impl Fn(&CallbackPayload) -> bool + 'static,
+        f: impl Fn(&Event<'_>) -> R + 'static,
+    ) -> CallbackId {
+        let cb = Callback::new(move |data: &CallbackPayload| {
+            let event =
+                Event::parse(data.as_bytes()).expect("données vides refusées par le filtre");
+            f(&event)
+        });
+        self.push_entry(|id| Entry::new(id, cb).filtered(Box::new(accepts)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::EmptyEvent;
+    use crate::registry::{CallbackHost, OwnedRegistry};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste que chaque callback ne reçoit que son type d'événement, et le joker tous les types.
+    #[test]
+    fn test_kind_routing() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> =
+            OwnedRegistry::with_owned_data(vec![1u8, 10]);
+        for kind in [1u8, 2] {
+            let seen = Rc::clone(&seen);
+            registry.set_callback_for_kind(kind, move |event: &Event| {
+                seen.borrow_mut()
+                    .push(format!("{}:{:?}", kind, event.payload))
+            });
+        }
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback_for_any_kind(move |event: &Event| {
+            seen_in_cb.borrow_mut().push(format!("*:{}", event.kind))
+        });
+
+        registry.do_something();
+        registry.set_data(vec![2u8]);
+        registry.do_something();
+        registry.set_data(vec![3u8, 0]);
+        registry.do_something();
+        registry.set_data(Vec::new());
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec!["1:[10]", "*:1", "2:[]", "*:2", "*:3"]);
+    }
+
+    /// Teste que le décodage de données vides échoue.
+    #[test]
+    fn test_parse_empty_slice_fails() {
+        assert_eq!(Event::parse(&[]), Err(EmptyEvent));
+        assert_eq!(Event::parse(&[7]), Ok(Event::new(7, &[])));
+    }
+}
@@ -0,0 +1,191 @@
+//! Callbacks appelés un nombre limité de fois, puis retirés automatiquement.
+
+use std::cell::{Cell, RefCell};
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::error::ZeroLimit;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `f`, qui sera appelé lors du prochain `do_something` uniquement.
+    ///
+    /// Le callback est retiré automatiquement après son appel : il n'est plus compté par
+    /// `callback_count` et son état capturé est détruit dès la fin de l'appel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback_once(|data: &CallbackPayload| println!("Première donnée : {:?}", data));
+    /// assert_eq!(registry.callback_count(), 1);
+    /// registry.do_something();
+    /// assert_eq!(registry.callback_count(), 0);
+    /// ```
+    pub fn set_callback_once(&mut self, f: impl FnOnce(&T) -> R + 'static) -> CallbackId {
+        // Le `Cell` permet de consommer le `FnOnce` depuis un `Fn`.
+        let f = Cell::new(Some(f));
+        let cb = Callback::new(move |data: &T| {
+            // L'entrée, limitée à un appel, ne rappelle jamais ce callback.
+            let f = f.take().expect("callback unique déjà appelé");
+            f(data)
+        });
+        self.push_entry(|id| Entry::limited(id, cb, 1))
+    }
+
+    /// Enregistre `cb`, qui sera appelé par au plus `limit` appels à `do_something` puis retiré.
+    ///
+    /// La closure est détruite dès son dernier appel ; [`remaining_invocations`](Self::remaining_invocations)
+    /// indique entre-temps combien d'appels il lui reste.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`ZeroLimit`] si `limit` vaut 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let id = registry
+    ///     .set_callback_limited(2, Callback::new(|data: &CallbackPayload| println!("{:?}", data)))
+    ///     .unwrap();
+    /// registry.do_something();
+    /// assert_eq!(registry.remaining_invocations(id), Some(1));
+    /// registry.do_something();
+    /// assert_eq!(registry.remaining_invocations(id), None);
+    /// ```
+    pub fn set_callback_limited(
+        &mut self,
+        limit: usize,
+        cb: Callback<T, R>,
+    ) -> Result<CallbackId, ZeroLimit>
+    where
+        T: 'static,
+        R: 'static,
+    {
+        if limit == 0 {
+            return Err(ZeroLimit);
+        }
+        // La closure est détruite après son dernier appel. L'entrée décompte elle aussi les appels
+        // et ne rappelle jamais un callback épuisé.
+        let priority = cb.priority();
+        let slot = RefCell::new(Some(cb));
+        let left = Cell::new(limit);
+        let wrapper = Callback::with_priority(priority, move |data: &T| {
+            left.set(left.get() - 1);
+            let result = slot
+                .borrow()
+                .as_ref()
+                .map(|cb| cb.invoke(data))
+                .expect("callback limité déjà épuisé");
+            if left.get() == 0 {
+                // Lors d'un appel réentrant, c'est l'appel le plus externe qui détruit la closure.
+                if let Ok(mut slot) = slot.try_borrow_mut() {
+                    *slot = None;
+                }
+            }
+            result
+        });
+        Ok(self.push_entry(|id| Entry::limited(id, wrapper, limit)))
+    }
+
+    /// Renvoie le nombre d'appels restants du callback `id`.
+    ///
+    /// Renvoie `None` si `id` est inconnu, déjà épuisé, ou s'il n'a pas de limite d'appels.
+    pub fn remaining_invocations(&self, id: CallbackId) -> Option<usize> {
+        self.live_entries()
+            .find(|entry| entry.id == id)
+            .and_then(Entry::remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Teste qu'un callback limité à 3 appels n'est appelé que 3 fois sur 5 appels à `do_something`.
+    #[test]
+    fn test_limit_three_over_five_dispatches() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let count_in_cb = Rc::clone(&count);
+        let id = registry
+            .set_callback_limited(
+                3,
+                Callback::new(move |_data: &CallbackPayload| {
+                    count_in_cb.set(count_in_cb.get() + 1)
+                }),
+            )
+            .unwrap();
+
+        registry.do_something();
+        assert_eq!(registry.remaining_invocations(id), Some(2));
+        registry.do_something();
+        assert_eq!(registry.remaining_invocations(id), Some(1));
+        for _ in 0..3 {
+            registry.do_something();
+        }
+
+        assert_eq!(count.get(), 3);
+        assert_eq!(registry.remaining_invocations(id), None);
+        assert_eq!(registry.callback_count(), 0);
+    }
+
+    /// Teste qu'une limite nulle est refusée sans enregistrer le callback.
+    #[test]
+    fn test_zero_limit_is_rejected() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+
+        let count_in_cb = Rc::clone(&count);
+        assert_eq!(
+            registry.set_callback_limited(
+                0,
+                Callback::new(move |_data: &CallbackPayload| count_in_cb.set(count_in_cb.get() + 1))
+            ),
+            Err(ZeroLimit)
+        );
+        assert_eq!(registry.callback_count(), 0);
+    }
+
+    /// Teste que la closure est détruite dès son dernier appel, avant tout retrait explicite.
+    #[test]
+    fn test_closure_dropped_when_limit_reached() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let count_in_cb = Rc::clone(&count);
+        registry
+            .set_callback_limited(
+                2,
+                Callback::new(move |_data: &CallbackPayload| {
+                    count_in_cb.set(count_in_cb.get() + 1)
+                }),
+            )
+            .unwrap();
+
+        registry.do_something();
+        assert_eq!(Rc::strong_count(&count), 2);
+        registry.do_something();
+
+        assert_eq!(Rc::strong_count(&count), 1);
+    }
+
+    /// Teste qu'un callback sans limite n'a pas de compteur d'appels restants.
+    #[test]
+    fn test_unlimited_callback_has_no_remaining_count() {
+        let count = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let count_in_cb = Rc::clone(&count);
+        let id = registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            count_in_cb.set(count_in_cb.get() + 1)
+        }));
+
+        assert_eq!(registry.remaining_invocations(id), None);
+    }
+}
@@ -0,0 +1,258 @@
+//! Fusion de deux registres.
+
+use super::keyed::same_key;
+use super::CallbackRegistry;
+use crate::callback::{CallbackData, CallbackId};
+use crate::error::{DuplicateName, MergeError, RegistryFull};
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Déplace tous les callbacks de `other` dans ce registre, qui garde ses propres données.
+    ///
+    /// Chaque chaîne est déplacée : callbacks ordinaires, modificateurs, callbacks parallèles,
+    /// asynchrones et périodiques. Chaque callback déplacé reçoit un nouvel identifiant de ce
+    /// registre et conserve ses autres métadonnées (nom, groupe, priorité, clé, ...). Un callback
+    /// ordinaire est placé après les callbacks existants de même priorité, sans recevoir les
+    /// dernières données du mode collant : il les a déjà reçues, ou non, dans `other` ; les
+    /// autres sont ajoutés à la fin de leur chaîne. `other` reste vide mais utilisable. Renvoie
+    /// les paires (ancien identifiant dans `other`, nouvel identifiant), chaîne par chaîne dans
+    /// l'ordre ci-dessus, et dans l'ordre d'appel de `other` pour chaque chaîne.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`MergeError`] si un nom ou une clé de déduplication est utilisé dans les deux
+    /// registres, ou si la fusion dépasse le nombre maximal de callbacks de ce registre. Aucun
+    /// registre n'est alors modifié.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut network = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// network.set_callback(Callback::new(|_data: &CallbackPayload| println!("net")));
+    /// let mut metrics = CallbackRegistry::with_data(&[][..]);
+    /// metrics.set_callback(Callback::new(|_data: &CallbackPayload| println!("metrics")));
+    ///
+    /// network.merge(&mut metrics).unwrap();
+    /// assert_eq!(network.callback_count(), 2);
+    /// assert!(!metrics.has_callbacks());
+    /// ```
+    pub fn merge<D2: ?Sized>(
+        &mut self,
+        other: &mut CallbackRegistry<'_, T, D2, R>,
+    ) -> Result<Vec<(CallbackId, CallbackId)>, MergeError> {
+        self.prune_spent();
+        other.prune_spent();
+
+        let names = self.callback_names();
+        if let Some(name) = other
+            .callback_names()
+            .into_iter()
+            .find(|name| names.contains(name))
+        {
+            return Err(MergeError::DuplicateName(DuplicateName {
+                name: name.to_string(),
+            }));
+        }
+        let keys: Vec<_> = self
+            .callbacks
+            .iter()
+            .filter_map(|entry| entry.key.as_deref())
+            .collect();
+        if let Some(entry) = other.callbacks.iter().find(|entry| {
+            entry
+                .key
+                .as_deref()
+                .is_some_and(|key| keys.iter().any(|other| same_key(key, *other)))
+        }) {
+            return Err(MergeError::DuplicateKey(entry.id));
+        }
+        if let Some(limit) = self.max_callbacks {
+            let count = self.callbacks.len() + other.callbacks.len();
+            if count > limit {
+                return Err(MergeError::RegistryFull(RegistryFull { count, limit }));
+            }
+        }
+
+        self.callbacks.reserve(other.callbacks.len());
+        let mut ids = Vec::new();
+        for mut entry in other.callbacks.drain() {
+            let old_id = entry.id;
+            entry.id = self.next_id();
+            ids.push((old_id, entry.id));
+            self.callbacks.insert_by_priority(entry);
+        }
+        for (old_id, mutator) in other.mutators.drain(..) {
+            let id = self.next_id();
+            self.mutators.push((id, mutator));
+            ids.push((old_id, id));
+        }
+        for (old_id, callback) in other.parallel.callbacks.drain(..) {
+            let id = self.next_id();
+            self.parallel.callbacks.push((id, callback));
+            ids.push((old_id, id));
+        }
+        #[cfg(feature = "async")]
+        for (old_id, callback) in other.async_callbacks.callbacks.drain(..) {
+            let id = self.next_id();
+            self.async_callbacks.callbacks.push((id, callback));
+            ids.push((old_id, id));
+        }
+        for mut periodic in other.timers.periodic.drain(..) {
+            let old_id = periodic.id;
+            periodic.id = self.next_id();
+            ids.push((old_id, periodic.id));
+            self.timers.periodic.push(periodic);
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::clock::{Clock, ManualClock};
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    /// Teste l'ordre d'appel après fusion, par priorité puis registre d'origine, et l'unicité des identifiants.
+    #[test]
+    fn test_merge_order_and_unique_ids() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut network = CallbackRegistry::with_data(&[1u8][..]);
+        let mut metrics = CallbackRegistry::with_data(&[2u8][..]);
+        for (priority, label) in [(0, "net"), (10, "net cleanup")] {
+            let calls_in_cb = Rc::clone(&calls);
+            network.set_callback(Callback::with_priority(
+                priority,
+                move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push(label),
+            ));
+        }
+        for (priority, label) in [(-10, "metrics logging"), (0, "metrics")] {
+            let calls_in_cb = Rc::clone(&calls);
+            metrics.set_callback(Callback::with_priority(
+                priority,
+                move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push(label),
+            ));
+        }
+
+        let ids = network.merge(&mut metrics).unwrap();
+        network.do_something();
+
+        assert_eq!(
+            *calls.borrow(),
+            vec!["metrics logging", "net", "metrics", "net cleanup"]
+        );
+        let all_ids: HashSet<CallbackId> = network.iter_callbacks().map(|info| info.id).collect();
+        assert_eq!(all_ids.len(), 4);
+        assert!(ids.iter().all(|(_, new_id)| all_ids.contains(new_id)));
+        assert!(!metrics.has_callbacks());
+    }
+
+    /// Teste qu'un nom présent dans les deux registres fait échouer la fusion sans rien déplacer.
+    #[test]
+    fn test_merge_rejects_duplicate_names() {
+        let mut first = CallbackRegistry::with_data(&[1u8][..]);
+        first
+            .set_named_callback("logger", Callback::new(|_data: &CallbackPayload| {}))
+            .unwrap();
+        let mut second = CallbackRegistry::with_data(&[2u8][..]);
+        second
+            .set_named_callback("logger", Callback::new(|_data: &CallbackPayload| {}))
+            .unwrap();
+
+        let error = first.merge(&mut second).unwrap_err();
+
+        assert!(matches!(error, MergeError::DuplicateName(ref e) if e.name == "logger"));
+        assert_eq!(first.callback_count(), 1);
+        assert_eq!(second.callback_count(), 1);
+    }
+
+    /// Teste que la limite compte les callbacks des deux registres, et que l'erreur aussi.
+    #[test]
+    fn test_merge_rejects_combined_overflow() {
+        let mut first = CallbackRegistry::with_data(&[1u8][..]);
+        first.set_max_callbacks(Some(3));
+        first.set_callback(|_data: &CallbackPayload| {});
+        first.set_callback(|_data: &CallbackPayload| {});
+        let mut second = CallbackRegistry::with_data(&[2u8][..]);
+        second.set_callback(|_data: &CallbackPayload| {});
+        second.set_callback(|_data: &CallbackPayload| {});
+
+        let error = first.merge(&mut second).unwrap_err();
+
+        assert_eq!(
+            error,
+            MergeError::RegistryFull(RegistryFull { count: 4, limit: 3 })
+        );
+        assert_eq!((first.callback_count(), second.callback_count()), (2, 2));
+    }
+
+    /// Teste que les callbacks déplacés ne reçoivent pas les dernières données du mode collant.
+    #[test]
+    fn test_merge_skips_sticky_delivery() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut first: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_data(&[1u8][..]);
+        first.enable_sticky();
+        first.do_something();
+        let mut second = CallbackRegistry::with_data(&[2u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        second.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("moved"));
+
+        first.merge(&mut second).unwrap();
+        assert!(calls.borrow().is_empty());
+
+        first.do_something();
+        assert_eq!(*calls.borrow(), vec!["moved"]);
+    }
+
+    /// Teste qu'un modificateur, un callback périodique et un callback à clé sont déplacés, et
+    /// qu'une clé présente dans les deux registres fait échouer la fusion.
+    #[test]
+    fn test_merge_moves_every_chain() {
+        let clock = ManualClock::new();
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut first: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1]);
+        first.set_timer_clock(clock.clone());
+        let mut second: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        second.set_timer_clock(clock.clone());
+        second.set_callback_mut(|data: &mut CallbackPayload| data.as_mut_bytes()[0] += 1);
+        let ticks = Rc::clone(&calls);
+        second.set_periodic_callback(Duration::from_secs(1), move || {
+            ticks.borrow_mut().push("tick")
+        });
+        let plugin = Rc::clone(&calls);
+        second.set_callback_keyed(
+            "plugin",
+            Callback::new(move |_data: &CallbackPayload| plugin.borrow_mut().push("plugin")),
+        );
+
+        let ids = first.merge(&mut second).unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(!second.has_callbacks());
+        assert_eq!(second.next_due(), None);
+
+        first.do_something_mut();
+        assert_eq!(first.data(), &[2]);
+        first.do_something();
+        clock.advance(Duration::from_secs(1));
+        first.run_due(clock.now());
+        assert_eq!(*calls.borrow(), vec!["plugin", "tick"]);
+
+        let mut third: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let (clashing, _) =
+            third.set_callback_keyed("plugin", Callback::new(|_data: &CallbackPayload| {}));
+        assert_eq!(
+            first.merge(&mut third).unwrap_err(),
+            MergeError::DuplicateKey(clashing)
+        );
+        assert_eq!((first.callback_count(), third.callback_count()), (1, 1));
+    }
+}
@@ -0,0 +1,131 @@
+//! Callbacks qui modifient les données, appelés en chaîne par `do_something_mut`.
+
+use super::CallbackRegistry;
+use crate::callback::{CallbackData, CallbackId};
+use crate::data::CallbackPayload;
+
+/// Callback qui reçoit les données en écriture.
+pub(crate) type Mutator = Box<dyn FnMut(&mut CallbackPayload)>;
+
+impl<'a, T: CallbackData + ?Sized, R> CallbackRegistry<'a, T, [u8], R> {
+    /// Enregistre `f`, qui sera appelé par [`do_something_mut`](Self::do_something_mut) avec les
+    /// données en écriture.
+    ///
+    /// Ces callbacks forment une chaîne distincte de celle de `do_something` : ils sont appelés
+    /// dans leur ordre d'enregistrement et ne sont ni comptés par `callback_count`, ni retirés
+    /// par `remove_callback` ; utilisez [`remove_callback_mut`](Self::remove_callback_mut).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![1, 2]);
+    /// registry.set_callback_mut(|data: &mut CallbackPayload| data.as_mut_bytes().reverse());
+    /// registry.do_something_mut();
+    /// assert_eq!(registry.data(), &[2, 1]);
+    /// ```
+    pub fn set_callback_mut(
+        &mut self,
+        f: impl FnMut(&mut CallbackPayload) + 'static,
+    ) -> CallbackId {
+        let id = self.next_id();
+        self.mutators.push((id, Box::new(f)));
+        id
+    }
+
+    /// Retire le callback modificateur `id` et indique s'il était enregistré.
+    pub fn remove_callback_mut(&mut self, id: CallbackId) -> bool {
+        let before = self.mutators.len();
+        self.mutators.retain(|(other, _)| *other != id);
+        let removed = before != self.mutators.len();
+        if removed {
+            self.callbacks.ids().borrow_mut().release(id);
+        }
+        removed
+    }
+
+    /// Appelle chaque callback enregistré par [`set_callback_mut`](Self::set_callback_mut) avec
+    /// les données en écriture, puis le traitement des données : chaque modification est visible
+    /// du callback suivant et du traitement, voir [`set_processor`](Self::set_processor).
+    ///
+    /// Les données empruntées ou partagées sont d'abord copiées dans un tampon possédé par le
+    /// registre ; l'original n'est jamais modifié et [`data`](Self::data) renvoie le résultat.
+    /// Comme `do_something`, l'appel est ignoré tant que le registre est en pause, et les données
+    /// qui dépassent la taille maximale sont refusées sans être copiées.
+    pub fn do_something_mut(&mut self) {
+        if !self.begin_dispatch() || self.mutators.is_empty() {
+            return;
+        }
+        if !self.admit_payload_size(self.data.get().len()) {
+            return;
+        }
+        let data = self.data.make_mut();
+        for (_, mutator) in &mut self.mutators {
+            mutator(CallbackPayload::new_mut(data));
+            if self.processor.per_callback() {
+                self.processor.process(data);
+            }
+        }
+        if !self.processor.per_callback() {
+            self.processor.process(data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Inverse l'ordre des bytes de chaque mot de 16 bits.
+    fn swap_u16(data: &mut CallbackPayload) {
+        for word in data.as_mut_bytes().chunks_exact_mut(2) {
+            word.swap(0, 1);
+        }
+    }
+
+    /// Remplace le dernier byte par la somme (modulo 256) des précédents.
+    fn insert_checksum(data: &mut CallbackPayload) {
+        let bytes = data.as_mut_bytes();
+        let (body, checksum) = bytes.split_at_mut(bytes.len() - 1);
+        checksum[0] = body.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    }
+
+    /// Teste que le second callback voit les bytes modifiés par le premier.
+    #[test]
+    fn test_mutating_callbacks_are_chained() {
+        let mut registry: CallbackRegistry<CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![0x01, 0x02, 0x03, 0x04, 0x00]);
+        registry.set_callback_mut(swap_u16);
+        registry.set_callback_mut(insert_checksum);
+
+        registry.do_something_mut();
+
+        assert_eq!(registry.data(), &[0x02, 0x01, 0x04, 0x03, 0x0A]);
+    }
+
+    /// Teste que des données empruntées sont copiées avant modification, et que `do_something` voit le résultat.
+    #[test]
+    fn test_borrowed_data_is_copied_before_mutation() {
+        let bytes = [1u8, 2];
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_in_cb = Rc::clone(&seen);
+        let mut registry = CallbackRegistry::with_data(&bytes[..]);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().extend_from_slice(data.as_bytes())
+        }));
+        let id = registry.set_callback_mut(|data: &mut CallbackPayload| data.as_mut_bytes()[0] = 7);
+
+        registry.do_something_mut();
+        registry.do_something();
+
+        assert_eq!(bytes, [1, 2]);
+        assert_eq!(*seen.borrow(), vec![7, 2]);
+        assert!(registry.remove_callback_mut(id));
+        assert!(!registry.remove_callback_mut(id));
+    }
+}
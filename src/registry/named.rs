@@ -0,0 +1,193 @@
+//! Enregistrement et recherche de callbacks par nom.
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::error::DuplicateName;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb` sous le nom `name`, qui apparaît ensuite dans la sortie `Debug` du registre.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`DuplicateName`] si un callback porte déjà ce nom ; le registre n'est alors pas modifié.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let noop = || Callback::new(|_data: &CallbackPayload| {});
+    /// registry.set_named_callback("flush_metrics", noop()).unwrap();
+    /// assert!(registry.set_named_callback("flush_metrics", noop()).is_err());
+    /// assert_eq!(registry.callback_names(), vec!["flush_metrics"]);
+    /// ```
+    pub fn set_named_callback(
+        &mut self,
+        name: &str,
+        cb: Callback<T, R>,
+    ) -> Result<CallbackId, DuplicateName> {
+        if self.callback_names().contains(&name) {
+            return Err(DuplicateName {
+                name: name.to_string(),
+            });
+        }
+        Ok(self.push_entry(|id| Entry::new(id, cb).named(name)))
+    }
+
+    /// Retire le callback nommé `name`. Renvoie `false` si aucun callback ne porte ce nom.
+    pub fn remove_by_name(&mut self, name: &str) -> bool {
+        let id = self
+            .callbacks
+            .iter()
+            .find(|entry| entry.name.as_deref() == Some(name))
+            .map(|entry| entry.id);
+        match id {
+            Some(id) => self.remove_entry(id).is_some(),
+            None => false,
+        }
+    }
+
+    /// Renvoie les noms des callbacks nommés, dans l'ordre d'appel.
+    pub fn callback_names(&self) -> Vec<&str> {
+        self.live_entries()
+            .filter_map(|entry| entry.name.as_deref())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste qu'un nom déjà utilisé est refusé sans modifier le registre.
+    #[test]
+    fn test_duplicate_name_is_rejected() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_named_callback(
+                "logger",
+                Callback::new(move |_data: &CallbackPayload| {
+                    calls_in_cb.borrow_mut().push("first")
+                }),
+            )
+            .unwrap();
+
+        let calls_in_cb = Rc::clone(&calls);
+        let error = registry
+            .set_named_callback(
+                "logger",
+                Callback::new(move |_data: &CallbackPayload| {
+                    calls_in_cb.borrow_mut().push("second")
+                }),
+            )
+            .unwrap_err();
+
+        assert_eq!(error.name, "logger");
+        assert_eq!(registry.callback_count(), 1);
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["first"]);
+    }
+
+    /// Teste qu'un nom retiré peut être réutilisé.
+    #[test]
+    fn test_remove_then_register_same_name() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_named_callback(
+                "logger",
+                Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("old")),
+            )
+            .unwrap();
+
+        assert!(registry.remove_by_name("logger"));
+        assert!(!registry.remove_by_name("logger"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_named_callback(
+                "logger",
+                Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("new")),
+            )
+            .unwrap();
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), vec!["new"]);
+    }
+
+    /// Teste que `callback_names` ignore les callbacks anonymes et respecte l'ordre d'appel.
+    #[test]
+    fn test_callback_names_in_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_named_callback(
+                "b",
+                Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b")),
+            )
+            .unwrap();
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("anonymous")
+        }));
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_named_callback(
+                "a",
+                Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a")),
+            )
+            .unwrap();
+
+        assert_eq!(registry.callback_names(), vec!["b", "a"]);
+    }
+
+    /// Teste que les noms apparaissent dans la sortie `Debug` du registre.
+    #[test]
+    fn test_names_in_debug_output() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_named_callback(
+                "flush_metrics",
+                Callback::new(move |_data: &CallbackPayload| {
+                    calls_in_cb.borrow_mut().push("flush")
+                }),
+            )
+            .unwrap();
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("anonymous")
+        }));
+
+        let output = format!("{:?}", registry);
+        assert!(output.contains("flush_metrics"));
+        assert!(output.contains("<anonymous>"));
+    }
+
+    /// Teste que les noms donnés par `Callback::named` apparaissent dans la sortie `Debug` du registre.
+    #[test]
+    fn test_callback_labels_in_debug_output() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(Callback::named(
+            "flush_metrics",
+            |_data: &CallbackPayload| {},
+        ));
+        let id =
+            registry.set_callback(Callback::named("rotate_logs", |_data: &CallbackPayload| {}));
+        registry.disable_callback(id);
+
+        let output = format!("{:?}", registry);
+        assert!(output.contains("flush_metrics"));
+        assert!(output.contains("rotate_logs"));
+        assert!(output.contains("enabled: false"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Callbacks appelés en parallèle sur plusieurs threads par `dispatch_parallel`.
+
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use super::{CallbackRegistry, DispatchReport};
+use crate::callback::{CallbackData, CallbackId};
+
+/// Callback qui peut être appelé depuis n'importe quel thread.
+pub(crate) type ParallelCallback<T> = Box<dyn Fn(&T) + Send + Sync>;
+
+/// Callbacks de `dispatch_parallel` et nombre de threads qui les appellent.
+pub(crate) struct ParallelCallbacks<T: ?Sized> {
+    pub(crate) callbacks: Vec<(CallbackId, ParallelCallback<T>)>, // Dans l'ordre d'enregistrement.
+    workers: Option<NonZeroUsize>, // Nombre de threads, `None` pour le parallélisme disponible.
+}
+
+impl<T: ?Sized> Default for ParallelCallbacks<T> {
+    fn default() -> Self {
+        ParallelCallbacks {
+            callbacks: Vec::new(),
+            workers: None,
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `f`, qui sera appelé par [`dispatch_parallel`](Self::dispatch_parallel),
+    /// depuis un autre thread.
+    ///
+    /// Comme les callbacks de `do_something_mut`, ces callbacks forment une chaîne distincte :
+    /// ils ne sont ni comptés par `callback_count`, ni appelés par `do_something` ; utilisez
+    /// [`remove_parallel_callback`](Self::remove_parallel_callback) pour les retirer.
+    pub fn set_parallel_callback(&mut self, f: impl Fn(&T) + Send + Sync + 'static) -> CallbackId {
+        let id = self.next_id();
+        self.parallel.callbacks.push((id, Box::new(f)));
+        id
+    }
+
+    /// Retire le callback parallèle `id` et indique s'il était enregistré.
+    pub fn remove_parallel_callback(&mut self, id: CallbackId) -> bool {
+        let callbacks = &mut self.parallel.callbacks;
+        let before = callbacks.len();
+        callbacks.retain(|(other, _)| *other != id);
+        let removed = before != callbacks.len();
+        if removed {
+            self.callbacks.ids().borrow_mut().release(id);
+        }
+        removed
+    }
+
+    /// Fixe le nombre de threads de `dispatch_parallel`, par défaut celui renvoyé par
+    /// [`std::thread::available_parallelism`].
+    pub fn set_parallelism(&mut self, workers: NonZeroUsize) {
+        self.parallel.workers = Some(workers);
+    }
+
+    /// Appelle chaque callback enregistré par [`set_parallel_callback`](Self::set_parallel_callback)
+    /// avec `data`, en les répartissant sur plusieurs threads, et attend qu'ils aient tous terminé
+    /// pour renvoyer le bilan de l'appel.
+    ///
+    /// Contrairement à `do_something`, l'ordre des appels n'est pas garanti : deux callbacks
+    /// peuvent s'exécuter en même temps, dans n'importe quel ordre. Les données sont transmises
+    /// telles quelles, sans validation ni transformation ; l'appel est seulement ignoré tant que
+    /// le registre est en pause. La panique d'un callback est isolée et comptée parmi les erreurs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackPayload, OwnedRegistry};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let total = Arc::new(AtomicUsize::new(0));
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// for _ in 0..4 {
+    ///     let total = Arc::clone(&total);
+    ///     registry.set_parallel_callback(move |data: &CallbackPayload| {
+    ///         total.fetch_add(data.as_bytes().len(), Ordering::SeqCst);
+    ///     });
+    /// }
+    /// let bytes = [1, 2, 3];
+    /// let report = registry.dispatch_parallel(CallbackPayload::new(&bytes));
+    /// assert_eq!(report.callbacks_invoked, 4);
+    /// assert_eq!(total.load(Ordering::SeqCst), 12);
+    /// ```
+    pub fn dispatch_parallel(&self, data: &T) -> DispatchReport
+    where
+        T: Sync,
+    {
+        let callbacks = &self.parallel.callbacks;
+        if !self.begin_dispatch() {
+            return DispatchReport {
+                skipped: callbacks.len(),
+                ..DispatchReport::default()
+            };
+        }
+        let start = Instant::now();
+        let workers = self
+            .parallel
+            .workers
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+            .min(callbacks.len());
+        let (next, errors) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while let Some((_, callback)) =
+                        callbacks.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        if panic::catch_unwind(AssertUnwindSafe(|| callback(data))).is_err() {
+                            errors.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        DispatchReport {
+            callbacks_invoked: callbacks.len(),
+            skipped: 0,
+            duration: start.elapsed(),
+            errors: errors.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::OwnedRegistry;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Teste que 50 callbacks lents, répartis sur 10 threads, sont tous appelés une seule fois
+    /// en bien moins de temps que leurs durées cumulées.
+    #[test]
+    fn test_slow_callbacks_run_concurrently() {
+        let calls: Arc<Vec<AtomicUsize>> = Arc::new((0..50).map(|_| AtomicUsize::new(0)).collect());
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        registry.set_parallelism(NonZeroUsize::new(10).unwrap());
+        for index in 0..50 {
+            let calls = Arc::clone(&calls);
+            registry.set_parallel_callback(move |_data: &CallbackPayload| {
+                thread::sleep(Duration::from_millis(20));
+                calls[index].fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        let bytes = vec![1, 2, 3];
+        let start = Instant::now();
+        let report = registry.dispatch_parallel(CallbackPayload::new(&bytes));
+        let elapsed = start.elapsed();
+
+        assert_eq!((report.callbacks_invoked, report.errors), (50, 0));
+        assert!(calls.iter().all(|count| count.load(Ordering::SeqCst) == 1));
+        // Appelés en série, les callbacks prendraient une seconde.
+        assert!(elapsed < Duration::from_millis(500), "{:?}", elapsed);
+    }
+
+    /// Teste qu'une panique est comptée sans empêcher les autres appels, et que la pause
+    /// écarte tous les callbacks.
+    #[test]
+    fn test_panic_isolated_and_pause_skips() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        let id =
+            registry.set_parallel_callback(|_data: &CallbackPayload| panic!("greffon défectueux"));
+        for _ in 0..3 {
+            let calls = Arc::clone(&calls);
+            registry.set_parallel_callback(move |_data: &CallbackPayload| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        let report = registry.dispatch_parallel(CallbackPayload::new(&[7]));
+        assert_eq!((report.callbacks_invoked, report.errors), (4, 1));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        registry.pause();
+        let report = registry.dispatch_parallel(CallbackPayload::new(&[7]));
+        assert_eq!((report.callbacks_invoked, report.skipped), (0, 4));
+        assert!(registry.remove_parallel_callback(id));
+        assert!(!registry.remove_parallel_callback(id));
+    }
+}
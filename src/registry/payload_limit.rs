@@ -0,0 +1,167 @@
+//! Taille maximale des données transmises aux callbacks.
+
+use super::CallbackRegistry;
+use crate::callback::CallbackData;
+use crate::error::PayloadTooLarge;
+
+/// Reçoit les données refusées car trop grandes, voir [`CallbackRegistry::set_on_rejected`].
+pub(crate) type RejectedHandler = Box<dyn Fn(&PayloadTooLarge)>;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Refuse les données de plus de `limit` bytes : aucun callback n'est appelé pour elles et
+    /// l'erreur est transmise au gestionnaire de [`set_on_rejected`](Self::set_on_rejected).
+    ///
+    /// La taille est celle des données brutes, avant somme de contrôle et transformations ; avec
+    /// [`feed`](Self::feed), c'est celle de chaque trame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![0; 512]);
+    /// registry.set_max_payload_size(256);
+    /// registry.set_on_rejected(|error| eprintln!("{}", error));
+    /// registry.set_callback(|_data: &CallbackPayload| unreachable!());
+    /// registry.do_something();
+    /// assert!(registry.try_set_data(&[0; 257]).is_err());
+    /// ```
+    pub fn set_max_payload_size(&mut self, limit: usize) {
+        self.max_payload_size = Some(limit);
+    }
+
+    /// Retire la taille maximale des données.
+    pub fn clear_max_payload_size(&mut self) {
+        self.max_payload_size = None;
+    }
+
+    /// Renvoie la taille maximale des données, ou `None` si elle est illimitée.
+    pub fn max_payload_size(&self) -> Option<usize> {
+        self.max_payload_size
+    }
+
+    /// Transmet à `f` les données refusées car trop grandes, à la place de l'ancien gestionnaire.
+    pub fn set_on_rejected(&mut self, f: impl Fn(&PayloadTooLarge) + 'static) {
+        self.on_rejected = Some(Box::new(f));
+    }
+
+    /// Vérifie que des données de `size` bytes ne dépassent pas la taille maximale.
+    pub(crate) fn check_payload_size(&self, size: usize) -> Result<(), PayloadTooLarge> {
+        match self.max_payload_size {
+            Some(limit) if size > limit => Err(PayloadTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Transmet `error` au gestionnaire des données refusées, s'il y en a un.
+    pub(crate) fn report_rejected(&self, error: &PayloadTooLarge) {
+        if let Some(on_rejected) = &self.on_rejected {
+            on_rejected(error);
+        }
+    }
+
+    /// Comme `check_payload_size`, mais transmet l'erreur au gestionnaire des données refusées.
+    pub(crate) fn admit_payload_size(&self, size: usize) -> bool {
+        self.check_payload_size(size)
+            .inspect_err(|error| self.report_rejected(error))
+            .is_ok()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, R> CallbackRegistry<'a, T, [u8], R> {
+    /// Remplace les données du registre par une copie de `data`, sauf si elles dépassent la
+    /// taille maximale.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`PayloadTooLarge`] si `data` est trop grande ; elle n'est alors pas copiée et les
+    /// données du registre ne changent pas.
+    pub fn try_set_data(&mut self, data: &[u8]) -> Result<(), PayloadTooLarge> {
+        self.check_payload_size(data.len())?;
+        self.set_data(data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::data::CallbackPayload;
+    use crate::framer::Framer;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste que des données de la taille maximale sont transmises, et celles d'un byte de plus
+    /// refusées et signalées.
+    #[test]
+    fn test_at_limit_accepted_one_over_rejected() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let rejected = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.set_max_payload_size(4);
+        let rejected_in_cb = Rc::clone(&rejected);
+        registry.set_on_rejected(move |error| rejected_in_cb.borrow_mut().push(*error));
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().len())
+        }));
+
+        for size in [4, 5] {
+            registry.set_data(vec![0; size]);
+            registry.do_something();
+        }
+        assert_eq!(*seen.borrow(), vec![4]);
+        assert_eq!(
+            *rejected.borrow(),
+            vec![PayloadTooLarge { size: 5, limit: 4 }]
+        );
+
+        registry.clear_max_payload_size();
+        registry.do_something();
+        assert_eq!(*seen.borrow(), vec![4, 5]);
+    }
+
+    /// Teste que les trames trop grandes de `feed` sont refusées une à une, et ne sont pas
+    /// comptées parmi les trames renvoyées.
+    #[test]
+    fn test_oversized_frames_rejected() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let rejected = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(Vec::new());
+        registry.set_max_payload_size(4);
+        let rejected_in_cb = Rc::clone(&rejected);
+        registry.set_on_rejected(move |error| rejected_in_cb.borrow_mut().push(*error));
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes().len())
+        }));
+        registry.set_framer(Framer::delimited(b'\n'));
+
+        assert_eq!(registry.feed(b"abcd\nabcde\nab\n"), Ok(2));
+        assert_eq!(*seen.borrow(), vec![4, 2]);
+        assert_eq!(rejected.borrow()[0].size, 5);
+
+        assert_eq!(registry.feed(b"wxyz\n"), Ok(1));
+        assert_eq!(registry.feed(b"vwxyz\n"), Ok(0));
+        assert_eq!(*seen.borrow(), vec![4, 2, 4]);
+        assert_eq!(rejected.borrow()[1], PayloadTooLarge { size: 5, limit: 4 });
+    }
+
+    /// Teste que `try_set_data` ne copie pas des données trop grandes.
+    #[test]
+    fn test_try_set_data_refuses_oversized_copy() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_max_payload_size(2);
+        assert_eq!(registry.try_set_data(&[2, 3]), Ok(()));
+        assert_eq!(
+            registry.try_set_data(&[4, 5, 6]),
+            Err(PayloadTooLarge { size: 3, limit: 2 })
+        );
+        assert_eq!(registry.data(), &[2, 3]);
+    }
+}
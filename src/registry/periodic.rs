@@ -0,0 +1,291 @@
+//! Callbacks périodiques, appelés par `run_due` à intervalle régulier, indépendamment des données.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::{CallbackRegistry, DispatchReport};
+use crate::callback::{CallbackData, CallbackId};
+
+/// Nombre maximal d'appels d'un callback [`CatchUp::EachMissed`] par appel à `run_due`.
+const MAX_CATCH_UP: u32 = 64;
+
+/// Que faire des intervalles écoulés entre deux appels à `run_due`, voir
+/// [`CallbackRegistry::set_periodic_catch_up`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CatchUp {
+    /// Le callback est appelé une seule fois, quel que soit le nombre d'intervalles écoulés.
+    #[default]
+    Once,
+    /// Le callback est appelé une fois par intervalle écoulé, au plus 64 fois par `run_due` ;
+    /// les intervalles au-delà ne sont pas rattrapés.
+    EachMissed,
+}
+
+/// Un callback périodique et son prochain appel.
+pub(crate) struct Periodic {
+    pub(crate) id: CallbackId, // Identifiant renvoyé à l'enregistrement.
+    interval: Duration,        // Durée entre deux appels.
+    next: Option<Instant>,     // Heure du prochain appel, `None` hors de portée d'`Instant`.
+    catch_up: CatchUp,         // Appels dus pour les intervalles écoulés.
+    enabled: bool,             // `false` si le callback est temporairement désactivé.
+    f: Box<dyn FnMut()>,       // Le callback lui-même.
+}
+
+impl fmt::Debug for Periodic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Periodic")
+            .field("id", &self.id)
+            .field("interval", &self.interval)
+            .field("next", &self.next)
+            .field("catch_up", &self.catch_up)
+            .field("enabled", &self.enabled)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `f`, appelé par [`run_due`](Self::run_due) toutes les `interval`, à partir de
+    /// l'heure actuelle selon l'horloge de [`set_timer_clock`](Self::set_timer_clock).
+    ///
+    /// Comme un callback ordinaire, `f` se retire avec `remove_callback`, se désactive avec
+    /// [`disable_callback`](Self::disable_callback) et n'est pas appelé pendant une
+    /// [`pause`](Self::pause) ; les intervalles écoulés pendant la pause ou la désactivation ne
+    /// sont pas rattrapés. Les intervalles écoulés entre deux `run_due` sont rattrapés selon
+    /// [`set_periodic_catch_up`](Self::set_periodic_catch_up), une seule fois par défaut.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `interval` est nul. Un intervalle trop long pour être ajouté à l'heure actuelle,
+    /// comme `Duration::MAX`, est accepté : le callback n'est alors jamais appelé.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackPayload, Clock, ManualClock, OwnedRegistry};
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_timer_clock(clock.clone());
+    /// registry.set_periodic_callback(Duration::from_secs(1), || println!("vidage"));
+    /// clock.advance(Duration::from_secs(1));
+    /// assert_eq!(registry.run_due(clock.now()).callbacks_invoked, 1);
+    /// ```
+    pub fn set_periodic_callback(
+        &mut self,
+        interval: Duration,
+        f: impl FnMut() + 'static,
+    ) -> CallbackId {
+        assert!(
+            !interval.is_zero(),
+            "l'intervalle d'un callback périodique doit être non nul"
+        );
+        let id = self.next_id();
+        let next = self.timers.clock.now().checked_add(interval);
+        self.timers.periodic.push(Periodic {
+            id,
+            interval,
+            next,
+            catch_up: CatchUp::default(),
+            enabled: true,
+            f: Box::new(f),
+        });
+        id
+    }
+
+    /// Choisit comment le callback périodique `id` rattrape les intervalles écoulés entre deux
+    /// appels à `run_due`. Renvoie `false` si `id` n'est pas un callback périodique.
+    pub fn set_periodic_catch_up(&mut self, id: CallbackId, catch_up: CatchUp) -> bool {
+        match self.periodic_mut(id) {
+            Some(periodic) => {
+                periodic.catch_up = catch_up;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Renvoie le callback périodique `id`.
+    fn periodic_mut(&mut self, id: CallbackId) -> Option<&mut Periodic> {
+        self.timers
+            .periodic
+            .iter_mut()
+            .find(|periodic| periodic.id == id)
+    }
+
+    /// Retire le callback périodique `id` et indique s'il était enregistré.
+    pub(crate) fn remove_periodic(&mut self, id: CallbackId) -> bool {
+        let periodic = &mut self.timers.periodic;
+        let before = periodic.len();
+        periodic.retain(|periodic| periodic.id != id);
+        let removed = before != periodic.len();
+        if removed {
+            self.callbacks.ids().borrow_mut().release(id);
+        }
+        removed
+    }
+
+    /// Active ou désactive le callback périodique `id` et indique s'il est enregistré.
+    pub(crate) fn set_periodic_enabled(&mut self, id: CallbackId, enabled: bool) -> bool {
+        match self.periodic_mut(id) {
+            Some(periodic) => {
+                periodic.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Indique si `id` est un callback périodique activé.
+    pub(crate) fn is_periodic_enabled(&self, id: CallbackId) -> bool {
+        self.timers
+            .periodic
+            .iter()
+            .any(|periodic| periodic.id == id && periodic.enabled)
+    }
+
+    /// Renvoie l'heure du prochain appel d'un callback périodique.
+    pub(crate) fn next_periodic(&self) -> Option<Instant> {
+        self.timers
+            .periodic
+            .iter()
+            .filter_map(|periodic| periodic.next)
+            .min()
+    }
+
+    /// Appelle chaque callback périodique dont l'intervalle est écoulé à `now`, et renvoie le
+    /// bilan de ces appels.
+    pub(crate) fn run_periodic(&mut self, now: Instant) -> DispatchReport {
+        let start = Instant::now();
+        let mut report = DispatchReport::default();
+        let paused = self.paused;
+        for periodic in &mut self.timers.periodic {
+            let Some(next) = periodic.next.filter(|&next| next <= now) else {
+                continue;
+            };
+            let (behind, interval) = ((now - next).as_nanos(), periodic.interval.as_nanos());
+            let missed = behind / interval + 1;
+            // Le prochain appel reste aligné sur l'heure d'enregistrement.
+            let late = behind % interval;
+            let late = Duration::new((late / 1_000_000_000) as u64, (late % 1_000_000_000) as u32);
+            periodic.next = now.checked_add(periodic.interval - late);
+            if paused || !periodic.enabled {
+                report.skipped += 1;
+                continue;
+            }
+            let calls = match periodic.catch_up {
+                CatchUp::Once => 1,
+                CatchUp::EachMissed => {
+                    u32::try_from(missed).map_or(MAX_CATCH_UP, |missed| missed.min(MAX_CATCH_UP))
+                }
+            };
+            for _ in 0..calls {
+                (periodic.f)();
+            }
+            report.callbacks_invoked += calls as usize;
+        }
+        report.duration = start.elapsed();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use crate::data::CallbackPayload;
+    use crate::registry::{CallbackHost, OwnedRegistry};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Crée un registre sur `clock` avec un callback périodique d'une seconde qui compte ses
+    /// appels dans `calls`.
+    fn periodic_registry(
+        clock: &ManualClock,
+        catch_up: CatchUp,
+        calls: &Rc<Cell<u32>>,
+    ) -> (OwnedRegistry<CallbackPayload>, CallbackId) {
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        registry.set_timer_clock(clock.clone());
+        let calls = Rc::clone(calls);
+        let id = registry
+            .set_periodic_callback(Duration::from_secs(1), move || calls.set(calls.get() + 1));
+        assert!(registry.set_periodic_catch_up(id, catch_up));
+        (registry, id)
+    }
+
+    /// Teste le rattrapage de 3,5 intervalles écoulés, selon le mode choisi.
+    #[test]
+    fn test_catch_up_after_three_and_a_half_intervals() {
+        for (catch_up, expected) in [(CatchUp::Once, 1), (CatchUp::EachMissed, 3)] {
+            let (clock, calls) = (ManualClock::new(), Rc::new(Cell::new(0)));
+            let (mut registry, _) = periodic_registry(&clock, catch_up, &calls);
+
+            clock.advance(Duration::from_millis(3500));
+            let report = registry.run_due(clock.now());
+            assert_eq!(calls.get(), expected, "{:?}", catch_up);
+            assert_eq!(report.callbacks_invoked, expected as usize);
+            assert_eq!(registry.run_due(clock.now()).callbacks_invoked, 0);
+
+            // Le prochain appel reste aligné sur l'heure d'enregistrement : 4 s.
+            clock.advance(Duration::from_millis(500));
+            registry.run_due(clock.now());
+            assert_eq!(calls.get(), expected + 1);
+        }
+    }
+
+    /// Teste que la pause et la désactivation suspendent le callback sans rattrapage, et que
+    /// `remove_callback` le retire.
+    #[test]
+    fn test_pause_disable_and_remove() {
+        let (clock, calls) = (ManualClock::new(), Rc::new(Cell::new(0)));
+        let (mut registry, id) = periodic_registry(&clock, CatchUp::EachMissed, &calls);
+
+        registry.pause();
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(registry.run_due(clock.now()).skipped, 1);
+        registry.resume();
+        assert!(registry.disable_callback(id));
+        assert!(!registry.is_callback_enabled(id));
+        clock.advance(Duration::from_secs(1));
+        registry.run_due(clock.now());
+        assert_eq!(calls.get(), 0);
+
+        assert!(registry.enable_callback(id));
+        clock.advance(Duration::from_secs(1));
+        registry.run_due(clock.now());
+        assert_eq!(calls.get(), 1);
+
+        assert!(registry.remove_callback(id));
+        assert!(!registry.remove_callback(id));
+        clock.advance(Duration::from_secs(1));
+        registry.run_due(clock.now());
+        assert_eq!(calls.get(), 1);
+    }
+
+    /// Teste que le rattrapage d'un très long retard est borné, puis reprend normalement.
+    #[test]
+    fn test_catch_up_is_bounded() {
+        let (clock, calls) = (ManualClock::new(), Rc::new(Cell::new(0)));
+        let (mut registry, _) = periodic_registry(&clock, CatchUp::EachMissed, &calls);
+
+        clock.advance(Duration::from_secs(100_000));
+        assert_eq!(registry.run_due(clock.now()).callbacks_invoked, 64);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(registry.run_due(clock.now()).callbacks_invoked, 1);
+        assert_eq!(calls.get(), 65);
+    }
+
+    /// Teste qu'un intervalle trop long pour l'horloge est accepté sans jamais être appelé.
+    #[test]
+    fn test_unreachable_interval_never_fires() {
+        let clock = ManualClock::new();
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        registry.set_timer_clock(clock.clone());
+        registry.set_periodic_callback(Duration::MAX, || panic!("appel inattendu"));
+        assert_eq!(registry.next_due(), None);
+
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(registry.run_due(clock.now()).callbacks_invoked, 0);
+    }
+}
@@ -0,0 +1,228 @@
+//! Traitement des données par un [`DataProcessor`] une fois par appel, après les callbacks.
+
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{CallbackData, CallbackId};
+use crate::data::DataProcessor;
+use crate::error::ProcessError;
+
+/// Gestionnaire des erreurs de traitement, voir [`CallbackRegistry::set_on_process_error`].
+type ProcessErrorHandler = Box<dyn Fn(&ProcessError)>;
+
+/// Fréquence du traitement des données, voir [`CallbackRegistry::set_processing_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessingMode {
+    /// Traite les données une fois par appel, après tous les callbacks.
+    #[default]
+    PerDispatch,
+    /// Traite les données après chaque callback appelé, comme le faisaient les versions
+    /// précédentes avec `process_data`.
+    PerCallback,
+    /// Comme `PerDispatch`, mais traite une seule fois un lot de
+    /// [`dispatch_batch`](CallbackRegistry::dispatch_batch) : le traitement reçoit les données
+    /// transmises du lot mises bout à bout.
+    PerBatch,
+}
+
+/// Traitement des données d'un registre et gestionnaire de ses erreurs.
+#[derive(Default)]
+pub(crate) struct ProcessorSlot {
+    processor: Option<RefCell<Box<dyn DataProcessor>>>, // Le traitement, `None` s'il n'y en a pas.
+    on_error: Option<ProcessErrorHandler>,              // Reçoit les erreurs du traitement.
+    mode: ProcessingMode,                               // Fréquence du traitement.
+    batch: RefCell<Option<Vec<u8>>>, // Données du lot en cours, `None` hors lot ou lot par lot.
+}
+
+impl ProcessorSlot {
+    /// Traite `data`, et transmet l'éventuelle erreur au gestionnaire.
+    ///
+    /// # Panics
+    ///
+    /// Panique si le traitement déclenche lui-même un appel du registre.
+    pub(crate) fn process(&self, data: &[u8]) {
+        let Some(processor) = &self.processor else {
+            return;
+        };
+        let result = processor.borrow_mut().process(data);
+        if let (Err(error), Some(on_error)) = (result, &self.on_error) {
+            on_error(&error);
+        }
+    }
+
+    /// Indique si les données sont traitées après chaque callback.
+    pub(crate) fn per_callback(&self) -> bool {
+        self.mode == ProcessingMode::PerCallback
+    }
+
+    /// Traite `data` après tous les callbacks d'un appel, ou la met de côté jusqu'à la fin du lot.
+    fn process_dispatched(&self, data: &[u8]) {
+        match self.batch.borrow_mut().as_mut() {
+            Some(batch) => batch.extend_from_slice(data),
+            None => self.process(data),
+        }
+    }
+
+    /// Commence un lot : en mode [`ProcessingMode::PerBatch`], les données transmises sont mises
+    /// de côté au lieu d'être traitées.
+    pub(crate) fn begin_batch(&self) {
+        if self.mode == ProcessingMode::PerBatch && self.processor.is_some() {
+            self.batch.replace(Some(Vec::new()));
+        }
+    }
+
+    /// Termine un lot et traite les données mises de côté, s'il y en a.
+    pub(crate) fn end_batch(&self) {
+        if let Some(batch) = self.batch.take() {
+            self.process(&batch);
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Remplace le traitement des données, appelé une fois par appel à `do_something` après tous
+    /// les callbacks, avec les données qu'ils ont reçues. Par défaut, aucun traitement n'est fait ;
+    /// [`PrintProcessor`](crate::PrintProcessor) affiche les données.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, PrintProcessor};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(|_data: &CallbackPayload| {});
+    /// registry.set_processor(Box::new(PrintProcessor));
+    /// registry.do_something(); // Affiche « Processing data: [1, 2, 3] ».
+    /// ```
+    pub fn set_processor(&mut self, processor: Box<dyn DataProcessor>) {
+        self.processor.processor = Some(RefCell::new(processor));
+    }
+
+    /// Retire le traitement des données.
+    pub fn clear_processor(&mut self) {
+        self.processor.processor = None;
+    }
+
+    /// Appellera `f` avec chaque erreur du traitement des données ; sans gestionnaire, elles sont
+    /// ignorées.
+    pub fn set_on_process_error(&mut self, f: impl Fn(&ProcessError) + 'static) {
+        self.processor.on_error = Some(Box::new(f));
+    }
+
+    /// Choisit la fréquence du traitement des données : une fois par appel par défaut, ou après
+    /// chaque callback avec [`ProcessingMode::PerCallback`].
+    pub fn set_processing_mode(&mut self, mode: ProcessingMode) {
+        self.processor.mode = mode;
+    }
+
+    /// Appelle les callbacks comme `dispatch_payload` puis traite `bytes`, les bytes de `payload`,
+    /// selon la fréquence choisie.
+    pub(crate) fn dispatch_processed<V>(
+        &self,
+        payload: &T,
+        bytes: &[u8],
+        select: impl Fn(&Entry<T, R>) -> bool,
+        invoke: impl Fn(&Entry<T, R>, &T) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+    ) {
+        if !self.processor.per_callback() {
+            self.dispatch_payload(payload, select, invoke, sink);
+            self.processor.process_dispatched(bytes);
+            return;
+        }
+        let invoke = |entry: &Entry<T, R>, payload: &T| {
+            let result = invoke(entry, payload);
+            if result.is_some() {
+                self.processor.process(bytes);
+            }
+            result
+        };
+        self.dispatch_payload(payload, select, invoke, sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Teste que le traitement a lieu une fois par appel et non une fois par callback.
+    #[test]
+    fn test_processor_runs_once_per_dispatch() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1, 2]);
+        for _ in 0..3 {
+            registry.set_callback(|_data: &CallbackPayload| {});
+        }
+        let seen_in_proc = Rc::clone(&seen);
+        registry.set_processor(Box::new(move |data: &[u8]| -> Result<(), ProcessError> {
+            seen_in_proc.borrow_mut().push(data.to_vec());
+            Ok(())
+        }));
+
+        registry.do_something();
+        registry.set_data(vec![3]);
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![3]]);
+
+        registry.clear_processor();
+        registry.do_something();
+        assert_eq!(seen.borrow().len(), 2);
+    }
+
+    /// Teste que les erreurs du traitement sont transmises au gestionnaire.
+    #[test]
+    fn test_process_errors_reach_handler() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_data(&[][..]);
+        registry.set_processor(Box::new(|data: &[u8]| {
+            if data.is_empty() {
+                return Err(ProcessError::new("données vides"));
+            }
+            Ok(())
+        }));
+        let errors_in_handler = Rc::clone(&errors);
+        registry.set_on_process_error(move |error: &ProcessError| {
+            errors_in_handler.borrow_mut().push(error.clone())
+        });
+
+        registry.do_something();
+
+        assert_eq!(*errors.borrow(), vec![ProcessError::new("données vides")]);
+    }
+
+    /// Teste que le mode `PerCallback` traite les données après chaque callback appelé.
+    #[test]
+    fn test_per_callback_mode() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_data(&[4u8][..]);
+        for _ in 0..3 {
+            registry.set_callback(|_data: &CallbackPayload| {});
+        }
+        registry.set_callback_filtered(
+            |_data: &CallbackPayload| false,
+            Callback::new(|_data: &CallbackPayload| {}),
+        );
+        let seen_in_proc = Rc::clone(&seen);
+        registry.set_processor(Box::new(move |data: &[u8]| -> Result<(), ProcessError> {
+            seen_in_proc.borrow_mut().push(data.to_vec());
+            Ok(())
+        }));
+        registry.set_processing_mode(ProcessingMode::PerCallback);
+
+        registry.do_something();
+        assert_eq!(seen.borrow().len(), 3);
+
+        registry.set_processing_mode(ProcessingMode::PerDispatch);
+        registry.do_something();
+        assert_eq!(seen.borrow().len(), 4);
+    }
+}
@@ -0,0 +1,117 @@
+//! Arrêt de la propagation : un callback peut empêcher les suivants de recevoir les données.
+
+use std::ops::ControlFlow;
+
+use super::CallbackRegistry;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+impl<'a, D: AsRef<[u8]> + ?Sized> CallbackRegistry<'a, CallbackPayload, D, ControlFlow<()>> {
+    /// Appelle les callbacks dans l'ordre jusqu'au premier qui renvoie `ControlFlow::Break`,
+    /// comme `stopPropagation` dans le DOM ; les callbacks suivants ne sont pas appelés.
+    ///
+    /// Renvoie `true` si la propagation a été arrêtée. Un callback sans valeur de retour
+    /// s'adapte avec [`Callback::continuing`](crate::Callback::continuing).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[b'q'][..]);
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| {
+    ///     if data.as_bytes() == b"q" {
+    ///         return ControlFlow::Break(()); // Touche consommée.
+    ///     }
+    ///     ControlFlow::Continue(())
+    /// }));
+    /// registry.set_callback(Callback::new(|_data: &CallbackPayload| println!("Jamais appelé")).continuing());
+    /// assert!(registry.dispatch_until_break());
+    /// ```
+    pub fn dispatch_until_break(&self) -> bool {
+        let mut stopped = false;
+        self.dispatch_where(
+            |_| true,
+            |_, flow| {
+                stopped = flow.is_break();
+                flow
+            },
+        );
+        stopped
+    }
+}
+
+impl<'a> CallbackRegistry<'a, ArcCallbackPayload, [u8], ControlFlow<()>> {
+    /// Appelle les callbacks dans l'ordre jusqu'au premier qui renvoie `ControlFlow::Break`.
+    ///
+    /// Renvoie `true` si la propagation a été arrêtée.
+    pub fn dispatch_until_break(&self) -> bool {
+        let mut stopped = false;
+        self.dispatch_where(
+            |_| true,
+            |_, flow| {
+                stopped = flow.is_break();
+                flow
+            },
+        );
+        stopped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Enregistre un callback qui ajoute `label` à `calls` puis renvoie `flow`.
+    fn push_flow(
+        registry: &mut CallbackRegistry<'_, CallbackPayload, [u8], ControlFlow<()>>,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        label: &'static str,
+        flow: ControlFlow<()>,
+    ) {
+        let calls = Rc::clone(calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls.borrow_mut().push(label);
+            flow
+        }));
+    }
+
+    /// Teste que les callbacks enregistrés après celui qui renvoie `Break` ne sont jamais appelés.
+    #[test]
+    fn test_break_stops_later_callbacks() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_flow(&mut registry, &calls, "first", ControlFlow::Continue(()));
+        push_flow(&mut registry, &calls, "consumer", ControlFlow::Break(()));
+        push_flow(&mut registry, &calls, "late", ControlFlow::Continue(()));
+
+        assert!(registry.dispatch_until_break());
+        assert!(registry.dispatch_until_break());
+
+        assert_eq!(
+            *calls.borrow(),
+            vec!["first", "consumer", "first", "consumer"]
+        );
+    }
+
+    /// Teste que des callbacks adaptés par `continuing` laissent la propagation aller à son terme.
+    #[test]
+    fn test_continuing_callbacks_do_not_stop() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        for label in ["a", "b"] {
+            let calls = Rc::clone(&calls);
+            registry.set_callback(
+                Callback::new(move |_data: &ArcCallbackPayload| calls.borrow_mut().push(label))
+                    .continuing(),
+            );
+        }
+
+        assert!(!registry.dispatch_until_break());
+        assert_eq!(*calls.borrow(), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Mise en quarantaine des callbacks qui échouent de façon répétée (disjoncteur).
+
+use std::any::Any;
+
+use super::CallbackRegistry;
+use crate::callback::{CallbackData, CallbackId};
+use crate::error::CallbackError;
+
+/// Cause de la mise en quarantaine d'un callback : son dernier échec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureReason {
+    /// Le callback a renvoyé une erreur, voir [`CallbackRegistry::try_do_something`].
+    Error(CallbackError),
+    /// Le callback a paniqué avec ce message, voir [`CallbackRegistry::dispatch_isolated`].
+    Panic(String),
+}
+
+impl FailureReason {
+    /// Crée la cause correspondant à la valeur d'une panique interceptée.
+    pub(crate) fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<panique sans message>".to_string());
+        FailureReason::Panic(message)
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Met en quarantaine tout callback qui échoue `threshold` fois de suite ; 0 désactive la quarantaine.
+    ///
+    /// Seuls les échecs observés par `try_do_something`, `try_do_something_fail_fast` et
+    /// `dispatch_isolated` sont comptés ; un appel réussi remet le compte à zéro. Un callback en
+    /// quarantaine n'est plus appelé jusqu'à [`reinstate`](Self::reinstate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+    /// registry.set_failure_threshold(2);
+    /// let plugin = registry.set_callback(Callback::new(|_data: &CallbackPayload| panic!("greffon défectueux")));
+    /// registry.dispatch_isolated();
+    /// registry.dispatch_isolated();
+    /// assert_eq!(registry.quarantined_callbacks().len(), 1);
+    /// assert!(registry.dispatch_isolated().is_empty()); // Le greffon n'est plus appelé.
+    /// assert!(registry.reinstate(plugin));
+    /// ```
+    pub fn set_failure_threshold(&mut self, threshold: u32) {
+        self.failure_threshold = (threshold > 0).then_some(threshold);
+    }
+
+    /// Renvoie les callbacks en quarantaine avec la cause de leur dernier échec, dans l'ordre d'appel.
+    pub fn quarantined_callbacks(&self) -> Vec<(CallbackId, FailureReason)> {
+        self.live_entries()
+            .filter_map(|entry| Some((entry.id, entry.quarantined.borrow().clone()?)))
+            .collect()
+    }
+
+    /// Sort le callback `id` de quarantaine et oublie ses échecs passés.
+    ///
+    /// Renvoie `false` si `id` est inconnu ou n'était pas en quarantaine.
+    pub fn reinstate(&mut self, id: CallbackId) -> bool {
+        match self.entry_mut(id) {
+            Some(entry) if entry.is_quarantined() => {
+                entry.reinstate();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    type Outcome = Result<(), CallbackError>;
+
+    /// Crée un callback faillible qui compte ses appels dans `count` et échoue toujours si `fails`.
+    fn tracked(count: &Rc<Cell<usize>>, fails: bool) -> Callback<CallbackPayload, Outcome> {
+        let count = Rc::clone(count);
+        Callback::new(move |_data: &CallbackPayload| {
+            count.set(count.get() + 1);
+            if fails {
+                Err(CallbackError::new("socket fermée"))
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// Teste qu'un callback en échec trois fois de suite n'est plus appelé au quatrième appel.
+    #[test]
+    fn test_callback_quarantined_after_three_failures() {
+        let failing_calls = Rc::new(Cell::new(0));
+        let healthy_calls = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_failure_threshold(3);
+        let failing = registry.set_callback(tracked(&failing_calls, true));
+        registry.set_callback(tracked(&healthy_calls, false));
+
+        for _ in 0..3 {
+            assert!(registry.try_do_something().is_err());
+        }
+        assert_eq!(registry.try_do_something(), Ok(()));
+
+        assert_eq!(failing_calls.get(), 3);
+        assert_eq!(healthy_calls.get(), 4);
+        assert_eq!(
+            registry.quarantined_callbacks(),
+            vec![(
+                failing,
+                FailureReason::Error(CallbackError::new("socket fermée"))
+            )]
+        );
+    }
+
+    /// Teste qu'un callback réintégré est de nouveau appelé et que son compte d'échecs repart de zéro.
+    #[test]
+    fn test_reinstate_resumes_calls() {
+        let calls = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_failure_threshold(1);
+        let id = registry.set_callback(tracked(&calls, true));
+
+        let _ = registry.try_do_something_fail_fast();
+        let _ = registry.try_do_something_fail_fast();
+        assert_eq!(calls.get(), 1);
+
+        assert!(registry.reinstate(id));
+        assert!(!registry.reinstate(id));
+        let _ = registry.try_do_something_fail_fast();
+        assert_eq!(calls.get(), 2);
+    }
+
+    /// Teste qu'une panique interceptée met le callback en quarantaine avec son message.
+    #[test]
+    fn test_panic_quarantines_with_message() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_failure_threshold(1);
+        let id = registry.set_callback(Callback::new(|_data: &CallbackPayload| {
+            panic!("greffon {}", "défectueux")
+        }));
+
+        registry.dispatch_isolated();
+
+        assert_eq!(
+            registry.quarantined_callbacks(),
+            vec![(id, FailureReason::Panic("greffon défectueux".to_string()))]
+        );
+    }
+
+    /// Teste que sans seuil, les échecs répétés ne mettent aucun callback en quarantaine.
+    #[test]
+    fn test_no_threshold_never_quarantines() {
+        let calls = Rc::new(Cell::new(0));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(tracked(&calls, true));
+
+        for _ in 0..5 {
+            let _ = registry.try_do_something();
+        }
+
+        assert_eq!(calls.get(), 5);
+        assert!(registry.quarantined_callbacks().is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+//! File d'attente des données : les mettre de côté, puis les transmettre toutes d'un coup.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use super::{CallbackRegistry, DispatchReport};
+use crate::callback::CallbackData;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+use crate::error::QueueFull;
+
+/// Que faire d'une donnée mise en file quand la file est pleine, voir
+/// [`CallbackRegistry::set_queue_capacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// `enqueue` refuse la donnée et la renvoie dans son erreur.
+    #[default]
+    Error,
+    /// La donnée la plus ancienne de la file est oubliée pour faire de la place.
+    DropOldest,
+}
+
+/// Données mises en file par `enqueue`, en attente de `flush`.
+#[derive(Debug, Default)]
+pub(crate) struct DispatchQueue {
+    payloads: RefCell<VecDeque<Vec<u8>>>, // Données en attente, de la plus ancienne à la plus récente.
+    capacity: Option<usize>, // Nombre maximal de données en attente, `None` si illimité.
+    policy: OverflowPolicy,  // Que faire d'une donnée quand la file est pleine.
+}
+
+impl DispatchQueue {
+    /// Met `payload` en file selon la politique de la file, y compris pendant un appel des
+    /// callbacks.
+    pub(crate) fn push(&self, payload: Vec<u8>) -> Result<(), QueueFull> {
+        let mut payloads = self.payloads.borrow_mut();
+        match self.capacity {
+            Some(capacity) if payloads.len() >= capacity => match self.policy {
+                OverflowPolicy::Error => return Err(QueueFull { payload, capacity }),
+                OverflowPolicy::DropOldest => {
+                    if payloads.pop_front().is_none() {
+                        // Une file de capacité nulle ne garde rien.
+                        return Ok(());
+                    }
+                }
+            },
+            _ => {}
+        }
+        payloads.push_back(payload);
+        Ok(())
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Met `payload` en file, sans appeler aucun callback ; [`flush`](Self::flush) la
+    /// transmettra plus tard.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`QueueFull`], avec `payload`, si la file est pleine et que sa politique est
+    /// [`OverflowPolicy::Error`].
+    pub fn enqueue(&mut self, payload: Vec<u8>) -> Result<(), QueueFull> {
+        self.queue.push(payload)
+    }
+
+    /// Limite la file à `capacity` données, en appliquant `policy` aux données de trop. Les
+    /// données déjà en file au-delà de la nouvelle limite sont conservées.
+    pub fn set_queue_capacity(&mut self, capacity: usize, policy: OverflowPolicy) {
+        self.queue.capacity = Some(capacity);
+        self.queue.policy = policy;
+    }
+
+    /// Retire la limite de la file.
+    pub fn clear_queue_capacity(&mut self) {
+        self.queue.capacity = None;
+    }
+
+    /// Renvoie le nombre de données en file.
+    pub fn queued_len(&self) -> usize {
+        self.queue.payloads.borrow().len()
+    }
+
+    /// Vide la file et transmet chaque donnée à `dispatch`, de la plus ancienne à la plus
+    /// récente, en cumulant les bilans.
+    fn flush_with(
+        &mut self,
+        dispatch: impl Fn(&Self, Vec<u8>) -> DispatchReport,
+    ) -> DispatchReport {
+        let payloads = std::mem::take(self.queue.payloads.get_mut());
+        let mut report = DispatchReport::default();
+        for payload in payloads {
+            report += dispatch(self, payload);
+        }
+        report
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Transmet toutes les données en file, de la plus ancienne à la plus récente, comme
+    /// [`dispatch_report`](Self::dispatch_report), puis vide la file. Renvoie le cumul des bilans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    /// registry.enqueue(vec![1, 2]).unwrap();
+    /// registry.enqueue(vec![3]).unwrap();
+    /// assert_eq!(registry.flush().callbacks_invoked, 2);
+    /// assert_eq!(registry.queued_len(), 0);
+    /// ```
+    pub fn flush(&mut self) -> DispatchReport {
+        self.flush_with(|registry, payload| registry.report_bytes(&payload))
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Transmet toutes les données en file, de la plus ancienne à la plus récente, comme
+    /// [`dispatch_report`](Self::dispatch_report), puis vide la file. Renvoie le cumul des bilans.
+    pub fn flush(&mut self) -> DispatchReport {
+        self.flush_with(|registry, payload| registry.report_shared(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    /// Teste que rien n'est appelé avant `flush`, puis que les trois données sont transmises
+    /// dans l'ordre.
+    #[test]
+    fn test_enqueue_three_then_flush() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        for value in 1..=3 {
+            registry.enqueue(vec![value]).unwrap();
+        }
+        assert!(seen.borrow().is_empty());
+        assert_eq!(registry.queued_len(), 3);
+
+        let report = registry.flush();
+        assert_eq!(report.callbacks_invoked, 3);
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+        assert_eq!(registry.queued_len(), 0);
+        let empty = registry.flush();
+        assert_eq!((empty.callbacks_invoked, empty.skipped), (0, 0));
+    }
+
+    /// Teste les deux politiques d'une file pleine.
+    #[test]
+    fn test_overflow_policies() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        registry.set_queue_capacity(2, OverflowPolicy::Error);
+        registry.enqueue(vec![1]).unwrap();
+        registry.enqueue(vec![2]).unwrap();
+        assert_eq!(
+            registry.enqueue(vec![3]),
+            Err(QueueFull {
+                payload: vec![3],
+                capacity: 2
+            })
+        );
+
+        registry.set_queue_capacity(2, OverflowPolicy::DropOldest);
+        registry.enqueue(vec![4]).unwrap();
+        registry.flush();
+        assert_eq!(*seen.borrow(), vec![2, 4]);
+    }
+
+    /// Teste la file d'un registre de données partagées.
+    #[test]
+    fn test_flush_shared_payloads() {
+        let seen: Rc<RefCell<Vec<Arc<[u8]>>>> = Rc::default();
+        let mut registry: CallbackRegistry<'static, ArcCallbackPayload> = CallbackRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &ArcCallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.to_arc())
+        }));
+        registry.enqueue(vec![5, 6]).unwrap();
+        assert_eq!(registry.flush().callbacks_invoked, 1);
+        assert_eq!(&seen.borrow()[0][..], &[5, 6]);
+    }
+}
@@ -0,0 +1,241 @@
+//! Limitation du débit des appels par seau à jetons.
+
+use std::cell::Cell;
+use std::fmt;
+use std::time::Instant;
+
+use super::CallbackRegistry;
+use crate::callback::CallbackData;
+use crate::clock::{Clock, SystemClock};
+
+/// Que faire des données qui dépassent la limite de débit, voir
+/// [`CallbackRegistry::set_rate_limit_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitPolicy {
+    /// Les données sont oubliées et comptées par [`rate_limited`](CallbackRegistry::rate_limited).
+    #[default]
+    Drop,
+    /// Les données sont mises en file, comme par [`enqueue`](CallbackRegistry::enqueue), et
+    /// transmises par le prochain `flush` s'il reste alors des jetons ; une donnée refusée par
+    /// la file est comptée comme oubliée.
+    Queue,
+}
+
+/// Seau à jetons : chaque appel consomme un jeton, et les jetons se reconstituent au rythme
+/// de `rate` par seconde, jusqu'à `rate + burst`.
+pub(crate) struct RateLimiter {
+    rate: u32,                          // Jetons reconstitués par seconde.
+    burst: u32,                         // Jetons tolérés en plus de `rate`, pour les rafales.
+    tokens: Cell<f64>,                  // Jetons disponibles lors du dernier appel.
+    last: Cell<Instant>,                // Heure du dernier appel, selon `clock`.
+    clock: Box<dyn Clock>,              // Source de l'heure.
+    pub(crate) policy: RateLimitPolicy, // Que faire des données de trop.
+    dropped: Cell<u64>,                 // Données oubliées depuis la mise en place de la limite.
+}
+
+impl RateLimiter {
+    /// Crée un seau plein de `rate + burst` jetons, qui lit l'heure sur `clock`.
+    fn new(rate: u32, burst: u32, clock: Box<dyn Clock>) -> Self {
+        RateLimiter {
+            rate,
+            burst,
+            tokens: Cell::new(f64::from(rate) + f64::from(burst)),
+            last: Cell::new(clock.now()),
+            clock,
+            policy: RateLimitPolicy::default(),
+            dropped: Cell::new(0),
+        }
+    }
+
+    /// Reconstitue les jetons depuis le dernier appel, puis consomme un jeton s'il y en a un.
+    fn try_acquire(&self) -> bool {
+        let now = self.clock.now();
+        let elapsed = now.saturating_duration_since(self.last.get());
+        self.last.set(now);
+        let capacity = f64::from(self.rate) + f64::from(self.burst);
+        let tokens =
+            (self.tokens.get() + elapsed.as_secs_f64() * f64::from(self.rate)).min(capacity);
+        if tokens >= 1.0 {
+            self.tokens.set(tokens - 1.0);
+            true
+        } else {
+            self.tokens.set(tokens);
+            false
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("rate", &self.rate)
+            .field("burst", &self.burst)
+            .field("tokens", &self.tokens.get())
+            .field("policy", &self.policy)
+            .field("dropped", &self.dropped.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Limite les appels des callbacks à `events_per_sec` par seconde, avec `burst` appels
+    /// supplémentaires tolérés en rafale.
+    ///
+    /// Chaque appel consomme un jeton d'un seau qui en contient au plus
+    /// `events_per_sec + burst`, plein au départ, et qui se remplit de `events_per_sec` jetons
+    /// par seconde. Sans jeton, l'appel est traité selon
+    /// [`set_rate_limit_policy`](Self::set_rate_limit_policy). Remplace une limite précédente,
+    /// en conservant sa politique et son horloge.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `events_per_sec` vaut 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    /// registry.set_rate_limit(2, 0);
+    /// for value in 0..5 {
+    ///     registry.dispatch(&[value]).unwrap();
+    /// }
+    /// assert_eq!(registry.rate_limited(), 3);
+    /// ```
+    pub fn set_rate_limit(&mut self, events_per_sec: u32, burst: u32) {
+        assert!(events_per_sec > 0, "le débit maximal doit être au moins 1");
+        let (clock, policy): (Box<dyn Clock>, _) = match self.rate_limit.take() {
+            Some(limiter) => (limiter.clock, limiter.policy),
+            None => (Box::new(SystemClock), RateLimitPolicy::default()),
+        };
+        let mut limiter = RateLimiter::new(events_per_sec, burst, clock);
+        limiter.policy = policy;
+        self.rate_limit = Some(limiter);
+    }
+
+    /// Choisit le sort des données qui dépassent la limite de débit.
+    ///
+    /// # Panics
+    ///
+    /// Panique si aucune limite n'a été fixée par [`set_rate_limit`](Self::set_rate_limit).
+    pub fn set_rate_limit_policy(&mut self, policy: RateLimitPolicy) {
+        self.limiter_mut().policy = policy;
+    }
+
+    /// Lit l'heure de la limite de débit sur `clock` plutôt que sur l'horloge du système ; le
+    /// seau est de nouveau rempli.
+    ///
+    /// # Panics
+    ///
+    /// Panique si aucune limite n'a été fixée par [`set_rate_limit`](Self::set_rate_limit).
+    pub fn set_rate_limit_clock(&mut self, clock: impl Clock + 'static) {
+        let limiter = self.limiter_mut();
+        let mut replaced = RateLimiter::new(limiter.rate, limiter.burst, Box::new(clock));
+        replaced.policy = limiter.policy;
+        replaced.dropped = Cell::new(limiter.dropped.get());
+        *limiter = replaced;
+    }
+
+    /// Retire la limite de débit.
+    pub fn clear_rate_limit(&mut self) {
+        self.rate_limit = None;
+    }
+
+    /// Renvoie le nombre de données oubliées parce qu'elles dépassaient la limite de débit.
+    pub fn rate_limited(&self) -> u64 {
+        self.rate_limit
+            .as_ref()
+            .map_or(0, |limiter| limiter.dropped.get())
+    }
+
+    /// Renvoie la limite de débit, qui doit avoir été fixée.
+    fn limiter_mut(&mut self) -> &mut RateLimiter {
+        self.rate_limit
+            .as_mut()
+            .expect("aucune limite de débit : appelez `set_rate_limit`")
+    }
+
+    /// Indique si un appel avec `raw` respecte la limite de débit ; sinon, `raw` est oublié ou
+    /// mis en file selon la politique.
+    pub(crate) fn admit_rate(&self, raw: &[u8]) -> bool {
+        let Some(limiter) = &self.rate_limit else {
+            return true;
+        };
+        if limiter.try_acquire() {
+            return true;
+        }
+        let kept =
+            limiter.policy == RateLimitPolicy::Queue && self.queue.push(raw.to_vec()).is_ok();
+        if !kept {
+            limiter.dropped.set(limiter.dropped.get() + 1);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::clock::ManualClock;
+    use crate::data::CallbackPayload;
+    use crate::registry::{CallbackHost, OwnedRegistry};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    /// Teste qu'avec 2 appels par seconde, 5 appels rapprochés en laissent passer 2 plus la
+    /// rafale, et que les jetons se reconstituent avec le temps.
+    #[test]
+    fn test_two_per_second_drops_the_rest() {
+        for burst in [0, 1] {
+            let clock = ManualClock::new();
+            let seen = Rc::new(RefCell::new(Vec::new()));
+            let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+            let seen_in_cb = Rc::clone(&seen);
+            registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+                seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+            }));
+            registry.set_rate_limit(2, burst);
+            registry.set_rate_limit_clock(clock.clone());
+            for value in 0..5 {
+                registry.dispatch(&[value]).unwrap();
+            }
+            let passed = 2 + burst as usize;
+            assert_eq!(seen.borrow().len(), passed);
+            assert_eq!(registry.rate_limited(), 5 - passed as u64);
+
+            clock.advance(Duration::from_millis(500));
+            registry.dispatch(&[9]).unwrap();
+            assert_eq!(seen.borrow().last(), Some(&9));
+        }
+    }
+
+    /// Teste que les données de trop mises en file sont transmises par `flush` une fois les
+    /// jetons reconstitués.
+    #[test]
+    fn test_queue_policy_defers_to_flush() {
+        let clock = ManualClock::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        registry.set_rate_limit(2, 0);
+        registry.set_rate_limit_clock(clock.clone());
+        registry.set_rate_limit_policy(RateLimitPolicy::Queue);
+        for value in 0..5 {
+            registry.dispatch(&[value]).unwrap();
+        }
+        assert_eq!(*seen.borrow(), vec![0, 1]);
+        assert_eq!((registry.queued_len(), registry.rate_limited()), (3, 0));
+
+        clock.advance(Duration::from_secs(1));
+        registry.flush();
+        assert_eq!(*seen.borrow(), vec![0, 1, 2, 3]);
+        assert_eq!(registry.queued_len(), 1);
+    }
+}
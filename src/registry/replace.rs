@@ -0,0 +1,134 @@
+//! Remplacement à chaud de la closure d'un callback enregistré.
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::error::UnknownId;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Remplace la closure du callback `id` par celle de `new_cb` et renvoie l'ancien callback.
+    ///
+    /// Le callback garde son identifiant, sa place dans l'ordre d'appel, sa priorité (celle de
+    /// `new_cb` est ignorée), son nom, son groupe et son état d'activation.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`UnknownId`] si aucun callback n'est enregistré sous `id` ; `new_cb` est alors détruit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let id = registry.set_callback(Callback::new(|_data: &CallbackPayload| println!("v1")));
+    /// let old = registry
+    ///     .replace_callback(id, Callback::new(|_data: &CallbackPayload| println!("v2")))
+    ///     .unwrap();
+    /// drop(old);
+    /// registry.do_something(); // Affiche « v2 ».
+    /// ```
+    pub fn replace_callback(
+        &mut self,
+        id: CallbackId,
+        mut new_cb: Callback<T, R>,
+    ) -> Result<Callback<T, R>, UnknownId> {
+        let entry = self.entry_mut(id).ok_or(UnknownId(id))?;
+        new_cb.set_priority(entry.callback.priority());
+        Ok(std::mem::replace(&mut entry.callback, new_cb))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste que le premier appel utilise l'ancienne closure et le second la nouvelle, à la même place.
+    #[test]
+    fn test_replace_between_dispatches() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("first")
+        }));
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry.set_callback_in_group(
+            "net",
+            Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("old")),
+        );
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("last")
+        }));
+
+        registry.do_something();
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .replace_callback(
+                id,
+                Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("new")),
+            )
+            .unwrap();
+        registry.do_something();
+
+        assert_eq!(
+            *calls.borrow(),
+            vec!["first", "old", "last", "first", "new", "last"]
+        );
+        assert_eq!(registry.remove_group("net"), 1);
+    }
+
+    /// Teste que le callback remplacé garde sa priorité et que l'ancien callback est renvoyé intact.
+    #[test]
+    fn test_replace_keeps_priority_and_returns_old() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry.set_callback(Callback::with_priority(
+            10,
+            move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("cleanup"),
+        ));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("default")
+        }));
+
+        let calls_in_cb = Rc::clone(&calls);
+        let old = registry
+            .replace_callback(
+                id,
+                Callback::new(move |_data: &CallbackPayload| {
+                    calls_in_cb.borrow_mut().push("new cleanup")
+                }),
+            )
+            .unwrap();
+        registry.do_something();
+        old.invoke(CallbackPayload::new(&[]));
+
+        assert_eq!(*calls.borrow(), vec!["default", "new cleanup", "cleanup"]);
+    }
+
+    /// Teste qu'un identifiant inconnu est signalé par `UnknownId`.
+    #[test]
+    fn test_replace_unknown_id() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry.set_callback(Callback::new(move |_data: &CallbackPayload| {
+            calls_in_cb.borrow_mut().push("a")
+        }));
+        registry.remove_callback(id);
+
+        let calls_in_cb = Rc::clone(&calls);
+        let result = registry.replace_callback(
+            id,
+            Callback::new(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b")),
+        );
+
+        assert_eq!(result.err().map(|UnknownId(id)| id), Some(id));
+    }
+}
@@ -0,0 +1,186 @@
+//! Bilan d'un appel des callbacks : combien ont été appelés, écartés ou ont échoué.
+
+use std::cell::Cell;
+use std::ops::{Add, AddAssign};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::isolated::invoke_isolated;
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::callback::CallbackData;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Bilan d'un ou plusieurs appels à [`CallbackRegistry::dispatch_report`].
+///
+/// Les bilans s'additionnent avec `+` et `+=` pour cumuler plusieurs appels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DispatchReport {
+    pub callbacks_invoked: usize, // Callbacks appelés, y compris ceux qui ont paniqué.
+    pub skipped: usize,           // Callbacks enregistrés mais pas appelés.
+    pub duration: Duration,       // Durée totale des appels.
+    pub errors: usize,            // Callbacks qui ont paniqué.
+}
+
+impl AddAssign for DispatchReport {
+    fn add_assign(&mut self, other: Self) {
+        self.callbacks_invoked += other.callbacks_invoked;
+        self.skipped += other.skipped;
+        self.duration += other.duration;
+        self.errors += other.errors;
+    }
+}
+
+impl Add for DispatchReport {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Mesure l'appel des callbacks par `dispatch`, à qui il fournit la fonction d'appel des
+    /// entrées, qui isole les paniques et compte les callbacks appelés.
+    pub(crate) fn measure_dispatch(
+        &self,
+        dispatch: impl FnOnce(&dyn Fn(&Entry<T, R>, &T) -> Option<Result<R, ()>>),
+    ) -> DispatchReport {
+        self.measure_dispatches(1, dispatch)
+    }
+
+    /// Comme `measure_dispatch`, pour `dispatches` appels des callbacks par `dispatch`.
+    pub(crate) fn measure_dispatches(
+        &self,
+        dispatches: usize,
+        dispatch: impl FnOnce(&dyn Fn(&Entry<T, R>, &T) -> Option<Result<R, ()>>),
+    ) -> DispatchReport {
+        let start = Instant::now();
+        let registered = self.live_entries().count() * dispatches;
+        let (invoked, errors) = (Cell::new(0), Cell::new(0));
+        let threshold = self.failure_threshold;
+        dispatch(&|entry, data| {
+            let outcome = invoke_isolated(entry, data, threshold)?;
+            invoked.set(invoked.get() + 1);
+            if outcome.is_err() {
+                errors.set(errors.get() + 1);
+            }
+            Some(outcome.map_err(drop))
+        });
+        DispatchReport {
+            callbacks_invoked: invoked.get(),
+            skipped: registered.saturating_sub(invoked.get()),
+            duration: start.elapsed(),
+            errors: errors.get(),
+        }
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Comme [`dispatch_isolated`](Self::dispatch_isolated), mais renvoie le bilan de l'appel.
+    ///
+    /// Les callbacks désactivés, en quarantaine, refusés par leur prédicat ou écartés par
+    /// l'échantillonnage comptent parmi les callbacks écartés, comme tous les callbacks quand
+    /// l'appel entier est ignoré (pause, validation, données inchangées).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry, DispatchReport};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(Callback::new(|_data: &CallbackPayload| panic!("greffon défectueux")));
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("Data: {:?}", data)));
+    ///
+    /// let mut total = DispatchReport::default();
+    /// total += registry.dispatch_report();
+    /// total += registry.dispatch_report();
+    /// assert_eq!((total.callbacks_invoked, total.errors), (4, 2));
+    /// ```
+    pub fn dispatch_report(&self) -> DispatchReport {
+        self.measure_dispatch(|invoke| self.dispatch_with(|_| true, invoke, ignore_result))
+    }
+
+    /// Comme `dispatch_report`, mais avec `payload` à la place des données du registre.
+    pub(crate) fn report_bytes(&self, payload: &[u8]) -> DispatchReport {
+        self.measure_dispatch(|invoke| {
+            if let Err(error) = self.dispatch_bytes(payload, |_| true, invoke, ignore_result, false)
+            {
+                self.report_invalid(error);
+            }
+        })
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Comme [`dispatch_isolated`](Self::dispatch_isolated), mais renvoie le bilan de l'appel.
+    pub fn dispatch_report(&self) -> DispatchReport {
+        self.measure_dispatch(|invoke| self.dispatch_with(|_| true, invoke, ignore_result))
+    }
+
+    /// Comme `dispatch_report`, mais avec `payload` à la place des données du registre.
+    pub(crate) fn report_shared(&self, payload: Vec<u8>) -> DispatchReport {
+        let shared: Arc<[u8]> = Arc::from(payload);
+        self.measure_dispatch(|invoke| {
+            let dispatched = self.dispatch_shared(
+                &shared,
+                || Arc::clone(&shared),
+                |_| true,
+                invoke,
+                ignore_result,
+                false,
+            );
+            if let Err(error) = dispatched {
+                self.report_invalid(error);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+
+    /// Teste les compteurs avec des callbacks actifs, désactivés, filtrés et qui paniquent.
+    #[test]
+    fn test_counts_with_mixed_callbacks() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_callback(Callback::new(|_data: &CallbackPayload| {}));
+        registry.set_callback(Callback::new(|_data: &CallbackPayload| panic!("boom")));
+        let disabled = registry.set_callback(Callback::new(|_data: &CallbackPayload| {}));
+        registry.disable_callback(disabled);
+        registry.set_callback_filtered(
+            |data: &CallbackPayload| data.as_bytes()[0] == 2,
+            Callback::new(|_data: &CallbackPayload| {}),
+        );
+
+        let report = registry.dispatch_report();
+        assert_eq!(
+            (report.callbacks_invoked, report.skipped, report.errors),
+            (2, 2, 1)
+        );
+
+        registry.set_data(vec![2]);
+        let mut total = report;
+        total += registry.dispatch_report();
+        assert_eq!(
+            (total.callbacks_invoked, total.skipped, total.errors),
+            (5, 3, 2)
+        );
+        assert!(total.duration >= report.duration);
+    }
+
+    /// Teste qu'un appel ignoré pendant la pause écarte tous les callbacks.
+    #[test]
+    fn test_paused_dispatch_skips_everything() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_callback(Callback::new(|_data: &CallbackPayload| {}));
+        registry.pause();
+        let report = registry.dispatch_report();
+        assert_eq!((report.callbacks_invoked, report.skipped), (0, 1));
+    }
+}
@@ -0,0 +1,180 @@
+//! Requête/réponse : les callbacks reçoivent un `Responder` et le premier qui répond l'emporte.
+
+use std::ops::ControlFlow;
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Poignée transmise aux callbacks de requête pour répondre, voir
+/// [`CallbackRegistry::set_responder`].
+#[derive(Debug)]
+pub struct Responder<Reply> {
+    reply: Option<Reply>, // Première réponse donnée par le callback.
+}
+
+impl<Reply> Responder<Reply> {
+    /// Répond `value` ; seule la première réponse d'un callback est conservée.
+    ///
+    /// Renvoie `false` si le callback avait déjà répondu.
+    pub fn reply(&mut self, value: Reply) -> bool {
+        if self.reply.is_some() {
+            return false;
+        }
+        self.reply = Some(value);
+        true
+    }
+
+    /// Indique si le callback a déjà répondu.
+    pub fn has_replied(&self) -> bool {
+        self.reply.is_some()
+    }
+}
+
+/// Comportement de [`CallbackRegistry::dispatch_request`] une fois la première réponse obtenue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyMode {
+    /// Les callbacks suivants sont appelés, mais leurs réponses sont ignorées.
+    InvokeAll,
+    /// Les callbacks suivants ne sont pas appelés.
+    SkipRemaining,
+}
+
+impl<'a, T: CallbackData + ?Sized + 'static, D: ?Sized, Reply: 'static>
+    CallbackRegistry<'a, T, D, Option<Reply>>
+{
+    /// Enregistre `f`, qui reçoit avec les données un [`Responder`] pour répondre à
+    /// [`dispatch_request`](Self::dispatch_request).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackPayload, CallbackRegistry, ReplyMode, Responder};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[7u8][..]);
+    /// registry.set_responder(|_data: &CallbackPayload, _responder: &mut Responder<u8>| {});
+    /// registry.set_responder(|data: &CallbackPayload, responder: &mut Responder<u8>| {
+    ///     responder.reply(data.as_bytes()[0] * 2);
+    /// });
+    /// assert_eq!(registry.dispatch_request(ReplyMode::InvokeAll), Some(14));
+    /// ```
+    pub fn set_responder(&mut self, f: impl Fn(&T, &mut Responder<Reply>) + 'static) -> CallbackId {
+        self.push_callback(Callback::new(move |data: &T| {
+            let mut responder = Responder { reply: None };
+            f(data, &mut responder);
+            responder.reply
+        }))
+    }
+}
+
+/// Sink de `dispatch_where` qui conserve la première réponse dans `first`.
+fn first_reply<Reply>(
+    first: &mut Option<Reply>,
+    mode: ReplyMode,
+) -> impl FnMut(CallbackId, Option<Reply>) -> ControlFlow<()> + '_ {
+    move |_, reply| {
+        if first.is_none() {
+            *first = reply;
+        }
+        if first.is_some() && mode == ReplyMode::SkipRemaining {
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, Reply> CallbackRegistry<'a, CallbackPayload, D, Option<Reply>> {
+    /// Appelle les callbacks et renvoie la première réponse, ou `None` si aucun n'a répondu.
+    ///
+    /// Selon `mode`, les callbacks qui suivent la première réponse sont appelés (leurs réponses
+    /// étant ignorées) ou non.
+    pub fn dispatch_request(&self, mode: ReplyMode) -> Option<Reply> {
+        let mut first = None;
+        self.dispatch_where(|_| true, first_reply(&mut first, mode));
+        first
+    }
+}
+
+impl<'a, Reply> CallbackRegistry<'a, ArcCallbackPayload, [u8], Option<Reply>> {
+    /// Appelle les callbacks et renvoie la première réponse, ou `None` si aucun n'a répondu.
+    pub fn dispatch_request(&self, mode: ReplyMode) -> Option<Reply> {
+        let mut first = None;
+        self.dispatch_where(|_| true, first_reply(&mut first, mode));
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Enregistre un callback qui note `label` dans `calls` et répond `reply` s'il est fourni.
+    fn push_responder(
+        registry: &mut CallbackRegistry<'_, CallbackPayload, [u8], Option<&'static str>>,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        label: &'static str,
+        reply: Option<&'static str>,
+    ) {
+        let calls = Rc::clone(calls);
+        registry.set_responder(move |_data: &CallbackPayload, responder| {
+            calls.borrow_mut().push(label);
+            if let Some(reply) = reply {
+                responder.reply(reply);
+            }
+        });
+    }
+
+    /// Teste que la requête renvoie `None` lorsque aucun callback ne répond.
+    #[test]
+    fn test_no_reply_returns_none() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_responder(&mut registry, &calls, "silent", None);
+
+        assert_eq!(registry.dispatch_request(ReplyMode::InvokeAll), None);
+        assert_eq!(*calls.borrow(), vec!["silent"]);
+    }
+
+    /// Teste que la première réponse l'emporte et qu'une réponse ultérieure est ignorée.
+    #[test]
+    fn test_first_reply_wins() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_responder(&mut registry, &calls, "silent", None);
+        push_responder(&mut registry, &calls, "first", Some("pong"));
+        push_responder(&mut registry, &calls, "late", Some("trop tard"));
+
+        assert_eq!(
+            registry.dispatch_request(ReplyMode::InvokeAll),
+            Some("pong")
+        );
+        assert_eq!(*calls.borrow(), vec!["silent", "first", "late"]);
+    }
+
+    /// Teste que le mode `SkipRemaining` n'appelle pas les callbacks qui suivent la réponse.
+    #[test]
+    fn test_skip_remaining_after_reply() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        push_responder(&mut registry, &calls, "first", Some("pong"));
+        push_responder(&mut registry, &calls, "late", Some("trop tard"));
+
+        assert_eq!(
+            registry.dispatch_request(ReplyMode::SkipRemaining),
+            Some("pong")
+        );
+        assert_eq!(*calls.borrow(), vec!["first"]);
+    }
+
+    /// Teste qu'un callback ne peut répondre qu'une fois.
+    #[test]
+    fn test_responder_keeps_first_reply() {
+        let mut responder = Responder { reply: None };
+        assert!(responder.reply(1));
+        assert!(!responder.reply(2));
+        assert!(responder.has_replied());
+        assert_eq!(responder.reply, Some(1));
+    }
+}
@@ -0,0 +1,190 @@
+//! Échantillonnage : n'appeler un callback, ou tous, qu'une fois sur `n`.
+
+use std::cell::Cell;
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+use crate::error::ZeroSampleRate;
+
+/// Compteur qui laisse passer un appel sur `every`, le `every`-ième.
+#[derive(Debug)]
+pub(crate) struct Sampler {
+    every: u32,      // Un appel retenu sur `every`.
+    seen: Cell<u32>, // Appels vus depuis le dernier appel retenu.
+}
+
+impl Sampler {
+    /// Crée un compteur qui retient un appel sur `every`, qui n'est pas nul.
+    pub(crate) fn new(every: u32) -> Self {
+        Sampler {
+            every,
+            seen: Cell::new(0),
+        }
+    }
+
+    /// Compte un appel et indique s'il est retenu.
+    pub(crate) fn tick(&self) -> bool {
+        let seen = (self.seen.get() + 1) % self.every;
+        self.seen.set(seen);
+        seen == 0
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `cb`, appelé seulement une fois sur `every_n` parmi les appels qu'il aurait
+    /// reçus : le `every_n`-ième, le `2 * every_n`-ième, etc.
+    ///
+    /// Seuls les appels acceptés par le prédicat d'un callback filtré sont comptés, et chaque
+    /// callback a son propre compteur.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`ZeroSampleRate`] si `every_n` vaut 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry
+    ///     .set_callback_sampled(100, Callback::new(|data: &CallbackPayload| println!("{:?}", data)))
+    ///     .unwrap();
+    /// registry.do_something(); // Ignoré : seul le centième appel est transmis.
+    /// ```
+    pub fn set_callback_sampled(
+        &mut self,
+        every_n: u32,
+        cb: Callback<T, R>,
+    ) -> Result<CallbackId, ZeroSampleRate> {
+        if every_n == 0 {
+            return Err(ZeroSampleRate);
+        }
+        Ok(self.push_entry(|id| Entry::new(id, cb).sampled(every_n)))
+    }
+
+    /// N'appelle les callbacks qu'une fois sur `n` appels à `do_something`, en plus de
+    /// l'échantillonnage propre à chaque callback. `n == 1` transmet de nouveau tous les appels.
+    ///
+    /// Les appels écartés en amont (pause, validation, données inchangées) ne sont pas comptés.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie [`ZeroSampleRate`] si `n` vaut 0 ; le taux précédent est alors conservé.
+    pub fn set_sample_rate(&mut self, n: u32) -> Result<(), ZeroSampleRate> {
+        match n {
+            0 => return Err(ZeroSampleRate),
+            1 => self.sampler = None,
+            n => self.sampler = Some(Sampler::new(n)),
+        }
+        Ok(())
+    }
+
+    /// Indique si l'appel en cours doit être transmis aux callbacks selon le taux du registre.
+    pub(crate) fn sample_dispatch(&self) -> bool {
+        self.sampler.as_ref().is_none_or(Sampler::tick)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Transmet les événements 1 à 10 au registre.
+    fn dispatch_ten(registry: &mut CallbackRegistry<'static, CallbackPayload>) {
+        for value in 1..=10u8 {
+            registry.set_data(vec![value]);
+            registry.do_something();
+        }
+    }
+
+    /// Teste qu'un callback échantillonné à 3 reçoit exactement les événements 3, 6 et 9.
+    #[test]
+    fn test_every_third_of_ten() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_owned_data(Vec::new());
+        let seen_in_cb = Rc::clone(&seen);
+        registry
+            .set_callback_sampled(
+                3,
+                Callback::new(move |data: &CallbackPayload| {
+                    seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+                }),
+            )
+            .unwrap();
+
+        dispatch_ten(&mut registry);
+
+        assert_eq!(*seen.borrow(), vec![3, 6, 9]);
+    }
+
+    /// Teste que deux callbacks échantillonnés différemment gardent des compteurs indépendants et
+    /// que seuls les appels acceptés par le prédicat sont comptés.
+    #[test]
+    fn test_independent_counters_after_filters() {
+        let every_two = Rc::new(RefCell::new(Vec::new()));
+        let even_every_two = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_owned_data(Vec::new());
+        let every_two_in_cb = Rc::clone(&every_two);
+        registry
+            .set_callback_sampled(
+                2,
+                Callback::new(move |data: &CallbackPayload| {
+                    every_two_in_cb.borrow_mut().push(data.as_bytes()[0])
+                }),
+            )
+            .unwrap();
+        let id = registry.next_id();
+        let even_every_two_in_cb = Rc::clone(&even_every_two);
+        let entry = Entry::new(
+            id,
+            Callback::new(move |data: &CallbackPayload| {
+                even_every_two_in_cb.borrow_mut().push(data.as_bytes()[0])
+            }),
+        )
+        .filtered(Box::new(|data: &CallbackPayload| {
+            data.as_bytes()[0].is_multiple_of(2)
+        }))
+        .sampled(2);
+        registry.callbacks.insert_by_priority(entry);
+
+        dispatch_ten(&mut registry);
+
+        assert_eq!(*every_two.borrow(), vec![2, 4, 6, 8, 10]);
+        assert_eq!(*even_every_two.borrow(), vec![4, 8]);
+    }
+
+    /// Teste le taux du registre et le refus d'un taux nul.
+    #[test]
+    fn test_registry_sample_rate() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_owned_data(Vec::new());
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        assert_eq!(registry.set_sample_rate(0), Err(ZeroSampleRate));
+        let seen_in_cb = Rc::clone(&seen);
+        assert_eq!(
+            registry.set_callback_sampled(
+                0,
+                Callback::new(move |data: &CallbackPayload| seen_in_cb
+                    .borrow_mut()
+                    .push(data.as_bytes()[0]))
+            ),
+            Err(ZeroSampleRate)
+        );
+        registry.set_sample_rate(4).unwrap();
+
+        dispatch_ten(&mut registry);
+        assert_eq!(*seen.borrow(), vec![4, 8]);
+
+        registry.set_sample_rate(1).unwrap();
+        registry.do_something();
+        assert_eq!(seen.borrow().len(), 3);
+    }
+}
@@ -0,0 +1,447 @@
+//! Données programmées : les transmettre après un délai ou à une heure donnée.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::periodic::Periodic;
+use super::{CallbackRegistry, DispatchReport};
+use crate::callback::CallbackData;
+use crate::clock::{Clock, SystemClock};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Identifiant d'une donnée programmée, renvoyé par [`CallbackRegistry::schedule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(u64);
+
+/// Données programmées, rangées par heure de transmission puis par ordre de programmation.
+#[derive(Debug, Default)]
+pub(crate) struct TimerQueue {
+    pending: BTreeMap<(Instant, TimerId), Vec<u8>>, // Données en attente de leur heure.
+    deadlines: HashMap<TimerId, Instant>,           // Heure de chaque donnée en attente.
+    next_id: u64,                                   // Identifiant de la prochaine donnée.
+}
+
+impl TimerQueue {
+    /// Réserve l'identifiant de la prochaine donnée.
+    fn next_timer_id(&mut self) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Programme `payload` pour `at` et renvoie son identifiant.
+    fn insert(&mut self, at: Instant, payload: Vec<u8>) -> TimerId {
+        let id = self.next_timer_id();
+        self.pending.insert((at, id), payload);
+        self.deadlines.insert(id, at);
+        id
+    }
+
+    /// Programme `payload` pour `delay` après `now`. Si cette heure dépasse ce que `Instant`
+    /// représente, la donnée est oubliée : elle n'aurait jamais été due.
+    fn insert_after(&mut self, now: Instant, delay: Duration, payload: Vec<u8>) -> TimerId {
+        match now.checked_add(delay) {
+            Some(at) => self.insert(at, payload),
+            None => self.next_timer_id(),
+        }
+    }
+
+    /// Annule la donnée `id` et indique si elle était en attente.
+    fn cancel(&mut self, id: TimerId) -> bool {
+        match self.deadlines.remove(&id) {
+            Some(at) => self.pending.remove(&(at, id)).is_some(),
+            None => false,
+        }
+    }
+
+    /// Retire la plus ancienne donnée dont l'heure est passée à `now`.
+    fn pop_due(&mut self, now: Instant) -> Option<Vec<u8>> {
+        let entry = self
+            .pending
+            .first_entry()
+            .filter(|entry| entry.key().0 <= now)?;
+        self.deadlines.remove(&entry.key().1);
+        Some(entry.remove())
+    }
+
+    /// Renvoie l'heure de la prochaine donnée.
+    fn next_due(&self) -> Option<Instant> {
+        self.pending.keys().next().map(|(at, _)| *at)
+    }
+}
+
+/// Données programmées et callbacks périodiques d'un registre, et horloge qui date les délais.
+pub(crate) struct Timers {
+    queue: TimerQueue,                  // Données en attente de leur heure.
+    pub(crate) periodic: Vec<Periodic>, // Callbacks périodiques, dans l'ordre d'enregistrement.
+    pub(crate) clock: Box<dyn Clock>,   // Date les délais de `schedule`.
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Timers {
+            queue: TimerQueue::default(),
+            periodic: Vec::new(),
+            clock: Box::new(SystemClock),
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Programme la transmission de `payload` dans `delay`, selon l'horloge de
+    /// [`set_timer_clock`](Self::set_timer_clock) ; le premier [`run_due`](Self::run_due)
+    /// après cette heure la transmettra.
+    ///
+    /// Un délai trop long pour être ajouté à l'heure actuelle, comme `Duration::MAX`, est
+    /// accepté : la donnée n'est alors jamais transmise, ni comptée par
+    /// [`scheduled_len`](Self::scheduled_len).
+    pub fn schedule(&mut self, delay: Duration, payload: Vec<u8>) -> TimerId {
+        let now = self.timers.clock.now();
+        self.timers.queue.insert_after(now, delay, payload)
+    }
+
+    /// Programme la transmission de `payload` à `at`.
+    pub fn schedule_at(&mut self, at: Instant, payload: Vec<u8>) -> TimerId {
+        self.timers.queue.insert(at, payload)
+    }
+
+    /// Annule la donnée programmée `id`, qui ne sera jamais transmise, et indique si elle était
+    /// encore en attente.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        self.timers.queue.cancel(id)
+    }
+
+    /// Date les délais de [`schedule`](Self::schedule) avec `clock` plutôt qu'avec l'horloge du
+    /// système.
+    pub fn set_timer_clock(&mut self, clock: impl Clock + 'static) {
+        self.timers.clock = Box::new(clock);
+    }
+
+    /// Renvoie le nombre de données programmées en attente.
+    pub fn scheduled_len(&self) -> usize {
+        self.timers.queue.pending.len()
+    }
+
+    /// Renvoie l'heure de la prochaine donnée programmée ou du prochain appel d'un callback
+    /// périodique.
+    pub fn next_due(&self) -> Option<Instant> {
+        match (self.timers.queue.next_due(), self.next_periodic()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Transmet à `dispatch` chaque donnée programmée au plus tard à `now`, dans l'ordre
+    /// chronologique, puis appelle les callbacks périodiques dus, en cumulant les bilans.
+    fn run_due_with(
+        &mut self,
+        now: Instant,
+        dispatch: impl Fn(&Self, Vec<u8>) -> DispatchReport,
+    ) -> DispatchReport {
+        let mut report = DispatchReport::default();
+        while let Some(payload) = self.timers.queue.pop_due(now) {
+            report += dispatch(self, payload);
+        }
+        report + self.run_periodic(now)
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Transmet chaque donnée programmée au plus tard à `now`, de la plus ancienne à la plus
+    /// récente, comme [`dispatch_report`](Self::dispatch_report), et renvoie le cumul des bilans.
+    ///
+    /// Les données en retard sont toutes transmises par le premier appel qui suit leur heure ;
+    /// les callbacks périodiques dus sont ensuite appelés, voir
+    /// [`set_periodic_callback`](Self::set_periodic_callback).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, OwnedRegistry};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    /// let now = Instant::now();
+    /// registry.schedule_at(now + Duration::from_secs(1), vec![1]);
+    /// assert_eq!(registry.run_due(now).callbacks_invoked, 0);
+    /// assert_eq!(registry.run_due(now + Duration::from_secs(1)).callbacks_invoked, 1);
+    /// ```
+    pub fn run_due(&mut self, now: Instant) -> DispatchReport {
+        self.run_due_with(now, |registry, payload| registry.report_bytes(&payload))
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Transmet chaque donnée programmée au plus tard à `now`, de la plus ancienne à la plus
+    /// récente, comme [`dispatch_report`](Self::dispatch_report), et renvoie le cumul des bilans.
+    pub fn run_due(&mut self, now: Instant) -> DispatchReport {
+        self.run_due_with(now, |registry, payload| registry.report_shared(payload))
+    }
+}
+
+/// État partagé entre une [`SchedulerHandle`] et son thread.
+#[derive(Debug, Default)]
+struct SchedulerState {
+    timers: TimerQueue, // Données programmées par la poignée.
+    stopped: bool,      // `true` une fois la poignée arrêtée.
+}
+
+/// Poignée d'un thread qui transmet les données programmées à leur heure, créée par
+/// [`CallbackRegistry::spawn_scheduler`].
+///
+/// Détruire la poignée arrête le thread comme [`shutdown`](Self::shutdown), en ignorant son bilan.
+#[derive(Debug)]
+pub struct SchedulerHandle {
+    shared: Arc<(Mutex<SchedulerState>, Condvar)>, // État partagé avec le thread, qui attend sur la `Condvar`.
+    thread: Option<JoinHandle<DispatchReport>>,    // Le thread, `None` une fois attendu.
+}
+
+impl SchedulerHandle {
+    /// Verrouille l'état partagé, même si le thread a paniqué en le tenant.
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        self.shared
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Programme la transmission de `payload` dans `delay` ; comme avec
+    /// [`CallbackRegistry::schedule`], un délai qui dépasse ce que `Instant` représente n'est
+    /// jamais atteint.
+    pub fn schedule(&self, delay: Duration, payload: Vec<u8>) -> TimerId {
+        let id = self
+            .lock()
+            .timers
+            .insert_after(Instant::now(), delay, payload);
+        self.shared.1.notify_one();
+        id
+    }
+
+    /// Programme la transmission de `payload` à `at`.
+    pub fn schedule_at(&self, at: Instant, payload: Vec<u8>) -> TimerId {
+        let id = self.lock().timers.insert(at, payload);
+        self.shared.1.notify_one();
+        id
+    }
+
+    /// Annule la donnée programmée `id` et indique si elle était encore en attente.
+    pub fn cancel(&self, id: TimerId) -> bool {
+        self.lock().timers.cancel(id)
+    }
+
+    /// Arrête le thread et renvoie le cumul des bilans de ses appels. Les données programmées
+    /// qui n'ont pas encore été transmises sont abandonnées.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie la panique du thread si le traitement des données a paniqué.
+    pub fn shutdown(mut self) -> thread::Result<DispatchReport> {
+        self.stop()
+    }
+
+    /// Arrête le thread et attend sa fin.
+    fn stop(&mut self) -> thread::Result<DispatchReport> {
+        self.lock().stopped = true;
+        self.shared.1.notify_one();
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(DispatchReport::default()),
+        }
+    }
+}
+
+impl Drop for SchedulerHandle {
+    fn drop(&mut self) {
+        // La panique du thread a déjà été affichée par celui-ci.
+        let _ = self.stop();
+    }
+}
+
+impl<D: AsRef<[u8]> + ?Sized + 'static, R: 'static>
+    CallbackRegistry<'static, CallbackPayload, D, R>
+{
+    /// Démarre un thread qui transmet aux callbacks du registre construit par `build` les
+    /// données programmées par la poignée renvoyée, à leur heure selon l'horloge du système.
+    ///
+    /// Comme pour [`spawn_dispatcher`](Self::spawn_dispatcher), le registre est construit sur le
+    /// thread ; les données qu'il a lui-même programmées sont aussi transmises à leur heure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, OwnedRegistry};
+    /// use std::time::Duration;
+    ///
+    /// let scheduler = OwnedRegistry::spawn_scheduler(|| {
+    ///     let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    ///     registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    ///     registry
+    /// });
+    /// let id = scheduler.schedule(Duration::from_secs(60), vec![1]);
+    /// assert!(scheduler.cancel(id));
+    /// ```
+    pub fn spawn_scheduler(build: impl FnOnce() -> Self + Send + 'static) -> SchedulerHandle {
+        let shared: Arc<(Mutex<SchedulerState>, Condvar)> = Arc::default();
+        let in_thread = Arc::clone(&shared);
+        let thread = thread::spawn(move || {
+            let mut registry = build();
+            let mut report = DispatchReport::default();
+            let (state, ready) = &*in_thread;
+            let lock = || {
+                state
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+            };
+            let mut guard = lock();
+            while !guard.stopped {
+                let now = Instant::now();
+                if let Some(payload) = guard.timers.pop_due(now) {
+                    drop(guard);
+                    report += registry.report_bytes(&payload);
+                    guard = lock();
+                    continue;
+                }
+                if registry.next_due().is_some_and(|at| at <= now) {
+                    drop(guard);
+                    report += registry.run_due(now);
+                    guard = lock();
+                    continue;
+                }
+                let next = match (guard.timers.next_due(), registry.next_due()) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                guard = match next {
+                    Some(at) => {
+                        ready
+                            .wait_timeout(guard, at.saturating_duration_since(now))
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .0
+                    }
+                    None => ready
+                        .wait(guard)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner()),
+                };
+            }
+            report
+        });
+        SchedulerHandle {
+            shared,
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::clock::ManualClock;
+    use crate::registry::{CallbackHost, OwnedRegistry};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste que deux données programmées dans le désordre sont transmises dans l'ordre
+    /// chronologique, par le premier appel qui suit leur heure.
+    #[test]
+    fn test_two_timers_fire_in_order() {
+        let clock = ManualClock::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        registry.set_timer_clock(clock.clone());
+        let start = clock.now();
+        registry.schedule(Duration::from_millis(200), vec![2]);
+        registry.schedule(Duration::from_millis(100), vec![1]);
+        assert_eq!(
+            registry.next_due(),
+            Some(start + Duration::from_millis(100))
+        );
+
+        assert_eq!(
+            registry
+                .run_due(start + Duration::from_millis(50))
+                .callbacks_invoked,
+            0
+        );
+        let report = registry.run_due(start + Duration::from_millis(300));
+        assert_eq!(report.callbacks_invoked, 2);
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+        assert_eq!(registry.scheduled_len(), 0);
+    }
+
+    /// Teste qu'une donnée annulée n'est jamais transmise.
+    #[test]
+    fn test_cancelled_timer_never_fires() {
+        let clock = ManualClock::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        registry.set_timer_clock(clock.clone());
+        let first = registry.schedule(Duration::from_millis(10), vec![1]);
+        registry.schedule(Duration::from_millis(20), vec![2]);
+        assert!(registry.cancel(first));
+        assert!(!registry.cancel(first));
+
+        clock.advance(Duration::from_secs(1));
+        let now = clock.now();
+        registry.run_due(now);
+        assert_eq!(*seen.borrow(), vec![2]);
+    }
+
+    /// Teste qu'un délai trop long pour l'horloge est accepté sans que la donnée soit transmise.
+    #[test]
+    fn test_unreachable_delay_never_fires() {
+        let clock = ManualClock::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().push(data.as_bytes()[0])
+        }));
+        registry.set_timer_clock(clock.clone());
+        let never = registry.schedule(Duration::MAX, vec![1]);
+        assert_eq!((registry.scheduled_len(), registry.next_due()), (0, None));
+
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(registry.run_due(clock.now()).callbacks_invoked, 0);
+        assert!(!registry.cancel(never));
+        assert!(seen.borrow().is_empty());
+    }
+
+    /// Teste que le thread transmet une donnée programmée, mais pas celle qui a été annulée.
+    #[test]
+    fn test_background_scheduler_fires() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let fired_in_cb = Arc::clone(&fired);
+        let scheduler = OwnedRegistry::spawn_scheduler(move || {
+            let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+            registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+                fired_in_cb.lock().unwrap().push(data.as_bytes()[0])
+            }));
+            registry
+        });
+        let cancelled = scheduler.schedule(Duration::from_millis(20), vec![2]);
+        scheduler.schedule(Duration::from_millis(10), vec![1]);
+        scheduler.schedule(Duration::MAX, vec![3]);
+        assert!(scheduler.cancel(cancelled));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while fired.lock().unwrap().is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        thread::sleep(Duration::from_millis(30));
+        let report = scheduler.shutdown().unwrap();
+        assert_eq!(report.callbacks_invoked, 1);
+        assert_eq!(*fired.lock().unwrap(), vec![1]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Suivi des numéros de séquence des données, pour détecter les données perdues.
+
+use std::cell::RefCell;
+
+use super::CallbackRegistry;
+use crate::callback::CallbackData;
+
+/// Extrait le numéro de séquence des données, voir [`CallbackRegistry::track_sequence`].
+type Extract = Box<dyn Fn(&[u8]) -> Option<u64>>;
+
+/// Reçoit les numéros manquants, voir [`CallbackRegistry::set_on_gap`].
+type GapHandler = Box<dyn Fn(u64, u64)>;
+
+/// Compteurs du suivi des numéros de séquence, voir [`CallbackRegistry::sequence_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SequenceStats {
+    pub missing: u64,    // Nombre de numéros signalés manquants.
+    pub duplicates: u64, // Nombre de données dont le numéro a déjà été vu, ou signalé manquant.
+    pub reordered: u64, // Nombre de données arrivées en retard, dans la fenêtre de réordonnancement.
+}
+
+/// Suivi des numéros de séquence d'un registre.
+pub(crate) struct SequenceTracking {
+    extract: Extract,              // Extrait le numéro de séquence des données.
+    modulus: u128,                 // Les numéros repartent de 0 après `modulus - 1`.
+    window: u64,                   // Retard toléré avant qu'un numéro soit signalé manquant.
+    on_gap: Option<GapHandler>,    // Reçoit les numéros manquants.
+    state: RefCell<SequenceState>, // Numéros attendus et manquants.
+}
+
+/// État du suivi des numéros de séquence.
+#[derive(Debug, Default)]
+struct SequenceState {
+    newest: Option<u64>, // Plus grand numéro reçu, `None` avant les premières données.
+    pending: Vec<(u64, u64)>, // Suites de numéros manquants `[début, fin)`, pas encore signalées.
+    stats: SequenceStats, // Compteurs depuis `track_sequence`.
+}
+
+impl SequenceTracking {
+    /// Renvoie la distance de `from` à `to` en avançant, modulo `modulus`.
+    fn distance(&self, from: u64, to: u64) -> u64 {
+        ((u128::from(to) + self.modulus - u128::from(from)) % self.modulus) as u64
+    }
+
+    /// Renvoie le numéro qui suit `seq`.
+    fn successor(&self, seq: u64) -> u64 {
+        ((u128::from(seq) + 1) % self.modulus) as u64
+    }
+
+    /// Compte les données `data` et signale les numéros manquants qui sortent de la fenêtre.
+    fn observe(&self, data: &[u8]) {
+        let Some(seq) = (self.extract)(data) else {
+            return;
+        };
+        let seq = (u128::from(seq) % self.modulus) as u64;
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let Some(newest) = state.newest else {
+            state.newest = Some(seq);
+            return;
+        };
+
+        let ahead = self.distance(newest, seq);
+        if ahead != 0 && u128::from(ahead) < self.modulus / 2 {
+            let expected = self.successor(newest);
+            if expected != seq {
+                state.pending.push((expected, seq));
+            }
+            state.newest = Some(seq);
+        } else if let Some(index) = state
+            .pending
+            .iter()
+            .position(|&(start, end)| self.distance(start, seq) < self.distance(start, end))
+        {
+            // Arrivée en retard : la suite manquante est coupée en deux autour de `seq`.
+            let (start, end) = state.pending.remove(index);
+            let after = self.successor(seq);
+            if after != end {
+                state.pending.insert(index, (after, end));
+            }
+            if seq != start {
+                state.pending.insert(index, (start, seq));
+            }
+            state.stats.reordered += 1;
+        } else {
+            state.stats.duplicates += 1;
+        }
+
+        let newest = state.newest.unwrap_or(seq);
+        let expired = |&(_, end): &(u64, u64)| self.distance(end, newest) >= self.window;
+        while let Some(index) = state.pending.iter().position(expired) {
+            let (start, end) = state.pending.remove(index);
+            state.stats.missing += self.distance(start, end);
+            if let Some(on_gap) = &self.on_gap {
+                on_gap(start, end);
+            }
+        }
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Suit les numéros de séquence que `extract` lit dans les données et signale ceux qui
+    /// manquent au gestionnaire de [`set_on_gap`](Self::set_on_gap).
+    ///
+    /// Les données sont suivies après somme de contrôle et transformations, qu'elles soient
+    /// ensuite transmises aux callbacks ou non ; celles dont `extract` renvoie `None` ne sont pas
+    /// suivies. Les numéros déjà vus sont comptés comme doublons, sans être signalés. Le suivi
+    /// repart de zéro, avec les numéros de 64 bits et une fenêtre de réordonnancement nulle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::new();
+    /// registry.track_sequence(|data: &[u8]| data.first().map(|&seq| u64::from(seq)));
+    /// registry.set_sequence_modulus(256);
+    /// registry.set_on_gap(|expected, got| eprintln!("numéros {} à {} perdus", expected, got - 1));
+    /// for seq in [254u8, 255, 0, 2] {
+    ///     registry.set_data(vec![seq]);
+    ///     registry.do_something();
+    /// }
+    /// assert_eq!(registry.sequence_stats().unwrap().missing, 1);
+    /// ```
+    pub fn track_sequence(&mut self, extract: impl Fn(&[u8]) -> Option<u64> + 'static) {
+        let on_gap = self.sequence.take().and_then(|tracking| tracking.on_gap);
+        self.sequence = Some(SequenceTracking {
+            extract: Box::new(extract),
+            modulus: 1 << 64,
+            window: 0,
+            on_gap,
+            state: RefCell::default(),
+        });
+    }
+
+    /// Arrête le suivi des numéros de séquence, sans signaler les numéros manquants en attente.
+    pub fn stop_tracking_sequence(&mut self) {
+        self.sequence = None;
+    }
+
+    /// Fait repartir les numéros de séquence de 0 après `modulus - 1`, par exemple 256 pour un
+    /// compteur d'un byte.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `modulus` vaut moins de 2, ou si les numéros de séquence ne sont pas suivis.
+    pub fn set_sequence_modulus(&mut self, modulus: u64) {
+        assert!(
+            modulus >= 2,
+            "le modulo des numéros de séquence doit être au moins 2"
+        );
+        self.tracking_mut().modulus = u128::from(modulus);
+    }
+
+    /// Tolère les données qui arrivent jusqu'à `window` numéros en retard : un numéro n'est
+    /// signalé manquant qu'une fois reçu un numéro qui le dépasse de plus de `window`.
+    ///
+    /// # Panics
+    ///
+    /// Panique si les numéros de séquence ne sont pas suivis.
+    pub fn set_reorder_window(&mut self, window: u64) {
+        self.tracking_mut().window = window;
+    }
+
+    /// Transmet à `f` chaque suite de numéros manquants, comme `f(attendu, reçu)` : les numéros
+    /// de `attendu` à `reçu - 1` manquent et `reçu` les suit.
+    ///
+    /// # Panics
+    ///
+    /// Panique si les numéros de séquence ne sont pas suivis.
+    pub fn set_on_gap(&mut self, f: impl Fn(u64, u64) + 'static) {
+        self.tracking_mut().on_gap = Some(Box::new(f));
+    }
+
+    /// Renvoie les compteurs du suivi des numéros de séquence, ou `None` s'ils ne sont pas suivis.
+    pub fn sequence_stats(&self) -> Option<SequenceStats> {
+        self.sequence
+            .as_ref()
+            .map(|tracking| tracking.state.borrow().stats)
+    }
+
+    /// Renvoie le suivi des numéros de séquence, qui doit avoir été activé.
+    fn tracking_mut(&mut self) -> &mut SequenceTracking {
+        self.sequence
+            .as_mut()
+            .expect("aucun suivi des numéros de séquence : appelez `track_sequence`")
+    }
+
+    /// Suit le numéro de séquence de `data`, si les numéros de séquence sont suivis.
+    pub(crate) fn observe_sequence(&self, data: &[u8]) {
+        if let Some(tracking) = &self.sequence {
+            tracking.observe(data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Crée un registre qui suit le premier byte des données et note les suites manquantes.
+    fn tracking_registry(
+        gaps: &Rc<RefCell<Vec<(u64, u64)>>>,
+    ) -> CallbackRegistry<'static, CallbackPayload> {
+        let mut registry = CallbackRegistry::with_owned_data(Vec::new());
+        registry.track_sequence(|data: &[u8]| data.first().map(|&seq| u64::from(seq)));
+        let gaps = Rc::clone(gaps);
+        registry.set_on_gap(move |expected, got| gaps.borrow_mut().push((expected, got)));
+        registry
+    }
+
+    /// Transmet les numéros `seqs`, un par appel.
+    fn feed_sequence(registry: &mut CallbackRegistry<'static, CallbackPayload>, seqs: &[u8]) {
+        for &seq in seqs {
+            registry.set_data(vec![seq]);
+            registry.do_something();
+        }
+    }
+
+    /// Teste qu'une donnée perdue est signalée et qu'un doublon est seulement compté.
+    #[test]
+    fn test_dropped_frame_and_duplicate() {
+        let gaps = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = tracking_registry(&gaps);
+        feed_sequence(&mut registry, &[1, 2, 4, 4, 5, 8]);
+        assert_eq!(*gaps.borrow(), vec![(3, 4), (6, 8)]);
+        assert_eq!(
+            registry.sequence_stats(),
+            Some(SequenceStats {
+                missing: 3,
+                duplicates: 1,
+                reordered: 0
+            })
+        );
+    }
+
+    /// Teste le passage de 255 à 0 d'un compteur d'un byte, avec et sans perte.
+    #[test]
+    fn test_wrap_around() {
+        let gaps = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = tracking_registry(&gaps);
+        registry.set_sequence_modulus(256);
+        feed_sequence(&mut registry, &[254, 255, 0, 1, 254, 3]);
+        assert_eq!(*gaps.borrow(), vec![(2, 3)]);
+        assert_eq!(registry.sequence_stats().unwrap().duplicates, 1);
+    }
+
+    /// Teste que les données en retard dans la fenêtre ne sont pas signalées, et les autres si.
+    #[test]
+    fn test_reorder_window() {
+        let gaps = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = tracking_registry(&gaps);
+        registry.set_reorder_window(2);
+        feed_sequence(&mut registry, &[1, 4, 2, 5]);
+        assert!(gaps.borrow().is_empty());
+        feed_sequence(&mut registry, &[6]);
+        assert_eq!(*gaps.borrow(), vec![(3, 4)]);
+        feed_sequence(&mut registry, &[3]);
+        assert_eq!(
+            registry.sequence_stats(),
+            Some(SequenceStats {
+                missing: 1,
+                duplicates: 1,
+                reordered: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Stockage des callbacks d'un registre : un slab indexé par l'identifiant du callback, et la
+//! liste qui fixe leur ordre d'appel.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::storage::CallbackVec;
+use super::Entry;
+use crate::callback::{CallbackData, CallbackId};
+
+/// Allocateur partagé des identifiants d'un registre, voir [`IdAllocator`].
+pub(crate) type SharedIds = Rc<RefCell<IdAllocator>>;
+
+/// Attribue les cases du slab et tient à jour leur génération.
+#[derive(Debug, Default)]
+pub(crate) struct IdAllocator {
+    generations: Vec<u32>, // Génération actuelle de chaque case.
+    free: Vec<u32>,        // Cases libérées, réattribuées en priorité.
+}
+
+impl IdAllocator {
+    /// Réserve une case et renvoie son identifiant.
+    pub(crate) fn next_id(&mut self) -> CallbackId {
+        match self.free.pop() {
+            Some(index) => CallbackId::from_parts(index, self.generations[index as usize]),
+            None => {
+                let index = u32::try_from(self.generations.len()).expect("trop de callbacks");
+                self.generations.push(0);
+                CallbackId::from_parts(index, 0)
+            }
+        }
+    }
+
+    /// Libère la case de `id` ; son prochain identifiant aura une nouvelle génération.
+    pub(crate) fn release(&mut self, id: CallbackId) {
+        let generation = &mut self.generations[id.index()];
+        if *generation == id.generation() {
+            *generation = generation.wrapping_add(1);
+            self.free.push(id.index() as u32);
+        }
+    }
+}
+
+/// Les callbacks d'un registre, rangés dans la case de leur identifiant.
+///
+/// L'accès et le retrait par identifiant sont en O(1). Un retrait laisse une case périmée dans
+/// `order`, ignorée par `iter` et supprimée lorsque les cases périmées dépassent la moitié de la
+/// liste, ou à la prochaine insertion.
+pub(crate) struct EntrySlab<T: CallbackData + ?Sized, R> {
+    slots: CallbackVec<Option<Entry<T, R>>>, // Entrées, à la case de leur identifiant.
+    order: CallbackVec<CallbackId>,          // Ordre d'appel, cases périmées comprises.
+    stale: usize,                            // Nombre de cases périmées dans `order`.
+    ids: SharedIds,                          // Allocateur des identifiants du registre.
+}
+
+impl<T: CallbackData + ?Sized, R> EntrySlab<T, R> {
+    /// Crée un slab vide dont les identifiants sont attribués par `ids`.
+    pub(crate) fn new(ids: SharedIds) -> Self {
+        EntrySlab {
+            slots: CallbackVec::default(),
+            order: CallbackVec::default(),
+            stale: 0,
+            ids,
+        }
+    }
+
+    /// Renvoie l'allocateur des identifiants du slab.
+    pub(crate) fn ids(&self) -> &SharedIds {
+        &self.ids
+    }
+
+    /// Renvoie le nombre d'entrées.
+    pub(crate) fn len(&self) -> usize {
+        self.order.len() - self.stale
+    }
+
+    /// Indique si le slab ne contient aucune entrée.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Renvoie le nombre d'entrées que l'ordre d'appel peut contenir sans allouer.
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.order.capacity()
+    }
+
+    /// Indique si l'ordre d'appel est stocké sur le tas.
+    #[cfg(test)]
+    pub(crate) fn is_spilled(&self) -> bool {
+        self.order.is_spilled()
+    }
+
+    /// Renvoie l'entrée `id`, sauf si elle a été retirée.
+    pub(crate) fn get(&self, id: CallbackId) -> Option<&Entry<T, R>> {
+        self.slots
+            .get(id.index())?
+            .as_ref()
+            .filter(|entry| entry.id == id)
+    }
+
+    /// Renvoie l'entrée `id` en écriture, sauf si elle a été retirée.
+    pub(crate) fn get_mut(&mut self, id: CallbackId) -> Option<&mut Entry<T, R>> {
+        self.slots
+            .get_mut(id.index())?
+            .as_mut()
+            .filter(|entry| entry.id == id)
+    }
+
+    /// Itère sur les entrées, dans l'ordre d'appel.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Entry<T, R>> {
+        self.order.iter().filter_map(|id| self.get(*id))
+    }
+
+    /// Itère sur les entrées en écriture, dans l'ordre de leurs cases et non d'appel.
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut Entry<T, R>> {
+        self.slots.iter_mut().flatten()
+    }
+
+    /// Réserve la place pour au moins `additional` entrées supplémentaires.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.order.reserve(additional);
+        self.slots.reserve(additional);
+    }
+
+    /// Réserve la place pour exactement `additional` entrées supplémentaires.
+    pub(crate) fn reserve_exact(&mut self, additional: usize) {
+        self.order.reserve_exact(additional);
+        self.slots.reserve_exact(additional);
+    }
+
+    /// Supprime les cases périmées de l'ordre d'appel.
+    fn compact(&mut self) {
+        if self.stale > 0 {
+            let slots = &self.slots;
+            self.order.retain(|id| {
+                slots
+                    .get(id.index())
+                    .and_then(Option::as_ref)
+                    .is_some_and(|entry| entry.id == *id)
+            });
+            self.stale = 0;
+        }
+    }
+
+    /// Range `entry` dans la case de son identifiant, réservée auprès de l'allocateur.
+    fn place(&mut self, entry: Entry<T, R>) {
+        let index = entry.id.index();
+        while self.slots.len() <= index {
+            self.slots.push(None);
+        }
+        self.slots[index] = Some(entry);
+    }
+
+    /// Insère `entry` après toutes les entrées de priorité inférieure ou égale.
+    pub(crate) fn insert_by_priority(&mut self, entry: Entry<T, R>) {
+        self.compact();
+        let priority = entry.callback.priority();
+        let slots = &self.slots;
+        let position = self.order.partition_point(|id| {
+            slots[id.index()]
+                .as_ref()
+                .is_some_and(|other| other.callback.priority() <= priority)
+        });
+        self.order.insert(position, entry.id);
+        self.place(entry);
+    }
+
+    /// Retire et renvoie l'entrée `id`, en libérant sa case.
+    pub(crate) fn remove(&mut self, id: CallbackId) -> Option<Entry<T, R>> {
+        self.get(id)?;
+        let entry = self.slots[id.index()].take();
+        self.ids.borrow_mut().release(id);
+        self.stale += 1;
+        if self.stale * 2 > self.order.len() {
+            self.compact();
+        }
+        entry
+    }
+
+    /// Remplace l'entrée `old` par `entry`, à la même place dans l'ordre d'appel, et renvoie
+    /// l'ancienne entrée.
+    pub(crate) fn replace(&mut self, old: CallbackId, entry: Entry<T, R>) -> Option<Entry<T, R>> {
+        self.compact();
+        let position = self.order.iter().position(|id| *id == old)?;
+        let previous = self.slots[old.index()].take();
+        self.ids.borrow_mut().release(old);
+        self.order[position] = entry.id;
+        self.place(entry);
+        previous
+    }
+
+    /// Ne conserve que les entrées pour lesquelles `keep` renvoie `true`, dans le même ordre.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&Entry<T, R>) -> bool) {
+        let removed: Vec<CallbackId> = self
+            .iter()
+            .filter(|entry| !keep(entry))
+            .map(|entry| entry.id)
+            .collect();
+        for id in removed {
+            self.remove(id);
+        }
+        self.compact();
+    }
+
+    /// Retire toutes les entrées et libère leurs cases.
+    pub(crate) fn clear(&mut self) {
+        let mut ids = self.ids.borrow_mut();
+        for entry in self.slots.iter_mut().filter_map(Option::take) {
+            ids.release(entry.id);
+        }
+        self.order.clear();
+        self.stale = 0;
+    }
+
+    /// Retire toutes les entrées et les renvoie dans un nouveau slab, sans libérer leurs cases.
+    pub(crate) fn take(&mut self) -> Self {
+        let ids = Rc::clone(&self.ids);
+        std::mem::replace(self, EntrySlab::new(ids))
+    }
+
+    /// Retire toutes les entrées, libère leurs cases et les renvoie dans l'ordre d'appel.
+    pub(crate) fn drain(&mut self) -> Vec<Entry<T, R>> {
+        let order: Vec<CallbackId> = self.order.take().into_iter().collect();
+        let entries = order.into_iter().filter_map(|id| self.remove(id)).collect();
+        self.clear();
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::{CallbackHost, CallbackRegistry};
+    use std::cell::RefCell;
+
+    /// Teste qu'une case réattribuée ne ressuscite pas l'ancien callback sous son identifiant.
+    #[test]
+    fn test_reused_slot_rejects_stale_id() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let old = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("ancien"));
+        assert!(registry.remove_callback(old));
+
+        let calls_in_cb = Rc::clone(&calls);
+        let new = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("nouveau"));
+        assert_eq!(new.index(), old.index());
+        assert_ne!(new, old);
+        assert!(registry.callbacks.get(old).is_none());
+        assert!(!registry.remove_callback(old));
+        assert!(!registry.disable_callback(old));
+
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["nouveau"]);
+        assert_eq!(registry.callback_count(), 1);
+    }
+
+    /// Teste que l'ordre d'appel reste déterministe quand des cases sont réutilisées.
+    #[test]
+    fn test_order_is_kept_across_reuse() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let mut ids = Vec::new();
+        for tag in 0..5 {
+            let order = Rc::clone(&order);
+            ids.push(
+                registry.set_callback(move |_data: &CallbackPayload| order.borrow_mut().push(tag)),
+            );
+        }
+        registry.remove_callback(ids[1]);
+        registry.remove_callback(ids[3]);
+        let order_in_cb = Rc::clone(&order);
+        registry.set_callback(move |_data: &CallbackPayload| order_in_cb.borrow_mut().push(5));
+
+        registry.do_something();
+        assert_eq!(*order.borrow(), vec![0, 2, 4, 5]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Mise de côté temporaire des callbacks d'un registre, puis restauration.
+
+use super::slab::EntrySlab;
+use super::CallbackRegistry;
+use crate::callback::CallbackData;
+
+/// Callbacks retirés d'un registre par [`CallbackRegistry::snapshot`], avec leurs métadonnées.
+///
+/// Le contenu est opaque : il ne peut qu'être rendu à un registre via [`CallbackRegistry::restore`].
+pub struct CallbackSnapshot<T: CallbackData + ?Sized, R = ()> {
+    entries: EntrySlab<T, R>, // Entrées retirées, avec leur ordre d'appel.
+}
+
+impl<T: CallbackData + ?Sized, R> CallbackSnapshot<T, R> {
+    /// Renvoie le nombre de callbacks mis de côté.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indique si aucun callback n'a été mis de côté.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Retire tous les callbacks du registre et les renvoie dans un [`CallbackSnapshot`].
+    ///
+    /// Le registre se comporte ensuite comme s'il était vide, jusqu'à [`restore`](Self::restore).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(Callback::new(|_data: &CallbackPayload| println!("production")));
+    ///
+    /// let production = registry.snapshot();
+    /// registry.set_callback(Callback::new(|_data: &CallbackPayload| println!("espion")));
+    /// registry.do_something(); // N'affiche que « espion ».
+    /// registry.restore(production);
+    /// registry.do_something(); // N'affiche que « production ».
+    /// ```
+    pub fn snapshot(&mut self) -> CallbackSnapshot<T, R> {
+        self.prune_spent();
+        CallbackSnapshot {
+            entries: self.callbacks.take(),
+        }
+    }
+
+    /// Remet en place les callbacks de `snapshot`, avec leurs identifiants et métadonnées d'origine.
+    ///
+    /// Les callbacks enregistrés depuis le [`snapshot`](Self::snapshot) sont détruits.
+    pub fn restore(&mut self, snapshot: CallbackSnapshot<T, R>) {
+        self.callbacks.clear();
+        self.callbacks = snapshot.entries;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::CallbackId;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste qu'un aller-retour restaure exactement l'ordre et les identifiants d'origine.
+    #[test]
+    fn test_snapshot_round_trip() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("c"));
+        let before: Vec<CallbackId> = registry.iter_callbacks().map(|info| info.id).collect();
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 3);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("spy"));
+        registry.do_something();
+        registry.restore(snapshot);
+        registry.do_something();
+
+        let after: Vec<CallbackId> = registry.iter_callbacks().map(|info| info.id).collect();
+        assert_eq!(before, after);
+        assert_eq!(*calls.borrow(), vec!["spy", "a", "b", "c"]);
+    }
+
+    /// Teste qu'un appel pendant le snapshot voit un registre vide.
+    #[test]
+    fn test_dispatch_during_snapshot_sees_empty_registry() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+
+        let snapshot = registry.snapshot();
+        registry.do_something();
+
+        assert!(calls.borrow().is_empty());
+        assert!(!registry.has_callbacks());
+        registry.restore(snapshot);
+        assert_eq!(registry.callback_count(), 1);
+    }
+}
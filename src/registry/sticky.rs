@@ -0,0 +1,157 @@
+//! Dernières données « collantes », transmises à chaque nouveau callback dès son enregistrement.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::CallbackData;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Dernières données transmises et façon de les remettre à une entrée du registre.
+pub(crate) struct Sticky<T: CallbackData + ?Sized, R> {
+    last: RefCell<Option<Vec<u8>>>, // Dernières données transmises aux callbacks.
+    deliver: fn(&Entry<T, R>, &[u8]), // Appelle l'entrée avec ces données.
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// N'appelle plus les nouveaux callbacks avec les dernières données, et les oublie.
+    pub fn disable_sticky(&mut self) {
+        self.sticky = None;
+    }
+
+    /// Retient `data` comme dernières données transmises, si le mode collant est activé.
+    pub(crate) fn record_sticky(&self, data: &[u8]) {
+        if let Some(sticky) = &self.sticky {
+            let mut last = sticky.last.borrow_mut();
+            let last = last.get_or_insert_with(Vec::new);
+            last.clear();
+            last.extend_from_slice(data);
+        }
+    }
+
+    /// Appelle `entry` avec les dernières données, si le mode collant est activé et qu'il y en a.
+    pub(crate) fn deliver_sticky(&self, entry: &Entry<T, R>) {
+        if let Some(sticky) = &self.sticky {
+            if let Some(last) = sticky.last.borrow().as_deref() {
+                (sticky.deliver)(entry, last);
+            }
+        }
+    }
+}
+
+impl<'a, D: ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Active le mode collant : chaque callback enregistré ensuite est appelé aussitôt avec les
+    /// dernières données transmises, s'il y en a, puis rejoint les appels suivants.
+    ///
+    /// Cet appel est un appel comme un autre : un callback filtré n'est appelé que si son
+    /// prédicat accepte les données, et un callback unique ainsi satisfait n'est pas enregistré.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, OwnedRegistry};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![42]);
+    /// registry.enable_sticky();
+    /// registry.do_something();
+    /// registry.set_callback_once(|data: &CallbackPayload| assert_eq!(data.as_bytes(), &[42]));
+    /// assert_eq!(registry.callback_count(), 0);
+    /// ```
+    pub fn enable_sticky(&mut self) {
+        self.sticky = Some(Sticky {
+            last: RefCell::new(None),
+            deliver: |entry, data| {
+                entry.invoke(CallbackPayload::new(data));
+            },
+        });
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Active le mode collant, voir
+    /// [`CallbackRegistry::<CallbackPayload>::enable_sticky`](CallbackRegistry::enable_sticky).
+    pub fn enable_sticky(&mut self) {
+        self.sticky = Some(Sticky {
+            last: RefCell::new(None),
+            deliver: |entry, data| {
+                entry.invoke(&ArcCallbackPayload::new(Arc::from(data)));
+            },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::Callback;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Journal des appels : nom du callback et premier byte des données reçues.
+    type Log = Rc<RefCell<Vec<(&'static str, u8)>>>;
+
+    /// Crée un registre collant et un journal des données reçues par ses callbacks.
+    fn sticky_registry() -> (CallbackRegistry<'static, CallbackPayload>, Log) {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.enable_sticky();
+        (registry, Rc::default())
+    }
+
+    /// Crée un callback qui note `label` et le premier byte des données dans `log`.
+    fn logging(log: &Log, label: &'static str) -> impl Fn(&CallbackPayload) + 'static {
+        let log = Rc::clone(log);
+        move |data: &CallbackPayload| log.borrow_mut().push((label, data.as_bytes()[0]))
+    }
+
+    /// Teste qu'un callback enregistré avant le premier appel n'est appelé qu'à cet appel.
+    #[test]
+    fn test_subscribe_before_first_event() {
+        let (mut registry, log) = sticky_registry();
+        registry.set_callback(logging(&log, "avant"));
+        assert!(log.borrow().is_empty());
+
+        registry.do_something();
+        assert_eq!(*log.borrow(), vec![("avant", 1)]);
+    }
+
+    /// Teste qu'un callback enregistré après un appel reçoit aussitôt les dernières données.
+    #[test]
+    fn test_subscribe_after_event() {
+        let (mut registry, log) = sticky_registry();
+        registry.do_something();
+        registry.set_data(vec![2]);
+        registry.do_something();
+
+        registry.set_callback(logging(&log, "après"));
+        assert_eq!(*log.borrow(), vec![("après", 2)]);
+
+        registry.set_data(vec![3]);
+        registry.do_something();
+        assert_eq!(*log.borrow(), vec![("après", 2), ("après", 3)]);
+    }
+
+    /// Teste qu'un callback unique satisfait n'est pas enregistré et qu'un callback filtré ne
+    /// reçoit les dernières données que si son prédicat les accepte.
+    #[test]
+    fn test_once_and_filtered_callbacks() {
+        let (mut registry, log) = sticky_registry();
+        registry.do_something();
+
+        registry.set_callback_once(logging(&log, "unique"));
+        assert_eq!(registry.callback_count(), 0);
+        registry.set_callback_filtered(
+            |data: &CallbackPayload| data.as_bytes()[0] > 1,
+            Callback::new(logging(&log, "filtré")),
+        );
+        assert_eq!(registry.callback_count(), 1);
+
+        registry.set_data(vec![5]);
+        registry.do_something();
+        assert_eq!(*log.borrow(), vec![("unique", 1), ("filtré", 5)]);
+
+        registry.disable_sticky();
+        registry.set_callback(logging(&log, "normal"));
+        assert_eq!(log.borrow().len(), 2);
+    }
+}
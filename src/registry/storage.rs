@@ -0,0 +1,343 @@
+//! Stockage des entrées d'un registre : les premières entrées sont rangées dans le registre
+//! lui-même, sans allocation, et le stockage ne passe sur le tas qu'au-delà.
+
+use std::ops::{Index, IndexMut};
+use std::{array, iter, mem, slice, vec};
+
+/// Nombre d'entrées stockées sans allocation.
+pub(crate) const INLINE_CAPACITY: usize = 2;
+
+/// Vecteur d'entrées, stockées dans le registre jusqu'à [`INLINE_CAPACITY`] puis sur le tas.
+///
+/// Les entrées en ligne occupent toujours les premières cases, dans l'ordre : `[Some, None]`
+/// pour une entrée, jamais `[None, Some]`. Une fois passé sur le tas, le vecteur y reste, même
+/// s'il redescend sous la capacité en ligne.
+pub(crate) enum CallbackVec<E> {
+    Inline([Option<E>; INLINE_CAPACITY]), // Entrées en ligne, suivies des cases libres.
+    Spilled(Vec<E>),                      // Entrées sur le tas.
+}
+
+impl<E> CallbackVec<E> {
+    /// Renvoie le nombre d'entrées.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            CallbackVec::Inline(slots) => slots.iter().take_while(|slot| slot.is_some()).count(),
+            CallbackVec::Spilled(entries) => entries.len(),
+        }
+    }
+
+    /// Renvoie le nombre d'entrées que le vecteur peut contenir sans allouer.
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        match self {
+            CallbackVec::Inline(_) => INLINE_CAPACITY,
+            CallbackVec::Spilled(entries) => entries.capacity(),
+        }
+    }
+
+    /// Indique si les entrées sont stockées sur le tas.
+    #[cfg(test)]
+    pub(crate) fn is_spilled(&self) -> bool {
+        matches!(self, CallbackVec::Spilled(_))
+    }
+
+    /// Renvoie l'entrée à la position `index`, si elle existe.
+    pub(crate) fn get(&self, index: usize) -> Option<&E> {
+        match self {
+            CallbackVec::Inline(slots) => slots.get(index)?.as_ref(),
+            CallbackVec::Spilled(entries) => entries.get(index),
+        }
+    }
+
+    /// Renvoie l'entrée à la position `index` en écriture, si elle existe.
+    pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut E> {
+        match self {
+            CallbackVec::Inline(slots) => slots.get_mut(index)?.as_mut(),
+            CallbackVec::Spilled(entries) => entries.get_mut(index),
+        }
+    }
+
+    /// Itère sur les entrées, dans l'ordre.
+    pub(crate) fn iter(&self) -> Iter<'_, E> {
+        match self {
+            CallbackVec::Inline(slots) => Iter::Inline(slots.iter().flatten()),
+            CallbackVec::Spilled(entries) => Iter::Spilled(entries.iter()),
+        }
+    }
+
+    /// Itère sur les entrées en écriture, dans l'ordre.
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, E> {
+        match self {
+            CallbackVec::Inline(slots) => IterMut::Inline(slots.iter_mut().flatten()),
+            CallbackVec::Spilled(entries) => IterMut::Spilled(entries.iter_mut()),
+        }
+    }
+
+    /// Passe sur le tas en réservant la place pour au moins `capacity` entrées.
+    fn spill(&mut self, capacity: usize) -> &mut Vec<E> {
+        if let CallbackVec::Inline(slots) = self {
+            let mut entries = Vec::with_capacity(capacity);
+            entries.extend(slots.iter_mut().map_while(Option::take));
+            *self = CallbackVec::Spilled(entries);
+        }
+        match self {
+            CallbackVec::Spilled(entries) => entries,
+            CallbackVec::Inline(_) => unreachable!("le vecteur vient de passer sur le tas"),
+        }
+    }
+
+    /// Réserve la place pour au moins `additional` entrées supplémentaires.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        let needed = self.len() + additional;
+        match self {
+            CallbackVec::Inline(_) if needed <= INLINE_CAPACITY => {}
+            CallbackVec::Inline(_) => {
+                self.spill(needed);
+            }
+            CallbackVec::Spilled(entries) => entries.reserve(additional),
+        }
+    }
+
+    /// Réserve la place pour exactement `additional` entrées supplémentaires, si elles ne tiennent
+    /// pas déjà.
+    pub(crate) fn reserve_exact(&mut self, additional: usize) {
+        let needed = self.len() + additional;
+        match self {
+            CallbackVec::Inline(_) if needed <= INLINE_CAPACITY => {}
+            CallbackVec::Inline(_) => {
+                self.spill(needed);
+            }
+            CallbackVec::Spilled(entries) => entries.reserve_exact(additional),
+        }
+    }
+
+    /// Insère `entry` à la position `index`, en décalant les entrées suivantes.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `index > len`.
+    pub(crate) fn insert(&mut self, index: usize, entry: E) {
+        let len = self.len();
+        assert!(index <= len, "position d'insertion {} hors limites", index);
+        match self {
+            CallbackVec::Inline(slots) if len < INLINE_CAPACITY => {
+                slots[len] = Some(entry);
+                slots[index..=len].rotate_right(1);
+            }
+            _ => self.spill(INLINE_CAPACITY * 2).insert(index, entry),
+        }
+    }
+
+    /// Ajoute `entry` après la dernière entrée.
+    pub(crate) fn push(&mut self, entry: E) {
+        self.insert(self.len(), entry);
+    }
+
+    /// Ne conserve que les entrées pour lesquelles `keep` renvoie `true`, dans le même ordre.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&E) -> bool) {
+        match self {
+            CallbackVec::Inline(slots) => {
+                let mut kept = 0;
+                for index in 0..INLINE_CAPACITY {
+                    match slots[index].take() {
+                        Some(entry) if keep(&entry) => {
+                            slots[kept] = Some(entry);
+                            kept += 1;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            CallbackVec::Spilled(entries) => entries.retain(keep),
+        }
+    }
+
+    /// Retire toutes les entrées ; un vecteur sur le tas conserve sa capacité.
+    pub(crate) fn clear(&mut self) {
+        match self {
+            CallbackVec::Inline(slots) => *slots = Default::default(),
+            CallbackVec::Spilled(entries) => entries.clear(),
+        }
+    }
+
+    /// Renvoie le nombre d'entrées en tête pour lesquelles `pred` renvoie `true`, les entrées
+    /// étant supposées partitionnées par `pred`.
+    pub(crate) fn partition_point(&self, pred: impl FnMut(&E) -> bool) -> usize {
+        match self {
+            CallbackVec::Inline(_) => {
+                let mut pred = pred;
+                self.iter().take_while(|entry| pred(entry)).count()
+            }
+            CallbackVec::Spilled(entries) => entries.partition_point(pred),
+        }
+    }
+
+    /// Retire toutes les entrées et les renvoie, dans l'ordre, en laissant le vecteur vide.
+    pub(crate) fn take(&mut self) -> Self {
+        mem::take(self)
+    }
+}
+
+impl<E> Default for CallbackVec<E> {
+    fn default() -> Self {
+        CallbackVec::Inline(Default::default())
+    }
+}
+
+impl<E> Index<usize> for CallbackVec<E> {
+    type Output = E;
+
+    fn index(&self, index: usize) -> &E {
+        self.get(index).expect("position hors limites")
+    }
+}
+
+impl<E> IndexMut<usize> for CallbackVec<E> {
+    fn index_mut(&mut self, index: usize) -> &mut E {
+        self.get_mut(index).expect("position hors limites")
+    }
+}
+
+/// Itérateur sur les entrées d'un [`CallbackVec`].
+pub(crate) enum Iter<'v, E> {
+    Inline(iter::Flatten<slice::Iter<'v, Option<E>>>),
+    Spilled(slice::Iter<'v, E>),
+}
+
+impl<'v, E> Iterator for Iter<'v, E> {
+    type Item = &'v E;
+
+    fn next(&mut self) -> Option<&'v E> {
+        match self {
+            Iter::Inline(inner) => inner.next(),
+            Iter::Spilled(inner) => inner.next(),
+        }
+    }
+}
+
+/// Itérateur sur les entrées d'un [`CallbackVec`], en écriture.
+pub(crate) enum IterMut<'v, E> {
+    Inline(iter::Flatten<slice::IterMut<'v, Option<E>>>),
+    Spilled(slice::IterMut<'v, E>),
+}
+
+impl<'v, E> Iterator for IterMut<'v, E> {
+    type Item = &'v mut E;
+
+    fn next(&mut self) -> Option<&'v mut E> {
+        match self {
+            IterMut::Inline(inner) => inner.next(),
+            IterMut::Spilled(inner) => inner.next(),
+        }
+    }
+}
+
+/// Itérateur qui consomme les entrées d'un [`CallbackVec`].
+pub(crate) enum IntoIter<E> {
+    Inline(iter::Flatten<array::IntoIter<Option<E>, INLINE_CAPACITY>>),
+    Spilled(vec::IntoIter<E>),
+}
+
+impl<E> Iterator for IntoIter<E> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        match self {
+            IntoIter::Inline(inner) => inner.next(),
+            IntoIter::Spilled(inner) => inner.next(),
+        }
+    }
+}
+
+impl<E> IntoIterator for CallbackVec<E> {
+    type Item = E;
+    type IntoIter = IntoIter<E>;
+
+    fn into_iter(self) -> IntoIter<E> {
+        match self {
+            CallbackVec::Inline(slots) => IntoIter::Inline(slots.into_iter().flatten()),
+            CallbackVec::Spilled(entries) => IntoIter::Spilled(entries.into_iter()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::{CallbackHost, CallbackRegistry};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Renvoie les entrées de `entries` dans l'ordre.
+    fn contents(entries: &CallbackVec<u32>) -> Vec<u32> {
+        entries.iter().copied().collect()
+    }
+
+    /// Teste le passage sur le tas à la troisième entrée, sans changer l'ordre.
+    #[test]
+    fn test_spill_on_third_insert() {
+        let mut entries = CallbackVec::default();
+        entries.insert(0, 2);
+        entries.insert(0, 1);
+        assert!(!entries.is_spilled());
+        assert_eq!(contents(&entries), vec![1, 2]);
+
+        entries.insert(1, 3);
+        assert!(entries.is_spilled());
+        assert_eq!(contents(&entries), vec![1, 3, 2]);
+        assert_eq!(entries.len(), 3);
+    }
+
+    /// Teste que le filtrage garde les entrées en ligne en tête, dans l'ordre.
+    #[test]
+    fn test_inline_retain_keeps_order() {
+        let mut entries = CallbackVec::default();
+        entries.push(1);
+        entries.push(2);
+        entries.retain(|entry| *entry != 1);
+        assert_eq!(contents(&entries), vec![2]);
+        entries.push(3);
+        assert_eq!(entries[1], 3);
+        entries.clear();
+        assert_eq!(entries.len(), 0);
+        assert!(!entries.is_spilled());
+    }
+
+    /// Teste que `partition_point` donne le même résultat en ligne et sur le tas.
+    #[test]
+    fn test_partition_point_in_both_modes() {
+        let mut entries = CallbackVec::default();
+        entries.insert(0, 1);
+        entries.insert(1, 5);
+        assert_eq!(entries.partition_point(|entry| *entry <= 3), 1);
+        entries.reserve(4);
+        assert!(entries.is_spilled());
+        assert_eq!(entries.partition_point(|entry| *entry <= 3), 1);
+        assert_eq!(entries.into_iter().collect::<Vec<_>>(), vec![1, 5]);
+    }
+
+    /// Teste qu'un registre se comporte de même avant et après le passage de 2 à 3 callbacks.
+    #[test]
+    fn test_registry_across_spill_boundary() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let mut ids = Vec::new();
+        for tag in 0..3 {
+            let order = Rc::clone(&order);
+            ids.push(
+                registry.set_callback(move |_data: &CallbackPayload| order.borrow_mut().push(tag)),
+            );
+            assert_eq!(registry.callbacks.is_spilled(), tag == 2);
+        }
+        registry.do_something();
+        assert_eq!(*order.borrow(), vec![0, 1, 2]);
+
+        assert!(registry.remove_callback(ids[1]));
+        assert!(!registry.remove_callback(ids[1]));
+        order.borrow_mut().clear();
+        registry.do_something();
+        assert_eq!(*order.borrow(), vec![0, 2]);
+        assert_eq!(registry.callback_count(), 2);
+    }
+}
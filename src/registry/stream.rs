@@ -0,0 +1,197 @@
+//! Flux des données transmises, pour les consommateurs asynchrones (feature `async`).
+
+use std::future::poll_fn;
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::TrySendError;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use super::dispatcher::Queue;
+use super::{Backpressure, CallbackRegistry, Entry};
+use crate::callback::Callback;
+use crate::data::ArcCallbackPayload;
+
+/// Flux des données transmises aux callbacks d'un registre, créé par
+/// [`CallbackRegistry::subscribe_stream`].
+///
+/// Le flux implémente [`Stream`], et s'utilise donc avec tout consommateur de flux ; détruire
+/// le flux retire le callback qui l'alimente. Le flux se termine quand ce callback est retiré ou
+/// que le registre est détruit, une fois les données en attente lues.
+#[derive(Debug)]
+pub struct EventStream {
+    queue: Arc<Queue<Arc<[u8]>>>, // File alimentée par le callback du registre.
+    detached: Arc<AtomicBool>,    // Levé à la destruction du flux, pour retirer le callback.
+}
+
+impl Stream for EventStream {
+    type Item = Arc<[u8]>;
+
+    /// Renvoie la donnée suivante si elle est arrivée, `Ready(None)` si le flux est terminé, et
+    /// sinon `Pending` en réveillant la tâche de `cx` à la prochaine donnée.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Arc<[u8]>>> {
+        self.queue.poll_pop(cx.waker())
+    }
+}
+
+impl EventStream {
+    /// Attend la donnée suivante, ou renvoie `None` une fois le flux terminé.
+    pub async fn next(&mut self) -> Option<Arc<[u8]>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Renvoie le nombre de données oubliées parce que la file du flux était pleine.
+    pub fn dropped(&self) -> u64 {
+        self.queue.lock().dropped
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.detached.store(true, Ordering::Release);
+        self.queue.disconnect();
+    }
+}
+
+/// Ferme la file du flux quand le callback qui l'alimente est détruit.
+struct StreamSender(Arc<Queue<Arc<[u8]>>>);
+
+impl Drop for StreamSender {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+impl<'a> CallbackRegistry<'a, ArcCallbackPayload> {
+    /// Enregistre un callback qui place chaque donnée reçue dans le flux renvoyé, sans borne.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{ArcCallbackPayload, CallbackHost, CallbackRegistry};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut registry: CallbackRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+    /// let mut stream = registry.subscribe_stream();
+    /// registry.dispatch(&[1, 2]).unwrap();
+    /// assert_eq!(stream.next().await.as_deref(), Some(&[1, 2][..]));
+    /// # });
+    /// ```
+    pub fn subscribe_stream(&mut self) -> EventStream {
+        self.subscribe_with_queue(None, Backpressure::default())
+    }
+
+    /// Comme [`subscribe_stream`](Self::subscribe_stream), mais la file du flux garde au plus
+    /// `capacity` données et applique `policy` aux données de trop.
+    ///
+    /// Avec [`Backpressure::Fail`], le callback ne peut pas renvoyer l'erreur : la donnée est
+    /// oubliée et comptée par [`EventStream::dropped`]. Avec [`Backpressure::Block`], l'appel
+    /// des callbacks attend que le flux soit lu, ce qui ne se produit que si le flux est lu
+    /// depuis un autre thread ; la capacité est non nulle pour que cette attente puisse finir.
+    pub fn subscribe_stream_bounded(
+        &mut self,
+        capacity: NonZeroUsize,
+        policy: Backpressure,
+    ) -> EventStream {
+        self.subscribe_with_queue(Some(capacity), policy)
+    }
+
+    /// Enregistre le callback d'un flux dont la file garde au plus `capacity` données.
+    fn subscribe_with_queue(
+        &mut self,
+        capacity: Option<NonZeroUsize>,
+        policy: Backpressure,
+    ) -> EventStream {
+        let queue = Arc::new(Queue::new(capacity, policy));
+        let detached = Arc::new(AtomicBool::new(false));
+        let sender = StreamSender(Arc::clone(&queue));
+        let cb = Callback::new(move |data: &ArcCallbackPayload| {
+            if let Err(TrySendError::Full(_)) = sender.0.push(data.to_arc()) {
+                sender.0.lock().dropped += 1;
+            }
+        });
+        let flag = Arc::clone(&detached);
+        self.push_entry(|id| Entry::new(id, cb).detached_by(flag));
+        EventStream { queue, detached }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::time::Duration;
+
+    /// Lit le flux jusqu'à sa fin.
+    async fn collect(mut stream: EventStream) -> Vec<Vec<u8>> {
+        let mut items = Vec::new();
+        while let Some(item) = stream.next().await {
+            items.push(item.to_vec());
+        }
+        items
+    }
+
+    /// Teste que trois données transmises donnent exactement trois éléments du flux.
+    #[tokio::test]
+    async fn test_three_events_three_items() {
+        let mut registry: CallbackRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+        let stream = registry.subscribe_stream();
+        for value in 1..=3 {
+            registry.dispatch(&[value]).unwrap();
+        }
+        drop(registry);
+        assert_eq!(collect(stream).await, vec![vec![1], vec![2], vec![3]]);
+    }
+
+    /// Teste qu'un flux vide attend, et que le détruire retire son callback.
+    #[tokio::test]
+    async fn test_drop_stream_unregisters_callback() {
+        let mut registry: CallbackRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+        let mut stream = registry.subscribe_stream();
+        let waited = tokio::time::timeout(Duration::from_millis(10), stream.next()).await;
+        assert!(waited.is_err());
+        assert_eq!(registry.callback_count(), 1);
+
+        drop(stream);
+        assert_eq!(registry.callback_count(), 0);
+        registry.dispatch(&[1]).unwrap();
+    }
+
+    /// Teste qu'une file bornée oublie la donnée la plus ancienne et la compte.
+    #[tokio::test]
+    async fn test_bounded_stream_drops_oldest() {
+        let mut registry: CallbackRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+        let stream = registry
+            .subscribe_stream_bounded(NonZeroUsize::new(2).unwrap(), Backpressure::DropOldest);
+        for value in 1..=3 {
+            registry.dispatch(&[value]).unwrap();
+        }
+        assert_eq!(stream.dropped(), 1);
+        drop(registry);
+        assert_eq!(collect(stream).await, vec![vec![2], vec![3]]);
+    }
+
+    /// Teste qu'un flux d'une seule place avec `Block`, lu depuis un autre thread, reçoit
+    /// toutes les données.
+    #[test]
+    fn test_capacity_one_blocks_until_read() {
+        let mut registry: CallbackRegistry<ArcCallbackPayload> = CallbackRegistry::new();
+        let one = NonZeroUsize::new(1).unwrap();
+        let stream = registry.subscribe_stream_bounded(one, Backpressure::Block);
+        let reader = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap();
+            runtime.block_on(collect(stream))
+        });
+        for value in 0..20 {
+            registry.dispatch(&[value]).unwrap();
+        }
+        drop(registry);
+        let expected: Vec<Vec<u8>> = (0..20).map(|value| vec![value]).collect();
+        assert_eq!(reader.join().unwrap(), expected);
+    }
+}
@@ -0,0 +1,108 @@
+//! Callbacks exécutés sur un thread auxiliaire, abandonnés s'ils dépassent leur délai.
+
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackId};
+use crate::data::ArcCallbackPayload;
+use crate::error::CallbackError;
+
+impl<'a> CallbackRegistry<'a, ArcCallbackPayload, [u8], Result<(), CallbackError>> {
+    /// Enregistre `f`, exécuté à chaque appel sur un nouveau thread auxiliaire et attendu au plus
+    /// `timeout`.
+    ///
+    /// Passé ce délai, l'appel renvoie [`CallbackError::TimedOut`] et les callbacks suivants sont
+    /// appelés sans attendre : le thread abandonné est détaché, pas interrompu, et son résultat est
+    /// ignoré. Une panique de `f` est signalée comme un échec du callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rust_reven::{ArcCallbackPayload, CallbackError, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_owned_data(vec![1u8, 2, 3]);
+    /// let id = registry.set_callback_with_timeout(Duration::from_millis(10), |_data: &ArcCallbackPayload| {
+    ///     std::thread::sleep(Duration::from_millis(100)); // E/S bloquante accidentelle.
+    ///     Ok(())
+    /// });
+    /// assert_eq!(registry.try_do_something(), Err(vec![(id, CallbackError::TimedOut(id))]));
+    /// ```
+    pub fn set_callback_with_timeout(
+        &mut self,
+        timeout: Duration,
+        f: impl Fn(&ArcCallbackPayload) -> Result<(), CallbackError> + Send + Sync + 'static,
+    ) -> CallbackId {
+        let f = Arc::new(f);
+        self.push_entry(|id| {
+            let cb = Callback::new(move |data: &ArcCallbackPayload| {
+                let (sender, receiver) = mpsc::channel();
+                let f = Arc::clone(&f);
+                let data = data.clone();
+                // Le thread est détaché : s'il dépasse le délai, il se termine seul et son
+                // résultat est perdu avec le récepteur.
+                thread::spawn(move || {
+                    let _ = sender.send(f(&data));
+                });
+                match receiver.recv_timeout(timeout) {
+                    Ok(outcome) => outcome,
+                    Err(mpsc::RecvTimeoutError::Timeout) => Err(CallbackError::TimedOut(id)),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        Err(CallbackError::new("le callback a paniqué"))
+                    }
+                }
+            });
+            Entry::new(id, cb)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Instant;
+
+    /// Teste qu'un callback de 200 ms est abandonné après 50 ms et que le suivant est appelé à temps.
+    #[test]
+    fn test_slow_callback_times_out() {
+        let later_ran = Arc::new(AtomicBool::new(false));
+        let later_ran_in_cb = Arc::clone(&later_ran);
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        let slow = registry.set_callback_with_timeout(Duration::from_millis(50), |_data| {
+            thread::sleep(Duration::from_millis(200));
+            Ok(())
+        });
+        registry.set_callback(Callback::new(move |_data: &ArcCallbackPayload| {
+            later_ran_in_cb.store(true, Ordering::SeqCst);
+            Ok(())
+        }));
+
+        let start = Instant::now();
+        let result = registry.try_do_something();
+
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert_eq!(result, Err(vec![(slow, CallbackError::TimedOut(slow))]));
+        assert!(later_ran.load(Ordering::SeqCst));
+    }
+
+    /// Teste qu'un callback rapide renvoie son résultat comme un callback ordinaire.
+    #[test]
+    fn test_fast_callback_returns_its_outcome() {
+        let mut registry = CallbackRegistry::with_owned_data(vec![4u8, 2]);
+        let id = registry.set_callback_with_timeout(Duration::from_secs(5), |data| {
+            if data.as_bytes().len() == 2 {
+                return Err(CallbackError::new("trame trop courte"));
+            }
+            Ok(())
+        });
+
+        assert_eq!(
+            registry.try_do_something(),
+            Err(vec![(id, CallbackError::new("trame trop courte"))])
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Détection des callbacks lents, qui dépassent une durée configurable.
+
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::callback::{CallbackData, CallbackId};
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+
+/// Callbacks plus lents que le seuil, avec leur durée d'exécution.
+type SlowCallbacks = Vec<(CallbackId, Duration)>;
+
+/// Appelle `entry` en mesurant la durée de l'appel.
+fn invoke_timed<T: CallbackData + ?Sized, R>(
+    entry: &Entry<T, R>,
+    data: &T,
+) -> Option<(R, Duration)> {
+    let start = Instant::now();
+    let result = entry.invoke(data)?;
+    Some((result, start.elapsed()))
+}
+
+/// Sink de `dispatch_with` qui conserve dans `slow` les callbacks plus lents que `threshold`.
+fn collect_slow<R>(
+    slow: &mut SlowCallbacks,
+    threshold: Duration,
+) -> impl FnMut(CallbackId, (R, Duration)) -> ControlFlow<()> + '_ {
+    move |id, (_, elapsed)| {
+        if elapsed > threshold {
+            slow.push((id, elapsed));
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Signale par `dispatch_timed` les callbacks dont l'exécution dure plus de `threshold`.
+    pub fn set_slow_threshold(&mut self, threshold: Duration) {
+        self.slow_threshold = Some(threshold);
+    }
+
+    /// Désactive la mesure des callbacks : `dispatch_timed` se comporte comme `do_something`.
+    pub fn clear_slow_threshold(&mut self) {
+        self.slow_threshold = None;
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Comme `do_something`, mais mesure chaque callback et renvoie, dans l'ordre d'appel, ceux
+    /// qui ont dépassé le seuil fixé par [`set_slow_threshold`](Self::set_slow_threshold).
+    ///
+    /// Sans seuil, aucun callback n'est mesuré et le rapport est vide.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+    /// registry.set_slow_threshold(Duration::from_millis(5));
+    /// let slow = registry.set_callback(|_data: &CallbackPayload| std::thread::sleep(Duration::from_millis(20)));
+    /// registry.set_callback(|_data: &CallbackPayload| {});
+    ///
+    /// let report = registry.dispatch_timed();
+    /// assert_eq!(report.len(), 1);
+    /// assert_eq!(report[0].0, slow);
+    /// ```
+    pub fn dispatch_timed(&self) -> SlowCallbacks {
+        let mut slow = Vec::new();
+        match self.slow_threshold {
+            Some(threshold) => {
+                self.dispatch_with(|_| true, invoke_timed, collect_slow(&mut slow, threshold))
+            }
+            None => self.dispatch_where(|_| true, ignore_result),
+        }
+        slow
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Comme `do_something`, mais renvoie les callbacks qui ont dépassé le seuil de lenteur.
+    ///
+    /// Sans seuil, aucun callback n'est mesuré et le rapport est vide.
+    pub fn dispatch_timed(&self) -> SlowCallbacks {
+        let mut slow = Vec::new();
+        match self.slow_threshold {
+            Some(threshold) => {
+                self.dispatch_with(|_| true, invoke_timed, collect_slow(&mut slow, threshold))
+            }
+            None => self.dispatch_where(|_| true, ignore_result),
+        }
+        slow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::thread;
+
+    /// Teste que seul le callback qui dort plus longtemps que le seuil est signalé.
+    #[test]
+    fn test_sleeping_callback_is_reported() {
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_slow_threshold(Duration::from_millis(10));
+        registry.set_callback(|_data: &CallbackPayload| {});
+        let slow = registry
+            .set_callback(|_data: &CallbackPayload| thread::sleep(Duration::from_millis(30)));
+
+        let report = registry.dispatch_timed();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].0, slow);
+        assert!(report[0].1 >= Duration::from_millis(30));
+    }
+
+    /// Teste que sans seuil, les callbacks sont appelés mais rien n'est signalé.
+    #[test]
+    fn test_no_threshold_reports_nothing() {
+        let calls = Rc::new(Cell::new(0));
+        let calls_in_cb = Rc::clone(&calls);
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_slow_threshold(Duration::ZERO);
+        registry.clear_slow_threshold();
+        registry.set_callback(move |_data: &ArcCallbackPayload| {
+            thread::sleep(Duration::from_millis(1));
+            calls_in_cb.set(calls_in_cb.get() + 1);
+        });
+
+        assert!(registry.dispatch_timed().is_empty());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -0,0 +1,206 @@
+//! Activation et désactivation des callbacks sans les retirer du registre, et pause du registre entier.
+
+use super::CallbackRegistry;
+use crate::callback::{CallbackData, CallbackId};
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Désactive le callback `id` : `do_something` l'ignore jusqu'à `enable_callback`.
+    ///
+    /// Le callback garde sa place dans l'ordre d'appel et reste compté par `callback_count`.
+    /// Renvoie `false` si `id` est inconnu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// let noisy = registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    /// registry.disable_callback(noisy);
+    /// registry.do_something(); // N'affiche rien.
+    /// assert_eq!(registry.callback_count(), 1);
+    /// assert_eq!(registry.active_callback_count(), 0);
+    /// registry.enable_callback(noisy);
+    /// ```
+    pub fn disable_callback(&mut self, id: CallbackId) -> bool {
+        self.set_enabled(id, false)
+    }
+
+    /// Réactive le callback `id`, à sa place d'origine dans l'ordre d'appel.
+    /// Renvoie `false` si `id` est inconnu.
+    pub fn enable_callback(&mut self, id: CallbackId) -> bool {
+        self.set_enabled(id, true)
+    }
+
+    /// Indique si le callback `id` est enregistré et activé.
+    pub fn is_callback_enabled(&self, id: CallbackId) -> bool {
+        self.active_entries().any(|entry| entry.id == id) || self.is_periodic_enabled(id)
+    }
+
+    /// Renvoie le nombre de callbacks activés, que `do_something` appellera.
+    pub fn active_callback_count(&self) -> usize {
+        self.active_entries().count()
+    }
+
+    /// Met le registre en pause : `do_something` n'appelle plus aucun callback jusqu'à `resume`.
+    ///
+    /// Les appels ignorés pendant la pause sont comptés, voir [`resume_and_report`](Self::resume_and_report).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{Callback, CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3][..]);
+    /// registry.set_callback(Callback::new(|data: &CallbackPayload| println!("{:?}", data)));
+    /// registry.pause();
+    /// registry.do_something(); // Ignoré.
+    /// registry.do_something(); // Ignoré.
+    /// assert_eq!(registry.resume_and_report(), 2);
+    /// assert!(!registry.is_paused());
+    /// ```
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Sort de la pause ; les appels ignorés ne sont pas rejoués.
+    pub fn resume(&mut self) {
+        self.resume_and_report();
+    }
+
+    /// Sort de la pause et renvoie le nombre d'appels à `do_something` ignorés depuis `pause`.
+    pub fn resume_and_report(&mut self) -> usize {
+        self.paused = false;
+        self.dropped.take()
+    }
+
+    /// Indique si le registre est en pause.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Indique si un appel à `do_something` doit appeler les callbacks ; compte l'appel comme ignoré sinon.
+    pub(crate) fn begin_dispatch(&self) -> bool {
+        if self.paused {
+            self.dropped.set(self.dropped.get() + 1);
+        }
+        !self.paused
+    }
+
+    // Modifie l'état d'activation du callback `id`.
+    fn set_enabled(&mut self, id: CallbackId, enabled: bool) -> bool {
+        match self.entry_mut(id) {
+            Some(entry) => {
+                entry.enabled = enabled;
+                true
+            }
+            None => self.set_periodic_enabled(id, enabled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Teste qu'un callback désactivé est ignoré, puis rappelé à sa place une fois réactivé.
+    #[test]
+    fn test_disabled_callback_is_skipped_until_enabled() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("first"));
+        let calls_in_cb = Rc::clone(&calls);
+        let noisy = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("noisy"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("last"));
+
+        assert!(registry.disable_callback(noisy));
+        assert!(!registry.is_callback_enabled(noisy));
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["first", "last"]);
+
+        calls.borrow_mut().clear();
+        assert!(registry.enable_callback(noisy));
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["first", "noisy", "last"]);
+    }
+
+    /// Teste que les callbacks désactivés restent comptés par `callback_count` mais pas par `active_callback_count`.
+    #[test]
+    fn test_disabled_callback_counts() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b"));
+
+        registry.disable_callback(id);
+
+        assert_eq!(registry.callback_count(), 2);
+        assert_eq!(registry.active_callback_count(), 1);
+    }
+
+    /// Teste qu'un identifiant inconnu ou retiré est signalé par `false`.
+    #[test]
+    fn test_toggle_unknown_id() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        let id = registry
+            .set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        registry.remove_callback(id);
+
+        assert!(!registry.disable_callback(id));
+        assert!(!registry.enable_callback(id));
+    }
+
+    /// Teste l'enchaînement pause → appel → reprise → appel.
+    #[test]
+    fn test_pause_then_resume() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("b"));
+
+        registry.pause();
+        assert!(registry.is_paused());
+        registry.do_something();
+        assert!(calls.borrow().is_empty());
+
+        registry.resume();
+        assert!(!registry.is_paused());
+        registry.do_something();
+        assert_eq!(*calls.borrow(), vec!["a", "b"]);
+    }
+
+    /// Teste que `resume_and_report` compte les appels ignorés, puis repart de zéro.
+    #[test]
+    fn test_resume_and_report_counts_dropped_dispatches() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &CallbackPayload| calls_in_cb.borrow_mut().push("a"));
+
+        registry.pause();
+        for _ in 0..3 {
+            registry.do_something();
+        }
+        assert_eq!(registry.resume_and_report(), 3);
+
+        registry.do_something();
+        registry.pause();
+        assert_eq!(registry.resume_and_report(), 0);
+        assert_eq!(*calls.borrow(), vec!["a"]);
+    }
+}
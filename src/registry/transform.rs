@@ -0,0 +1,168 @@
+//! Transformations appliquées aux données une fois par appel, avant les callbacks.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use super::CallbackRegistry;
+use crate::callback::CallbackData;
+use crate::error::ValidationError;
+
+/// Fonction d'une transformation faillible.
+type TransformFn = Box<dyn Fn(&[u8]) -> Result<Vec<u8>, ValidationError>>;
+
+/// Transformation des données, voir [`CallbackRegistry::add_transform`].
+///
+/// Toute closure `Fn(&[u8]) -> Vec<u8>` se convertit en `Transform` ; une transformation qui peut
+/// échouer se crée avec [`Transform::fallible`].
+pub struct Transform {
+    f: TransformFn, // La transformation, qui peut refuser les données.
+}
+
+impl Transform {
+    /// Crée une transformation qui n'échoue jamais.
+    pub fn new(f: impl Fn(&[u8]) -> Vec<u8> + 'static) -> Self {
+        Transform::fallible(move |data: &[u8]| Ok(f(data)))
+    }
+
+    /// Crée une transformation qui peut refuser les données : son erreur est alors traitée comme
+    /// celle d'un validateur, et aucun callback n'est appelé.
+    pub fn fallible(f: impl Fn(&[u8]) -> Result<Vec<u8>, ValidationError> + 'static) -> Self {
+        Transform { f: Box::new(f) }
+    }
+
+    /// Applique la transformation à `data`.
+    pub fn apply(&self, data: &[u8]) -> Result<Vec<u8>, ValidationError> {
+        (self.f)(data)
+    }
+}
+
+impl<F: Fn(&[u8]) -> Vec<u8> + 'static> From<F> for Transform {
+    fn from(f: F) -> Self {
+        Transform::new(f)
+    }
+}
+
+impl fmt::Debug for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transform").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Ajoute `f` aux transformations, appliquées dans leur ordre d'enregistrement aux données
+    /// validées. Les callbacks et le traitement des données reçoivent le résultat de la dernière ; les
+    /// données du registre ne sont pas modifiées.
+    ///
+    /// Sans transformation, les données sont transmises sans copie. Une transformation qui échoue
+    /// refuse les données comme un validateur, voir [`set_on_invalid`](Self::set_on_invalid).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[0xAA, 0xBB, 1, 2][..]);
+    /// registry.add_transform(|data: &[u8]| data[2..].to_vec()); // Retire l'en-tête.
+    /// registry.set_callback(|data: &CallbackPayload| assert_eq!(data.as_bytes(), &[1, 2]));
+    /// registry.do_something();
+    /// assert_eq!(registry.data(), &[0xAA, 0xBB, 1, 2]);
+    /// ```
+    pub fn add_transform(&mut self, f: impl Into<Transform>) {
+        self.transforms.push(f.into());
+    }
+
+    /// Retire toutes les transformations.
+    pub fn clear_transforms(&mut self) {
+        self.transforms.clear();
+    }
+
+    /// Applique les transformations à `data`, ou l'emprunte tel quel s'il n'y en a aucune.
+    /// S'arrête à la première qui échoue.
+    pub(crate) fn transform<'d>(&self, data: &'d [u8]) -> Result<Cow<'d, [u8]>, ValidationError> {
+        self.transforms
+            .iter()
+            .try_fold(Cow::Borrowed(data), |data, transform| {
+                transform.apply(&data).map(Cow::Owned)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ArcCallbackPayload, CallbackPayload};
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Retire l'en-tête de deux bytes.
+    fn strip_header(data: &[u8]) -> Vec<u8> {
+        data.get(2..).unwrap_or_default().to_vec()
+    }
+
+    /// Remplace le premier byte par zéro.
+    fn mask_first(data: &[u8]) -> Vec<u8> {
+        let mut data = data.to_vec();
+        if let Some(first) = data.first_mut() {
+            *first = 0;
+        }
+        data
+    }
+
+    /// Teste que deux transformations s'enchaînent dans l'ordre sans toucher aux données du registre.
+    #[test]
+    fn test_chained_transforms() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = CallbackRegistry::with_owned_data(vec![0xAAu8, 0xBB, 7, 8, 9]);
+        registry.add_transform(strip_header);
+        registry.add_transform(mask_first);
+        for _ in 0..2 {
+            let seen = Rc::clone(&seen);
+            registry.set_callback(move |data: &ArcCallbackPayload| {
+                seen.borrow_mut().push(data.as_bytes().to_vec())
+            });
+        }
+
+        registry.do_something();
+
+        assert_eq!(*seen.borrow(), vec![vec![0, 8, 9], vec![0, 8, 9]]);
+        assert_eq!(registry.data(), &[0xAA, 0xBB, 7, 8, 9]);
+    }
+
+    /// Teste que l'ordre des transformations compte et que sans transformation rien n'est copié.
+    #[test]
+    fn test_order_matters_and_empty_pipeline_borrows() {
+        let bytes = [0xAAu8, 0xBB, 7, 8];
+        let mut registry: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_data(&bytes[..]);
+        assert!(matches!(registry.transform(&bytes), Ok(Cow::Borrowed(_))));
+
+        registry.add_transform(mask_first);
+        registry.add_transform(strip_header);
+        assert_eq!(registry.transform(&bytes).unwrap().as_ref(), &[7, 8]);
+
+        registry.clear_transforms();
+        assert_eq!(registry.transform(&bytes).unwrap().as_ref(), &bytes);
+    }
+
+    /// Teste qu'une transformation qui échoue refuse les données comme un validateur.
+    #[test]
+    fn test_failing_transform_reports_invalid() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'_, CallbackPayload> =
+            CallbackRegistry::with_data(&[1u8, 2][..]);
+        registry.add_transform(Transform::fallible(|data: &[u8]| match data.len() {
+            0..=2 => Err(ValidationError::new("trame trop courte")),
+            _ => Ok(data.to_vec()),
+        }));
+        let errors_in_handler = Rc::clone(&errors);
+        registry.set_on_invalid(move |error: &ValidationError| {
+            errors_in_handler.borrow_mut().push(error.reason.clone())
+        });
+        registry.set_callback(|_data: &CallbackPayload| panic!("données refusées transmises"));
+
+        registry.do_something();
+        assert_eq!(*errors.borrow(), vec!["trame trop courte"]);
+        assert!(registry.try_dispatch().is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! Validation des données avant l'appel des callbacks.
+
+use std::borrow::Cow;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use super::{ignore_result, CallbackRegistry, Entry};
+use crate::callback::{CallbackData, CallbackId};
+use crate::data::{ArcCallbackPayload, CallbackPayload, Checksum};
+use crate::error::ValidationError;
+
+/// Validateur des données, voir [`CallbackRegistry::set_validator`].
+pub(crate) type Validator = Box<dyn Fn(&[u8]) -> Result<(), ValidationError>>;
+
+/// Reçoit les erreurs des validateurs, voir [`CallbackRegistry::set_on_invalid`].
+pub(crate) type InvalidHandler = Box<dyn Fn(&ValidationError)>;
+
+impl<'a, T: CallbackData + ?Sized, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Ajoute `f` aux validateurs, appelés avant les callbacks dans leur ordre d'enregistrement.
+    ///
+    /// Dès qu'un validateur refuse les données, les suivants ne sont pas appelés et aucun callback
+    /// ne l'est : l'erreur est transmise au gestionnaire de [`set_on_invalid`](Self::set_on_invalid),
+    /// ou renvoyée par `try_dispatch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, ValidationError};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[0u8, 1][..]);
+    /// registry.set_validator(|data: &[u8]| match data.first() {
+    ///     Some(0x7e) => Ok(()),
+    ///     _ => Err(ValidationError::new("byte magique absent")),
+    /// });
+    /// registry.set_callback(|_data: &CallbackPayload| unreachable!());
+    /// assert_eq!(
+    ///     registry.try_dispatch(),
+    ///     Err(ValidationError::new("byte magique absent"))
+    /// );
+    /// ```
+    pub fn set_validator(&mut self, f: impl Fn(&[u8]) -> Result<(), ValidationError> + 'static) {
+        self.validators.push(Box::new(f));
+    }
+
+    /// Retire tous les validateurs.
+    pub fn clear_validators(&mut self) {
+        self.validators.clear();
+    }
+
+    /// Transmet à `f` les erreurs des validateurs lors de `do_something`, à la place de l'ancien
+    /// gestionnaire.
+    pub fn set_on_invalid(&mut self, f: impl Fn(&ValidationError) + 'static) {
+        self.on_invalid = Some(Box::new(f));
+    }
+
+    /// Vérifie, avant chaque appel, la somme de contrôle `algo` qui termine les données ; les
+    /// callbacks et les validateurs ne reçoivent que les données qui la précèdent.
+    ///
+    /// Une somme de contrôle absente ou fausse refuse les données comme un validateur.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, Checksum};
+    ///
+    /// let mut registry = CallbackRegistry::with_data(&[1u8, 2, 3, 6][..]);
+    /// registry.verify_checksum(Checksum::Additive);
+    /// registry.set_callback(|data: &CallbackPayload| assert_eq!(data.as_bytes(), &[1, 2, 3]));
+    /// assert!(registry.try_dispatch().is_ok());
+    /// ```
+    pub fn verify_checksum(&mut self, algo: Checksum) {
+        self.checksum = Some(algo);
+    }
+
+    /// Ne vérifie plus de somme de contrôle : les callbacks reçoivent de nouveau les données entières.
+    pub fn clear_checksum(&mut self) {
+        self.checksum = None;
+    }
+
+    /// Vérifie la somme de contrôle de `data`, puis appelle les validateurs sur les données
+    /// qu'elle termine, jusqu'au premier qui les refuse. Renvoie les données à transmettre.
+    pub(crate) fn validate<'d>(&self, data: &'d [u8]) -> Result<&'d [u8], ValidationError> {
+        let data = match self.checksum {
+            Some(algo) => algo.verify(data)?,
+            None => data,
+        };
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator(data))?;
+        Ok(data)
+    }
+
+    /// Transmet `error` au gestionnaire des données refusées, s'il y en a un.
+    pub(crate) fn report_invalid(&self, error: ValidationError) {
+        if let Some(on_invalid) = &self.on_invalid {
+            on_invalid(&error);
+        }
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Comme `do_something`, mais renvoie l'erreur du premier validateur qui refuse les données
+    /// au lieu de la transmettre au gestionnaire de `set_on_invalid`.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie la [`ValidationError`] du validateur ; aucun callback n'a alors été appelé.
+    pub fn try_dispatch(&self) -> Result<(), ValidationError> {
+        self.dispatch_checked(|_| true, Entry::invoke, ignore_result, false)
+    }
+
+    /// Valide et transforme les données, puis appelle les callbacks comme `dispatch_with` si elles
+    /// ont changé ou si `force` est vrai.
+    pub(crate) fn dispatch_checked<V>(
+        &self,
+        select: impl Fn(&Entry<CallbackPayload, R>) -> bool,
+        invoke: impl Fn(&Entry<CallbackPayload, R>, &CallbackPayload) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+        force: bool,
+    ) -> Result<(), ValidationError> {
+        self.dispatch_bytes(self.data.get().as_ref(), select, invoke, sink, force)
+    }
+
+    /// Comme `dispatch_checked`, mais sur les bytes `raw` au lieu des données du registre.
+    pub(crate) fn dispatch_bytes<V>(
+        &self,
+        raw: &[u8],
+        select: impl Fn(&Entry<CallbackPayload, R>) -> bool,
+        invoke: impl Fn(&Entry<CallbackPayload, R>, &CallbackPayload) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+        force: bool,
+    ) -> Result<(), ValidationError> {
+        if !self.begin_dispatch() || !self.admit_payload_size(raw.len()) || !self.admit_rate(raw) {
+            return Ok(());
+        }
+        let data = self.transform(self.validate(raw)?)?;
+        self.observe_sequence(&data);
+        if !self.record_change(&data, force)
+            || !self.dedup_dispatch(&data, force)
+            || !self.sample_dispatch()
+        {
+            return Ok(());
+        }
+        self.record_history(&data);
+        self.record_sticky(&data);
+        self.dispatch_processed(CallbackPayload::new(&data), &data, select, invoke, sink);
+        Ok(())
+    }
+}
+
+impl<'a, R> CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    /// Comme `do_something`, mais renvoie l'erreur du premier validateur qui refuse les données
+    /// au lieu de la transmettre au gestionnaire de `set_on_invalid`.
+    ///
+    /// # Errors
+    ///
+    /// Renvoie la [`ValidationError`] du validateur ; aucun callback n'a alors été appelé.
+    pub fn try_dispatch(&self) -> Result<(), ValidationError> {
+        self.dispatch_checked(|_| true, Entry::invoke, ignore_result, false)
+    }
+
+    /// Valide et transforme les données, puis appelle les callbacks comme `dispatch_with` si elles
+    /// ont changé ou si `force` est vrai.
+    pub(crate) fn dispatch_checked<V>(
+        &self,
+        select: impl Fn(&Entry<ArcCallbackPayload, R>) -> bool,
+        invoke: impl Fn(&Entry<ArcCallbackPayload, R>, &ArcCallbackPayload) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+        force: bool,
+    ) -> Result<(), ValidationError> {
+        let data = self.data.get();
+        self.dispatch_shared(data, || self.data.to_arc(), select, invoke, sink, force)
+    }
+
+    /// Comme `dispatch_checked`, mais sur les bytes `raw` au lieu des données du registre ;
+    /// `share` les partage sans copie quand ni somme de contrôle ni transformation ne les modifie.
+    pub(crate) fn dispatch_shared<V>(
+        &self,
+        raw: &[u8],
+        share: impl FnOnce() -> Arc<[u8]>,
+        select: impl Fn(&Entry<ArcCallbackPayload, R>) -> bool,
+        invoke: impl Fn(&Entry<ArcCallbackPayload, R>, &ArcCallbackPayload) -> Option<V>,
+        sink: impl FnMut(CallbackId, V) -> ControlFlow<()>,
+        force: bool,
+    ) -> Result<(), ValidationError> {
+        if let Some(cb_data) = self.prepare_shared(raw, share, force)? {
+            self.dispatch_processed(&cb_data, cb_data.as_bytes(), select, invoke, sink);
+        }
+        Ok(())
+    }
+
+    /// Fait passer `raw` par la validation, les transformations et les filtres de
+    /// `dispatch_shared`, et renvoie les données à transmettre aux callbacks, ou `None` si
+    /// l'appel est ignoré.
+    pub(crate) fn prepare_shared(
+        &self,
+        raw: &[u8],
+        share: impl FnOnce() -> Arc<[u8]>,
+        force: bool,
+    ) -> Result<Option<ArcCallbackPayload>, ValidationError> {
+        if !self.begin_dispatch() || !self.admit_payload_size(raw.len()) || !self.admit_rate(raw) {
+            return Ok(None);
+        }
+        let data = self.transform(self.validate(raw)?)?;
+        self.observe_sequence(&data);
+        if !self.record_change(&data, force)
+            || !self.dedup_dispatch(&data, force)
+            || !self.sample_dispatch()
+        {
+            return Ok(None);
+        }
+        self.record_history(&data);
+        self.record_sticky(&data);
+        let data = match data {
+            Cow::Owned(data) => Arc::from(data),
+            // Sans somme de contrôle ni transformation, les données partagées ne sont pas copiées.
+            Cow::Borrowed(_) if self.checksum.is_none() => share(),
+            Cow::Borrowed(data) => Arc::from(data),
+        };
+        Ok(Some(ArcCallbackPayload::new(data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Octet qui doit ouvrir chaque trame.
+    const MAGIC: u8 = 0x7e;
+
+    /// Crée un registre sur `data` avec un validateur de longueur puis un validateur d'octet
+    /// magique, qui notent leur passage dans `trace`, et un callback qui note son appel.
+    fn validated_registry<'a>(
+        data: &'a [u8],
+        trace: &Rc<RefCell<Vec<&'static str>>>,
+    ) -> CallbackRegistry<'a, CallbackPayload> {
+        let mut registry = CallbackRegistry::with_data(data);
+        let trace_in_validator = Rc::clone(trace);
+        registry.set_validator(move |data: &[u8]| {
+            trace_in_validator.borrow_mut().push("longueur");
+            match data.len() {
+                2..=8 => Ok(()),
+                len => Err(ValidationError::new(format!("longueur {} invalide", len))),
+            }
+        });
+        let trace_in_validator = Rc::clone(trace);
+        registry.set_validator(move |data: &[u8]| {
+            trace_in_validator.borrow_mut().push("magique");
+            if data[0] == MAGIC {
+                Ok(())
+            } else {
+                Err(ValidationError::new("octet magique absent"))
+            }
+        });
+        let trace_in_cb = Rc::clone(trace);
+        registry
+            .set_callback(move |_data: &CallbackPayload| trace_in_cb.borrow_mut().push("callback"));
+        registry
+    }
+
+    /// Teste qu'une trame valide passe les deux validateurs puis atteint le callback.
+    #[test]
+    fn test_valid_frame_reaches_callbacks() {
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let registry = validated_registry(&[MAGIC, 1, 2], &trace);
+
+        assert_eq!(registry.try_dispatch(), Ok(()));
+        assert_eq!(*trace.borrow(), vec!["longueur", "magique", "callback"]);
+    }
+
+    /// Teste qu'une trame trop courte est refusée par le premier validateur, sans appeler le second.
+    #[test]
+    fn test_length_failure_is_fail_fast() {
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let registry = validated_registry(&[MAGIC], &trace);
+
+        assert_eq!(
+            registry.try_dispatch(),
+            Err(ValidationError::new("longueur 1 invalide"))
+        );
+        assert_eq!(*trace.borrow(), vec!["longueur"]);
+    }
+
+    /// Teste qu'un mauvais octet magique est transmis à `on_invalid` lors de `do_something`.
+    #[test]
+    fn test_bad_magic_routes_to_on_invalid() {
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = validated_registry(&[0x00, 1, 2], &trace);
+        let errors_in_handler = Rc::clone(&errors);
+        registry.set_on_invalid(move |error| errors_in_handler.borrow_mut().push(error.clone()));
+
+        registry.do_something();
+
+        assert_eq!(*trace.borrow(), vec!["longueur", "magique"]);
+        assert_eq!(
+            *errors.borrow(),
+            vec![ValidationError::new("octet magique absent")]
+        );
+        assert_eq!(registry.dispatch_collect(), Vec::<()>::new());
+    }
+
+    /// Teste la validation des données partagées et le retrait des validateurs.
+    #[test]
+    fn test_arc_registry_validation_and_clear() {
+        let calls = Rc::new(RefCell::new(0));
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_validator(|data: &[u8]| match data.len() {
+            0 | 1 => Err(ValidationError::new("trop court")),
+            _ => Ok(()),
+        });
+        let calls_in_cb = Rc::clone(&calls);
+        registry.set_callback(move |_data: &ArcCallbackPayload| *calls_in_cb.borrow_mut() += 1);
+
+        assert!(registry.try_dispatch().is_err());
+        assert_eq!(*calls.borrow(), 0);
+
+        registry.clear_validators();
+        registry.do_something();
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    /// Teste qu'une trame correcte est transmise sans sa somme de contrôle, aux validateurs comme
+    /// aux callbacks.
+    #[test]
+    fn test_checksum_is_stripped_before_validators_and_callbacks() {
+        let mut frame = vec![MAGIC, 1, 2];
+        Checksum::Crc32.append(&mut frame);
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = validated_registry(&frame, &trace);
+        registry.verify_checksum(Checksum::Crc32);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_in_cb = Rc::clone(&seen);
+        registry.set_callback(move |data: &CallbackPayload| {
+            seen_in_cb.borrow_mut().extend_from_slice(data.as_bytes())
+        });
+
+        assert_eq!(registry.try_dispatch(), Ok(()));
+        assert_eq!(*seen.borrow(), vec![MAGIC, 1, 2]);
+        assert_eq!(*trace.borrow(), vec!["longueur", "magique", "callback"]);
+    }
+
+    /// Teste qu'une trame corrompue ou trop courte n'atteint ni les validateurs ni les callbacks.
+    #[test]
+    fn test_bad_checksum_skips_callbacks() {
+        let mut frame = vec![MAGIC, 1, 2];
+        Checksum::Additive.append(&mut frame);
+        frame[2] = 3;
+        let trace = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = validated_registry(&frame, &trace);
+        registry.verify_checksum(Checksum::Additive);
+        assert!(registry.try_dispatch().is_err());
+        assert!(trace.borrow().is_empty());
+
+        let mut registry = CallbackRegistry::with_owned_data(vec![1u8, 2]);
+        registry.verify_checksum(Checksum::Crc32);
+        registry.set_callback(|_data: &ArcCallbackPayload| unreachable!());
+        assert!(registry
+            .try_dispatch()
+            .unwrap_err()
+            .reason
+            .contains("trop courte"));
+    }
+}
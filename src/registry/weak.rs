@@ -0,0 +1,112 @@
+//! Callbacks faibles : le callback disparaît avec l'objet qui le possède.
+
+use std::any::Any;
+use std::rc::{Rc, Weak};
+
+use super::{CallbackRegistry, Entry};
+use crate::callback::{Callback, CallbackData, CallbackId};
+
+impl<'a, T: CallbackData + ?Sized + 'static, D: ?Sized, R> CallbackRegistry<'a, T, D, R> {
+    /// Enregistre `f`, appelé avec `owner` et les données tant que `owner` est en vie.
+    ///
+    /// Le registre ne conserve qu'une référence faible vers `owner` : il ne le maintient pas en
+    /// vie. Une fois `owner` détruit, le callback n'est plus appelé ni compté, et son entrée est
+    /// libérée lors de la prochaine modification du registre ou par [`prune_dead`](Self::prune_dead).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry};
+    ///
+    /// struct Widget {
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let widget = Rc::new(Widget { name: "bouton" });
+    /// let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+    /// registry.set_callback_weak(&widget, |widget: &Widget, data: &CallbackPayload| {
+    ///     println!("{} reçoit {:?}", widget.name, data);
+    /// });
+    /// assert_eq!(registry.callback_count(), 1);
+    /// drop(widget);
+    /// assert_eq!(registry.callback_count(), 0);
+    /// ```
+    pub fn set_callback_weak<O: 'static>(
+        &mut self,
+        owner: &Rc<O>,
+        f: impl Fn(&O, &T) -> R + 'static,
+    ) -> CallbackId {
+        let weak = Rc::downgrade(owner);
+        let liveness: Weak<dyn Any> = weak.clone();
+        let cb = Callback::new(move |data: &T| {
+            // L'entrée n'est appelée que si le propriétaire est en vie.
+            let owner = weak
+                .upgrade()
+                .expect("propriétaire d'un callback faible détruit");
+            f(&owner, data)
+        });
+        self.push_entry(|id| Entry::new(id, cb).owned_by(liveness))
+    }
+
+    /// Libère les entrées mortes (propriétaire détruit, garde détruit ou nombre d'appels épuisé)
+    /// et renvoie leur nombre.
+    pub fn prune_dead(&mut self) -> usize {
+        let before = self.callbacks.len();
+        self.prune_spent();
+        before - self.callbacks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CallbackPayload;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+
+    /// Objet observateur qui note les données reçues.
+    struct Widget {
+        seen: RefCell<Vec<u8>>,
+    }
+
+    /// Teste que l'entrée disparaît et que la closure n'est jamais appelée après la mort du propriétaire.
+    #[test]
+    fn test_entry_disappears_with_owner() {
+        let calls = Rc::new(RefCell::new(0));
+        let calls_in_cb = Rc::clone(&calls);
+        let widget = Rc::new(Widget {
+            seen: RefCell::new(Vec::new()),
+        });
+        let mut registry = CallbackRegistry::with_data(&[5u8][..]);
+        registry.set_callback_weak(&widget, move |widget: &Widget, data: &CallbackPayload| {
+            *calls_in_cb.borrow_mut() += 1;
+            widget.seen.borrow_mut().extend_from_slice(data.as_bytes());
+        });
+
+        registry.do_something();
+        assert_eq!(*widget.seen.borrow(), vec![5]);
+        assert_eq!(Rc::strong_count(&widget), 1);
+        drop(widget);
+        registry.do_something();
+
+        assert_eq!(*calls.borrow(), 1);
+        assert_eq!(registry.callback_count(), 0);
+        assert_eq!(registry.prune_dead(), 1);
+        assert_eq!(registry.prune_dead(), 0);
+    }
+
+    /// Teste que `prune_dead` ne retire pas les callbacks vivants.
+    #[test]
+    fn test_prune_dead_keeps_live_callbacks() {
+        let widget = Rc::new(Widget {
+            seen: RefCell::new(Vec::new()),
+        });
+        let mut registry = CallbackRegistry::with_data(&[1u8][..]);
+        registry.set_callback(|_data: &CallbackPayload| {});
+        registry.set_callback_weak(&widget, |_widget: &Widget, _data: &CallbackPayload| {});
+
+        assert_eq!(registry.prune_dead(), 0);
+        assert_eq!(registry.callback_count(), 2);
+    }
+}
@@ -0,0 +1,253 @@
+//! Callbacks fenêtrés, qui reçoivent les dernières données en plus des données actuelles.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use super::CallbackRegistry;
+use crate::callback::{Callback, CallbackId};
+use crate::data::CallbackPayload;
+
+/// File circulaire des copies des dernières données, de la plus ancienne à la plus récente.
+#[derive(Debug, Clone)]
+pub(crate) struct PayloadRing {
+    payloads: VecDeque<Vec<u8>>, // Copies des données, de la plus ancienne à la plus récente.
+    capacity: usize,             // Nombre maximal de copies conservées.
+}
+
+impl PayloadRing {
+    /// Crée une file vide qui conservera au plus `capacity` copies.
+    pub(crate) fn new(capacity: usize) -> Self {
+        PayloadRing {
+            payloads: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Ajoute une copie de `data`, en oubliant la plus ancienne si la file est pleine.
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        if self.capacity == 0 {
+            return;
+        }
+        // Réutilise l'allocation de la copie oubliée.
+        let mut copy = if self.payloads.len() == self.capacity {
+            self.payloads.pop_front().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        copy.clear();
+        copy.extend_from_slice(data);
+        self.payloads.push_back(copy);
+    }
+
+    /// Itère sur les copies, de la plus ancienne à la plus récente.
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &[u8]> + ExactSizeIterator {
+        self.payloads.iter().map(Vec::as_slice)
+    }
+
+    /// Renvoie la copie `index`, la plus ancienne étant la copie 0.
+    pub(crate) fn get(&self, index: usize) -> &[u8] {
+        &self.payloads[index]
+    }
+
+    /// Renvoie le nombre de copies conservées.
+    pub(crate) fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    /// Renvoie le nombre maximal de copies conservées.
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Les dernières données reçues par un callback fenêtré, voir
+/// [`CallbackRegistry::set_windowed_callback`].
+///
+/// Tant que moins de `capacity` appels ont eu lieu, la fenêtre n'est pas pleine et contient
+/// toutes les données reçues depuis l'enregistrement.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowedData<'w> {
+    ring: &'w PayloadRing, // Les copies des dernières données.
+}
+
+impl<'w> WindowedData<'w> {
+    /// Itère sur les données de la fenêtre, de la plus ancienne à la plus récente.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'w [u8]> + ExactSizeIterator {
+        self.ring.iter()
+    }
+
+    /// Renvoie les données de l'appel en cours, les plus récentes de la fenêtre.
+    pub fn latest(&self) -> Option<&'w [u8]> {
+        self.ring.iter().next_back()
+    }
+
+    /// Renvoie le nombre de données dans la fenêtre.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Indique si la fenêtre est vide, ce qui n'arrive pas pendant un appel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Renvoie le nombre maximal de données dans la fenêtre.
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// Indique si la fenêtre contient déjà `capacity` données.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+impl<'a, D: ?Sized, R> CallbackRegistry<'a, CallbackPayload, D, R> {
+    /// Enregistre `f`, qui reçoit à chaque appel les `k` dernières données qui lui ont été
+    /// transmises, celles de l'appel en cours comprises.
+    ///
+    /// Le registre conserve pour ce callback une copie de chacune de ces données ; les appels
+    /// où le callback est désactivé ou filtré n'entrent pas dans sa fenêtre.
+    ///
+    /// `f` peut transmettre de nouvelles données au registre : l'appel imbriqué voit une fenêtre
+    /// qui les contient, tandis que la fenêtre de l'appel en cours reste inchangée.
+    ///
+    /// # Panics
+    ///
+    /// Panique si `k` vaut 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_reven::{CallbackHost, CallbackPayload, OwnedRegistry, WindowedData};
+    ///
+    /// let mut registry: OwnedRegistry<CallbackPayload> = OwnedRegistry::with_owned_data(vec![1]);
+    /// registry.set_windowed_callback(3, |window: &WindowedData| {
+    ///     let sum: u32 = window.iter().map(|data| u32::from(data[0])).sum();
+    ///     println!("moyenne glissante : {}", sum / window.len() as u32);
+    /// });
+    /// registry.do_something();
+    /// ```
+    pub fn set_windowed_callback(
+        &mut self,
+        k: usize,
+        f: impl Fn(&WindowedData<'_>) -> R + 'static,
+    ) -> CallbackId {
+        assert!(
+            k > 0,
+            "la fenêtre d'un callback doit contenir au moins 1 donnée"
+        );
+        let ring = RefCell::new(Rc::new(PayloadRing::new(k)));
+        self.push_callback(Callback::new(move |data: &CallbackPayload| {
+            // La fenêtre n'est copiée que si un appel imbriqué la modifie pendant qu'elle est vue.
+            let window = {
+                let mut ring = ring.borrow_mut();
+                Rc::make_mut(&mut ring).push(data.as_bytes());
+                Rc::clone(&ring)
+            };
+            f(&WindowedData { ring: &window })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::rc::Rc;
+
+    /// Contenu d'une fenêtre vue par un callback : ses données, et si elle était pleine.
+    type Seen = (Vec<u8>, bool);
+
+    /// Crée un registre dont le callback fenêtré de taille `k` note le premier byte de chaque
+    /// donnée de sa fenêtre.
+    fn windowed_registry(
+        k: usize,
+        seen: &Rc<RefCell<Vec<Seen>>>,
+    ) -> CallbackRegistry<'static, CallbackPayload> {
+        let mut registry = CallbackRegistry::with_owned_data(Vec::new());
+        let seen = Rc::clone(seen);
+        registry.set_windowed_callback(k, move |window: &WindowedData| {
+            assert_eq!(window.latest(), window.iter().last());
+            let firsts = window.iter().map(|data| data[0]).collect();
+            seen.borrow_mut().push((firsts, window.is_full()))
+        });
+        registry
+    }
+
+    /// Teste la phase de démarrage puis l'éviction des plus anciennes données, dans l'ordre.
+    #[test]
+    fn test_warm_up_then_eviction_order() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = windowed_registry(3, &seen);
+        for value in 1..=5u8 {
+            registry.set_data(vec![value]);
+            registry.do_something();
+        }
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                (vec![1], false),
+                (vec![1, 2], false),
+                (vec![1, 2, 3], true),
+                (vec![2, 3, 4], true),
+                (vec![3, 4, 5], true),
+            ]
+        );
+    }
+
+    /// Teste qu'une fenêtre de taille 1 ne contient que les données actuelles, de longueur variable.
+    #[test]
+    fn test_window_of_one_keeps_latest_copy() {
+        let latest = Rc::new(RefCell::new(Vec::new()));
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8, 2, 3]);
+        let latest_in_cb = Rc::clone(&latest);
+        registry.set_windowed_callback(1, move |window: &WindowedData| {
+            assert_eq!(window.len(), 1);
+            *latest_in_cb.borrow_mut() = window.latest().unwrap().to_vec();
+        });
+
+        registry.do_something();
+        registry.set_data(vec![9]);
+        registry.do_something();
+
+        assert_eq!(*latest.borrow(), vec![9]);
+    }
+
+    /// Teste qu'un callback fenêtré peut transmettre de nouvelles données au registre pendant son
+    /// appel, sans modifier la fenêtre qu'il est en train de lire.
+    #[test]
+    fn test_reentrant_dispatch_keeps_window() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let registry: Rc<RefCell<Option<CallbackRegistry<'static, CallbackPayload>>>> =
+            Rc::default();
+        let mut inner = CallbackRegistry::with_owned_data(Vec::new());
+        let (seen_in_cb, registry_in_cb) = (Rc::clone(&seen), Rc::downgrade(&registry));
+        inner.set_windowed_callback(3, move |window: &WindowedData| {
+            let before: Vec<u8> = window.iter().map(|data| data[0]).collect();
+            if window.latest() == Some(&[1][..]) {
+                let registry = registry_in_cb.upgrade().unwrap();
+                let registry = registry.borrow();
+                registry.as_ref().unwrap().dispatch(&[2]).unwrap();
+            }
+            let after: Vec<u8> = window.iter().map(|data| data[0]).collect();
+            assert_eq!(before, after);
+            seen_in_cb.borrow_mut().push(after);
+        });
+        *registry.borrow_mut() = Some(inner);
+
+        registry.borrow().as_ref().unwrap().dispatch(&[1]).unwrap();
+        assert_eq!(*seen.borrow(), vec![vec![1, 2], vec![1]]);
+    }
+
+    /// Teste qu'une fenêtre vide est refusée.
+    #[test]
+    #[should_panic(expected = "au moins 1")]
+    fn test_zero_window_panics() {
+        let mut registry: CallbackRegistry<'static, CallbackPayload> =
+            CallbackRegistry::with_owned_data(vec![1u8]);
+        registry.set_windowed_callback(0, |_window: &WindowedData| {});
+    }
+}
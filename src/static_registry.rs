@@ -0,0 +1,234 @@
+//! Registre statique : les callbacks sont stockés par leur type concret, sans `Box<dyn Fn>`.
+
+use crate::callback::CallbackData;
+use crate::data::{ArcCallbackPayload, CallbackPayload};
+use crate::registry::{ignore_result, CallbackRegistry};
+
+/// Interface commune à [`CallbackRegistry`] et [`StaticRegistry`], pour écrire du code générique
+/// sur l'un ou l'autre registre.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackHost, CallbackPayload, CallbackRegistry, Dispatch, StaticRegistry};
+///
+/// fn run(registry: &impl Dispatch) -> usize {
+///     registry.invoke_all();
+///     registry.active_count()
+/// }
+///
+/// let data = [1u8, 2, 3];
+/// let mut dynamic = CallbackRegistry::with_data(&data[..]);
+/// dynamic.set_callback(|data: &CallbackPayload| println!("{:?}", data));
+/// let fixed = StaticRegistry::new(&data[..], (|data: &CallbackPayload| println!("{:?}", data),));
+/// assert_eq!(run(&dynamic), run(&fixed));
+/// ```
+pub trait Dispatch {
+    /// Appelle chaque callback actif avec les données du registre, comme `do_something`.
+    fn invoke_all(&self);
+    /// Renvoie le nombre de callbacks que `invoke_all` appellera.
+    fn active_count(&self) -> usize;
+}
+
+/// Liste de callbacks de types concrets, implémentée pour `()` et les tuples de 1 à 8 closures
+/// `Fn(&T)`.
+pub trait CallbackList<T: CallbackData + ?Sized> {
+    /// Nombre de callbacks de la liste.
+    const LEN: usize;
+
+    /// Appelle chaque callback avec `data` dans l'ordre du tuple.
+    fn call_each(&self, data: &T);
+}
+
+impl<T: CallbackData + ?Sized> CallbackList<T> for () {
+    const LEN: usize = 0;
+
+    fn call_each(&self, _data: &T) {}
+}
+
+/// Implémente `CallbackList` pour un tuple dont chaque élément est une closure `Fn(&T)`.
+macro_rules! impl_callback_list {
+    ($len:expr; $($f:ident . $index:tt),+) => {
+        impl<T: CallbackData + ?Sized, $($f: Fn(&T)),+> CallbackList<T> for ($($f,)+) {
+            const LEN: usize = $len;
+
+            #[inline]
+            fn call_each(&self, data: &T) {
+                $(
+                    (self.$index)(data);
+                )+
+            }
+        }
+    };
+}
+
+impl_callback_list!(1; A.0);
+impl_callback_list!(2; A.0, B.1);
+impl_callback_list!(3; A.0, B.1, C.2);
+impl_callback_list!(4; A.0, B.1, C.2, D.3);
+impl_callback_list!(5; A.0, B.1, C.2, D.3, E.4);
+impl_callback_list!(6; A.0, B.1, C.2, D.3, E.4, F.5);
+impl_callback_list!(7; A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_callback_list!(8; A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+
+/// Registre dont les callbacks, fixés à la construction, sont un tuple de closures.
+///
+/// Chaque appel est résolu à la compilation et peut être inliné, contrairement au
+/// `Box<dyn Fn>` d'un [`Callback`](crate::Callback). En contrepartie, les callbacks ne peuvent
+/// être ni ajoutés ni retirés après la construction. `do_something` appelle les callbacks dans
+/// l'ordre du tuple, sans autre traitement des données.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::{CallbackPayload, StaticRegistry};
+///
+/// let data = [1u8, 2, 3];
+/// let registry = StaticRegistry::new(
+///     &data[..],
+///     (
+///         |data: &CallbackPayload| println!("Premier : {:?}", data),
+///         |data: &CallbackPayload| println!("Second : {:?}", data),
+///     ),
+/// );
+/// assert_eq!(registry.callback_count(), 2);
+/// registry.do_something();
+/// ```
+pub struct StaticRegistry<'a, C, D: ?Sized = [u8]> {
+    callbacks: C, // Les callbacks, dans l'ordre d'appel.
+    data: &'a D,  // Les données empruntées, vues comme un slice de bytes.
+}
+
+impl<'a, C, D: AsRef<[u8]> + ?Sized> StaticRegistry<'a, C, D>
+where
+    C: CallbackList<CallbackPayload>,
+{
+    /// Crée un registre qui appellera `callbacks` avec les données empruntées `data`.
+    pub fn new(data: &'a D, callbacks: C) -> Self {
+        StaticRegistry { callbacks, data }
+    }
+
+    /// Renvoie le nombre de callbacks du registre.
+    pub fn callback_count(&self) -> usize {
+        C::LEN
+    }
+
+    /// Appelle chaque callback avec les données du registre, dans l'ordre du tuple.
+    #[inline]
+    pub fn do_something(&self) {
+        let cb_data = CallbackPayload::new(self.data.as_ref());
+        self.callbacks.call_each(cb_data);
+    }
+
+    /// Renvoie les données du registre.
+    pub fn data(&self) -> &D {
+        self.data
+    }
+}
+
+impl<'a, C, D: AsRef<[u8]> + ?Sized> Dispatch for StaticRegistry<'a, C, D>
+where
+    C: CallbackList<CallbackPayload>,
+{
+    fn invoke_all(&self) {
+        self.do_something();
+    }
+
+    fn active_count(&self) -> usize {
+        self.callback_count()
+    }
+}
+
+impl<'a, D: AsRef<[u8]> + ?Sized, R> Dispatch for CallbackRegistry<'a, CallbackPayload, D, R> {
+    fn invoke_all(&self) {
+        self.dispatch_where(|_| true, ignore_result);
+    }
+
+    fn active_count(&self) -> usize {
+        self.active_entries().count()
+    }
+}
+
+impl<'a, R> Dispatch for CallbackRegistry<'a, ArcCallbackPayload, [u8], R> {
+    fn invoke_all(&self) {
+        self.dispatch_where(|_| true, ignore_result);
+    }
+
+    fn active_count(&self) -> usize {
+        self.active_entries().count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::CallbackHost;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Log = Rc<RefCell<Vec<(u8, usize)>>>;
+
+    /// Renvoie un callback qui note `tag` et la longueur des données dans `log`.
+    fn recorder(log: &Log, tag: u8) -> impl Fn(&CallbackPayload) {
+        let log = Rc::clone(log);
+        move |data: &CallbackPayload| log.borrow_mut().push((tag, data.as_bytes().len()))
+    }
+
+    /// Appelle deux fois le registre via `Dispatch`, pour du code générique sur les deux registres.
+    fn run_twice(registry: &impl Dispatch) -> usize {
+        registry.invoke_all();
+        registry.invoke_all();
+        registry.active_count()
+    }
+
+    /// Teste que les registres statique et dynamique appellent les mêmes callbacks dans le même ordre.
+    #[test]
+    fn test_static_and_dynamic_registries_match() {
+        let data = [7u8, 8, 9];
+        let static_log = Log::default();
+        let fixed = StaticRegistry::new(
+            &data[..],
+            (
+                recorder(&static_log, 1),
+                recorder(&static_log, 2),
+                recorder(&static_log, 3),
+            ),
+        );
+        let dynamic_log = Log::default();
+        let mut dynamic = CallbackRegistry::with_data(&data[..]);
+        for tag in 1..=3 {
+            dynamic.set_callback(recorder(&dynamic_log, tag));
+        }
+
+        assert_eq!(run_twice(&fixed), run_twice(&dynamic));
+        assert_eq!(*static_log.borrow(), *dynamic_log.borrow());
+        assert_eq!(static_log.borrow().len(), 6);
+    }
+
+    /// Teste qu'un registre statique accepte jusqu'à huit callbacks, et aucun.
+    #[test]
+    fn test_static_registry_arity() {
+        let data = [1u8];
+        let log = Log::default();
+        let full = StaticRegistry::new(
+            &data[..],
+            (
+                recorder(&log, 0),
+                recorder(&log, 1),
+                recorder(&log, 2),
+                recorder(&log, 3),
+                recorder(&log, 4),
+                recorder(&log, 5),
+                recorder(&log, 6),
+                recorder(&log, 7),
+            ),
+        );
+        full.do_something();
+        let tags: Vec<u8> = log.borrow().iter().map(|(tag, _)| *tag).collect();
+        assert_eq!(tags, (0..8).collect::<Vec<u8>>());
+
+        let empty = StaticRegistry::new(&data[..], ());
+        empty.do_something();
+        assert_eq!(empty.callback_count(), 0);
+    }
+}
@@ -0,0 +1,147 @@
+//! Registre d'événements typés : les callbacks sont rangés par type d'événement.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::callback::{CallbackId, CallbackIdGenerator};
+
+/// Callback dont le type d'événement a été effacé ; il n'est appelé qu'avec ce type.
+type ErasedCallback = Box<dyn Fn(&dyn Any)>;
+
+/// Registre qui range ses callbacks par type d'événement, dans une table indexée par `TypeId`.
+///
+/// Contrairement à [`set_callback_typed`](crate::CallbackRegistry::set_callback_typed), qui
+/// appelle le prédicat de chaque callback à chaque événement, [`emit`](Self::emit) ne parcourt
+/// que les callbacks du type émis : son coût ne dépend pas du nombre de callbacks enregistrés
+/// pour d'autres types, seulement d'une recherche dans la table.
+///
+/// # Examples
+///
+/// ```
+/// use rust_reven::TypedRegistry;
+///
+/// struct Connected(u32);
+/// struct Disconnected(u32);
+///
+/// let mut registry = TypedRegistry::new();
+/// registry.on(|event: &Connected| println!("connexion {}", event.0));
+/// registry.on(|event: &Disconnected| println!("déconnexion {}", event.0));
+/// registry.emit(&Connected(1));
+/// assert_eq!(registry.handler_count::<Connected>(), 1);
+/// ```
+#[derive(Default)]
+pub struct TypedRegistry {
+    handlers: HashMap<TypeId, Vec<(CallbackId, ErasedCallback)>>, // Callbacks par type, dans l'ordre d'enregistrement.
+    ids: CallbackIdGenerator, // Générateur des identifiants renvoyés par `on`.
+}
+
+impl TypedRegistry {
+    /// Crée un registre sans callback.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre `f`, appelé par chaque [`emit`](Self::emit) d'un événement de type `E`.
+    pub fn on<E: Any>(&mut self, f: impl Fn(&E) + 'static) -> CallbackId {
+        let id = self.ids.next_id();
+        let erased: ErasedCallback = Box::new(move |event: &dyn Any| {
+            // La table garantit que seuls les événements de type `E` arrivent ici.
+            if let Some(event) = event.downcast_ref::<E>() {
+                f(event);
+            }
+        });
+        self.handlers
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push((id, erased));
+        id
+    }
+
+    /// Retire le callback `id`, quel que soit son type d'événement, et indique s'il était enregistré.
+    pub fn off(&mut self, id: CallbackId) -> bool {
+        for handlers in self.handlers.values_mut() {
+            if let Some(index) = handlers.iter().position(|(other, _)| *other == id) {
+                drop(handlers.remove(index));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Appelle, dans leur ordre d'enregistrement, les callbacks enregistrés pour le type `E`.
+    pub fn emit<E: Any>(&self, event: &E) {
+        if let Some(handlers) = self.handlers.get(&TypeId::of::<E>()) {
+            for (_, handler) in handlers {
+                handler(event);
+            }
+        }
+    }
+
+    /// Renvoie le nombre de callbacks enregistrés pour le type `E`.
+    pub fn handler_count<E: Any>(&self) -> usize {
+        self.handlers.get(&TypeId::of::<E>()).map_or(0, Vec::len)
+    }
+}
+
+/// Affiche le nombre de types et de callbacks enregistrés.
+impl fmt::Debug for TypedRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedRegistry")
+            .field("event_types", &self.handlers.len())
+            .field(
+                "handlers",
+                &self.handlers.values().map(Vec::len).sum::<usize>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct Pressed(char);
+    struct Moved(i32);
+
+    /// Teste qu'un callback du type A n'est jamais appelé lors de l'émission d'un événement B.
+    #[test]
+    fn test_emit_only_touches_handlers_of_the_type() {
+        let pressed_calls = Rc::new(Cell::new(0));
+        let moved_total = Rc::new(Cell::new(0));
+        let mut registry = TypedRegistry::new();
+        let calls = Rc::clone(&pressed_calls);
+        registry.on(move |event: &Pressed| {
+            assert_eq!(event.0, 'a');
+            calls.set(calls.get() + 1)
+        });
+        for _ in 0..3 {
+            let total = Rc::clone(&moved_total);
+            registry.on(move |event: &Moved| total.set(total.get() + event.0));
+        }
+
+        registry.emit(&Moved(2));
+        registry.emit(&Moved(5));
+        assert_eq!(pressed_calls.get(), 0);
+        assert_eq!(moved_total.get(), 21);
+
+        registry.emit(&Pressed('a'));
+        registry.emit(&"sans callback");
+        assert_eq!(pressed_calls.get(), 1);
+    }
+
+    /// Teste que `off` retire un callback sans toucher aux autres types.
+    #[test]
+    fn test_off_removes_handler() {
+        let mut registry = TypedRegistry::new();
+        let id = registry.on(|_event: &Pressed| {});
+        registry.on(|_event: &Moved| {});
+
+        assert!(registry.off(id));
+        assert!(!registry.off(id));
+        assert_eq!(registry.handler_count::<Pressed>(), 0);
+        assert_eq!(registry.handler_count::<Moved>(), 1);
+    }
+}
@@ -0,0 +1,61 @@
+//! Vérifie qu'un callback créé à partir d'un pointeur de fonction n'alloue rien.
+
+use rust_reven::{Callback, CallbackPayload};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Allocateur qui compte les allocations du thread courant.
+struct CountingAllocator;
+
+thread_local! {
+    // Nombre d'allocations effectuées par le thread courant.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        // SAFETY: la requête est transmise telle quelle à l'allocateur système.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` a été alloué par `System` via `alloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Renvoie le nombre d'allocations effectuées par `f` sur le thread courant.
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Fonction libre enregistrée comme pointeur de fonction.
+fn log(data: &CallbackPayload) {
+    assert_eq!(data.as_bytes(), &[1, 2, 3]);
+}
+
+/// Teste que `from_ptr` n'alloue rien, contrairement à une closure qui capture un état.
+#[test]
+fn test_fn_ptr_callback_allocates_nothing() {
+    let payload = CallbackPayload::new(&[1, 2, 3]);
+
+    let fn_ptr = allocations_during(|| {
+        let callback = Callback::from_ptr(log);
+        callback.invoke(payload);
+    });
+    let captured = vec![1u8, 2, 3];
+    let boxed = allocations_during(move || {
+        let callback =
+            Callback::new(move |data: &CallbackPayload| assert_eq!(data.as_bytes(), captured));
+        callback.invoke(payload);
+    });
+
+    assert_eq!(fn_ptr, 0);
+    assert_eq!(boxed, 1);
+}
@@ -0,0 +1,39 @@
+//! Vérifie que le prélude suffit à écrire l'exemple du README.
+
+use rust_reven::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Teste l'exemple du README en n'important que le prélude.
+#[test]
+fn test_readme_example_with_prelude_only() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_in_cb = Rc::clone(&seen);
+
+    let mut registry = CallbackRegistry::builder()
+        .with_data(&[1, 2, 3])
+        .build()
+        .unwrap();
+    registry.set_callback(Callback::new(move |data: &CallbackPayload| {
+        seen_in_cb.borrow_mut().extend_from_slice(data.as_bytes())
+    }));
+    registry.do_something();
+
+    assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+}
+
+/// Teste que les variantes de registre et de données sont accessibles depuis le prélude.
+#[test]
+fn test_prelude_exposes_registry_variants() {
+    let mut owned: OwnedRegistry<CallbackPayload> = CallbackRegistry::with_owned_data(vec![1]);
+    owned.set_data(vec![2]);
+    owned.do_something();
+
+    let frame = [0u8; 8];
+    let fixed: FixedRegistry<CallbackPayload, 8> = CallbackRegistry::with_data(&frame);
+    fixed.do_something();
+
+    let cow: CowCallbackPayload = CallbackPayload::borrowed(&frame);
+    let buf: CallbackPayloadBuf = cow.into_owned();
+    assert_eq!(buf.as_bytes().len(), 8);
+}